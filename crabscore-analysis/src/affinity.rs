@@ -0,0 +1,117 @@
+//! CPU pinning of benchmarked processes (Linux only).
+//!
+//! A pinned process is spawned while the spawning thread's affinity is
+//! restricted to one core with `sched_setaffinity(2)`; the child inherits
+//! that mask across `fork` and `exec`, so every run stays on the core instead
+//! of being migrated by the scheduler, with no wrapper process in between.
+//! The thread's own mask is restored right after the spawn.
+
+use anyhow::Result;
+use crabscore_core::error::CrabScoreError;
+use tokio::process::{Child, Command};
+
+/// Check that benchmarked processes can be pinned to core `cpu`.
+///
+/// Returns [`CrabScoreError::UnsupportedError`] outside Linux, or when the
+/// core does not exist or is not available to this process.
+pub async fn check_pinning(cpu: usize) -> Result<()> {
+    imp::check(cpu)
+}
+
+/// Spawn `command`, on core `cpu` when it is set
+pub(crate) fn spawn(command: &mut Command, cpu: Option<usize>) -> std::io::Result<Child> {
+    match cpu {
+        Some(cpu) => imp::spawn_pinned(command, cpu),
+        None => command.spawn(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::*;
+    use rustix::thread::{sched_getaffinity, sched_setaffinity, CpuSet};
+
+    pub(super) fn check(cpu: usize) -> Result<()> {
+        if cpu >= CpuSet::MAX_CPU {
+            return Err(CrabScoreError::unsupported(format!("there is no CPU {cpu}")).into());
+        }
+        let allowed = sched_getaffinity(None).map_err(std::io::Error::from)?;
+        if !allowed.is_set(cpu) {
+            return Err(CrabScoreError::unsupported(format!(
+                "CPU {cpu} does not exist or is not available to this process"
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    pub(super) fn spawn_pinned(command: &mut Command, cpu: usize) -> std::io::Result<Child> {
+        if cpu >= CpuSet::MAX_CPU {
+            return Err(std::io::Error::other(format!("there is no CPU {cpu}")));
+        }
+        let mut pinned = CpuSet::new();
+        pinned.set(cpu);
+        // Nothing else runs on this thread between the two calls: there is
+        // no await point, so no other task can be scheduled onto it
+        let previous = sched_getaffinity(None)?;
+        sched_setaffinity(None, &pinned)?;
+        let child = command.spawn();
+        sched_setaffinity(None, &previous)?;
+        child
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::*;
+
+    pub(super) fn check(_cpu: usize) -> Result<()> {
+        Err(CrabScoreError::unsupported("CPU pinning requires Linux").into())
+    }
+
+    pub(super) fn spawn_pinned(_command: &mut Command, _cpu: usize) -> std::io::Result<Child> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "CPU pinning requires Linux",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{BenchmarkOptions, BenchmarkRunner};
+
+    #[tokio::test]
+    async fn pins_benchmarked_runs_to_one_core() {
+        assert!(check_pinning(usize::MAX).await.is_err());
+        if let Err(e) = check_pinning(0).await {
+            // No Linux or core 0 is not ours
+            assert!(e.downcast_ref::<CrabScoreError>().is_some(), "{e:#}");
+            return;
+        }
+
+        // Succeeds only when the shell runs on core 0 alone
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 0,
+            iterations: 2,
+            args: vec![
+                "-c".into(),
+                "grep -q '^Cpus_allowed_list:[[:space:]]*0$' /proc/self/status".into(),
+            ],
+            pin_cpu: Some(0),
+            ..Default::default()
+        });
+        let before = std::fs::read_to_string("/proc/thread-self/status").unwrap();
+        let perf = runner.benchmark("/bin/sh").await.unwrap();
+        assert!(perf.latency.p50_ms > 0.0);
+        // The spawning thread got its own mask back
+        assert_eq!(
+            std::fs::read_to_string("/proc/thread-self/status")
+                .unwrap()
+                .lines()
+                .find(|l| l.starts_with("Cpus_allowed_list")),
+            before.lines().find(|l| l.starts_with("Cpus_allowed_list"))
+        );
+    }
+}
@@ -3,7 +3,22 @@
 use anyhow::Result;
 use crabscore_core::metrics::SafetyMetrics;
 
+use crate::safety::{AnalysisOptions, FunctionComplexity};
+
 /// Run full static analysis on given path (proxy to safety::analyse_project).
 pub fn run(path: &str) -> Result<SafetyMetrics> {
     super::safety::analyse_project(path)
 }
+
+/// Run full static analysis with explicit options.
+pub fn run_with(path: &str, opts: &AnalysisOptions) -> Result<SafetyMetrics> {
+    super::safety::analyse_project_with(path, opts)
+}
+
+/// Run full static analysis, also ranking functions by complexity (most complex first).
+pub fn run_ranked(
+    path: &str,
+    opts: &AnalysisOptions,
+) -> Result<(SafetyMetrics, Vec<FunctionComplexity>)> {
+    super::safety::analyse_project_functions(path, opts)
+}
@@ -0,0 +1,111 @@
+//! Machine speed calibration for portable latency scores.
+//!
+//! A fixed integer hash loop is timed on the current machine and compared
+//! against its duration on a reference machine. The loop only uses 64-bit
+//! wrapping arithmetic, so it does the same work regardless of platform or
+//! endianness. Latencies multiplied by the resulting speed factor are roughly
+//! what the reference machine would have measured.
+
+use crabscore_core::metrics::{LatencyMetrics, PerformanceMetrics};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Iterations of the reference workload
+const REFERENCE_ROUNDS: u64 = 20_000_000;
+
+/// Duration of the reference workload on the reference machine: one 3 GHz
+/// x86-64 core running a release build.
+///
+/// Each round is a dependent xor and 64-bit multiply, about 4 cycles of
+/// latency, so [`REFERENCE_ROUNDS`] take about 80 million cycles or 27 ms,
+/// rounded to 30 ms. A debug build of crabscore runs the loop several times
+/// slower, which [`speed_factor`] can only bound by its clamp.
+pub const REFERENCE_DURATION: Duration = Duration::from_millis(30);
+
+/// Bounds of the speed factor. Real machines stay well within them; beyond
+/// them the timing says more about the build (e.g. unoptimized) or a
+/// contended machine than about its speed.
+pub const SPEED_FACTOR_RANGE: (f64, f64) = (0.25, 4.0);
+
+/// Timed runs of the workload; the fastest one counts
+const RUNS: usize = 5;
+
+/// FNV-1a style hash over the round counter
+fn reference_workload(rounds: u64) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for i in 0..rounds {
+        hash ^= black_box(i);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Speed of this machine relative to the reference machine.
+///
+/// Above 1.0 the machine is faster than the reference; measured latencies are
+/// multiplied by the factor to normalize them. The factor is clamped to
+/// [`SPEED_FACTOR_RANGE`]. Blocks for a few hundred milliseconds at most.
+pub fn speed_factor() -> f64 {
+    if cfg!(debug_assertions) {
+        tracing::warn!("Calibrating with a debug build - the speed factor is underestimated");
+    }
+    let fastest = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(reference_workload(black_box(REFERENCE_ROUNDS)));
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(REFERENCE_DURATION);
+    let (min, max) = SPEED_FACTOR_RANGE;
+    (REFERENCE_DURATION.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON)).clamp(min, max)
+}
+
+/// Scale latencies by `factor` as returned by [`speed_factor`]
+pub fn normalize_latency(latency: &mut LatencyMetrics, factor: f64) {
+    for ms in [
+        &mut latency.p50_ms,
+        &mut latency.p95_ms,
+        &mut latency.p99_ms,
+        &mut latency.p999_ms,
+        &mut latency.stddev_ms,
+        &mut latency.cold_start_ms,
+        &mut latency.ttfb_ms,
+    ] {
+        *ms *= factor;
+    }
+}
+
+/// Scale latencies and throughput to what the reference machine would have
+/// measured, given this machine's `factor`
+pub fn normalize_performance(perf: &mut PerformanceMetrics, factor: f64) {
+    normalize_latency(&mut perf.latency, factor);
+    perf.throughput.requests_per_second /= factor;
+    perf.throughput.mb_per_second /= factor;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workload_is_deterministic() {
+        assert_eq!(reference_workload(3), reference_workload(3));
+        assert_ne!(reference_workload(3), reference_workload(4));
+        let factor = speed_factor();
+        let (min, max) = SPEED_FACTOR_RANGE;
+        assert!((min..=max).contains(&factor), "{factor}");
+    }
+
+    #[test]
+    fn fast_machines_are_scaled_up_to_the_reference() {
+        let mut perf = PerformanceMetrics::default();
+        perf.latency.p50_ms = 10.0;
+        perf.latency.p99_ms = 20.0;
+        perf.throughput.requests_per_second = 100.0;
+        normalize_performance(&mut perf, 2.0);
+        assert_eq!(perf.latency.p50_ms, 20.0);
+        assert_eq!(perf.latency.p99_ms, 40.0);
+        assert_eq!(perf.throughput.requests_per_second, 50.0);
+    }
+}
@@ -0,0 +1,307 @@
+//! In-process benchmark results from Criterion.
+//!
+//! Library crates have no binary to time, and timing a binary measures
+//! process start-up rather than the hot path. This module runs `cargo bench`
+//! and reads the per-benchmark files Criterion leaves under
+//! `<target dir>/criterion/<id>/new/`:
+//!  * `sample.json` – per-sample iteration counts and total times (ns)
+//!  * `estimates.json` – median / std-dev point estimates (ns)
+//!  * `benchmark.json` – the benchmark's full id
+
+use anyhow::{Context, Result};
+use crabscore_core::metrics::{
+    LatencyMetrics, PerformanceMetrics, ResourceMetrics, ScalabilityMetrics, ThroughputMetrics,
+};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use walkdir::WalkDir;
+
+use crate::metrics::{percentile, stddev};
+
+/// Result of one Criterion benchmark.
+#[derive(Debug, Clone)]
+pub struct CriterionBenchmark {
+    /// Full benchmark id, e.g. `parse/small`
+    pub name: String,
+    /// Latency/throughput derived from the benchmark's samples
+    pub performance: PerformanceMetrics,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    median: Estimate,
+    std_dev: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Sample {
+    iters: Vec<f64>,
+    times: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+struct BenchmarkId {
+    full_id: String,
+}
+
+/// The parts of a `Cargo.toml` that declare benchmarks
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    bench: Vec<toml::Value>,
+    package: Option<Package>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Package {
+    autobenches: Option<bool>,
+}
+
+/// Whether the Cargo project at `project` declares any benchmarks: a
+/// `[[bench]]` target, or a benchmark under `benches/` that Cargo discovers
+/// on its own (unless `autobenches = false`).
+pub fn has_benches(project: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(project.join("Cargo.toml")) else {
+        return false;
+    };
+    let Ok(manifest) = toml::from_str::<Manifest>(&content) else {
+        return false;
+    };
+    let autobenches = manifest.package.and_then(|p| p.autobenches) != Some(false);
+    !manifest.bench.is_empty() || (autobenches && has_bench_sources(&project.join("benches")))
+}
+
+/// Whether `benches` holds `*.rs` files or `*/main.rs` benchmarks
+fn has_bench_sources(benches: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(benches) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            path.join("main.rs").is_file()
+        } else {
+            path.extension().is_some_and(|ext| ext == "rs")
+        }
+    })
+}
+
+/// Run `cargo bench` in `project`, then collect the Criterion results from
+/// its `target_dir`.
+///
+/// Returns an empty list when the project has no benchmarks or the run
+/// produced no Criterion output, so callers can fall back to other modes.
+pub async fn run_criterion(project: &Path, target_dir: &Path) -> Result<Vec<CriterionBenchmark>> {
+    if !has_benches(project) {
+        tracing::warn!("{} has no benchmarks to run", project.display());
+        return Ok(Vec::new());
+    }
+    // Criterion's console report would end up in our stdout (and break
+    // `--format json`); the results are read from its JSON files instead.
+    let status = Command::new("cargo")
+        .arg("bench")
+        .current_dir(project)
+        .stdout(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .status()
+        .await
+        .context("failed to run cargo bench")?;
+    if !status.success() {
+        tracing::warn!("cargo bench failed in {}", project.display());
+        return Ok(Vec::new());
+    }
+    match criterion_dir(target_dir) {
+        Some(dir) => read_criterion_results(&dir),
+        None => {
+            tracing::warn!("cargo bench produced no Criterion output");
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Criterion's output directory inside a Cargo `target_dir`, if it exists
+pub fn criterion_dir(target_dir: &Path) -> Option<PathBuf> {
+    Some(target_dir.join("criterion")).filter(|dir| dir.is_dir())
+}
+
+/// Read every benchmark stored under a Criterion output directory.
+pub fn read_criterion_results(dir: &Path) -> Result<Vec<CriterionBenchmark>> {
+    let mut benches = Vec::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry?;
+        let path = entry.path();
+        let in_new = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n == "new");
+        if in_new && entry.file_name() == "estimates.json" {
+            let run_dir = path.parent().unwrap();
+            benches.push(
+                read_benchmark(run_dir)
+                    .with_context(|| format!("invalid Criterion data in {}", run_dir.display()))?,
+            );
+        }
+    }
+    Ok(benches)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn read_benchmark(run_dir: &Path) -> Result<CriterionBenchmark> {
+    let estimates: Estimates = read_json(&run_dir.join("estimates.json"))?;
+    let name = read_json::<BenchmarkId>(&run_dir.join("benchmark.json"))
+        .map(|b| b.full_id)
+        .unwrap_or_else(|_| {
+            run_dir
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+    // Per-iteration times in ms; fall back to the median estimate alone.
+    let mut per_iter: Vec<f64> = read_json::<Sample>(&run_dir.join("sample.json"))
+        .map(|s| {
+            s.times
+                .iter()
+                .zip(&s.iters)
+                .filter(|(_, iters)| **iters > 0.0)
+                .map(|(time, iters)| time / iters / 1e6)
+                .collect()
+        })
+        .unwrap_or_default();
+    if per_iter.is_empty() {
+        per_iter.push(estimates.median.point_estimate / 1e6);
+    }
+    per_iter.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let median_ms = estimates.median.point_estimate / 1e6;
+    let latency = LatencyMetrics {
+        p50_ms: median_ms,
+        p95_ms: percentile(&per_iter, 0.95),
+        p99_ms: percentile(&per_iter, 0.99),
+        p999_ms: percentile(&per_iter, 0.999),
+        stddev_ms: if per_iter.len() > 1 {
+            stddev(&per_iter)
+        } else {
+            estimates.std_dev.point_estimate / 1e6
+        },
+        // Criterion collects 100 samples by default
+        reliable_percentiles: per_iter.len() >= 20,
+        cold_start_ms: 0.0,
+        ttfb_ms: 0.0,
+    };
+
+    Ok(CriterionBenchmark {
+        name,
+        performance: PerformanceMetrics {
+            throughput: ThroughputMetrics {
+                requests_per_second: if median_ms > 0.0 {
+                    1000.0 / median_ms
+                } else {
+                    0.0
+                },
+                ..Default::default()
+            },
+            latency,
+            resource_usage: ResourceMetrics::default(),
+            scalability: ScalabilityMetrics::default(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_run(root: &Path, id: &str, median_ns: f64, times: &[f64]) {
+        let dir = root.join(id.replace('/', "_")).join("new");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("estimates.json"),
+            format!(
+                r#"{{"mean":{{"point_estimate":{median_ns}}},"median":{{"point_estimate":{median_ns}}},"std_dev":{{"point_estimate":10.0}}}}"#
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("benchmark.json"),
+            format!(r#"{{"group_id":"g","full_id":"{id}"}}"#),
+        )
+        .unwrap();
+        let iters = vec![10.0; times.len()];
+        std::fs::write(
+            dir.join("sample.json"),
+            serde_json::json!({ "sampling_mode": "Linear", "iters": iters, "times": times })
+                .to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn maps_criterion_runs_to_latency() {
+        let tmp = tempfile::tempdir().unwrap();
+        // 10 iterations per sample: 1, 2, 3, 4 ms per iteration
+        write_run(
+            tmp.path(),
+            "parse/small",
+            2_500_000.0,
+            &[1e7, 2e7, 3e7, 4e7],
+        );
+        write_run(tmp.path(), "render", 1_000_000.0, &[1e7]);
+        // Criterion's own report directory must be ignored
+        std::fs::create_dir_all(tmp.path().join("report")).unwrap();
+
+        let benches = read_criterion_results(tmp.path()).unwrap();
+        let names: Vec<_> = benches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["parse/small", "render"]);
+
+        let parse = &benches[0].performance;
+        assert!((parse.latency.p50_ms - 2.5).abs() < 1e-9);
+        assert!((parse.latency.p99_ms - 3.97).abs() < 1e-9);
+        assert!(!parse.latency.reliable_percentiles);
+        assert!((parse.throughput.requests_per_second - 400.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn projects_without_benches_are_detected() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        assert!(!has_benches(tmp.path()));
+        std::fs::create_dir(tmp.path().join("benches")).unwrap();
+        assert!(!has_benches(tmp.path()));
+        std::fs::write(tmp.path().join("benches/parse.rs"), "fn main() {}\n").unwrap();
+        assert!(has_benches(tmp.path()));
+
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"x\"\nautobenches = false\n",
+        )
+        .unwrap();
+        assert!(!has_benches(tmp.path()));
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"x\"\nautobenches = false\n\n[[bench]]\nname = \"parse\"\n",
+        )
+        .unwrap();
+        assert!(has_benches(tmp.path()));
+
+        // A mention of [[bench]] outside a table header declares nothing
+        std::fs::remove_dir_all(tmp.path().join("benches")).unwrap();
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"x\"\ndescription = \"no [[bench]] here\"\n",
+        )
+        .unwrap();
+        assert!(!has_benches(tmp.path()));
+    }
+}
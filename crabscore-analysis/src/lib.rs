@@ -3,7 +3,18 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+/// CPU pinning of benchmarked processes
+pub mod affinity;
 pub mod analysis;
+/// Machine speed calibration for portable latency scores
+pub mod calibration;
+/// Criterion benchmark integration
+pub mod criterion;
 /// Code metrics collection
 pub mod metrics;
+/// Network egress measurement by syscall tracing
+#[cfg(target_os = "linux")]
+pub mod network;
 pub mod safety;
+/// Empirical complexity from scaling benchmarks
+pub mod scaling;
@@ -1,12 +1,21 @@
 //! Performance metrics collection utilities for CrabScore analysis.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use crabscore_core::error::CrabScoreError;
 use crabscore_core::metrics::{
     LatencyMetrics, PerformanceMetrics, ResourceMetrics, ScalabilityMetrics, ThroughputMetrics,
 };
-use std::time::Instant;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::process::Command;
 
+use crate::affinity;
+use crate::scaling::{fit_power_law, ScalingFit};
+
 /// Options controlling how benchmarks are executed.
 #[derive(Debug, Clone)]
 pub struct BenchmarkOptions {
@@ -16,6 +25,23 @@ pub struct BenchmarkOptions {
     pub iterations: u32,
     /// Arguments to pass to the executable.
     pub args: Vec<String>,
+    /// Maximum number of in-flight requests for HTTP benchmarks.
+    pub http_concurrency: usize,
+    /// How long to wait for a server binary to start listening.
+    pub startup_timeout: Duration,
+    /// How long a single HTTP benchmark request may take before it counts as
+    /// failed.
+    pub request_timeout: Duration,
+    /// Minimum number of samples for p95/p99/p999 to be considered reliable.
+    pub min_iterations_for_percentiles: u32,
+    /// Data piped to the executable's stdin on every run (stdin is inherited when `None`).
+    pub stdin_data: Option<Vec<u8>>,
+    /// Core to pin the executable to (see [`crate::affinity`]); check it with
+    /// [`crate::affinity::check_pinning`] first.
+    pub pin_cpu: Option<usize>,
+    /// How often a failed measured iteration is re-run before it is recorded
+    /// as a failure.
+    pub retries: u32,
 }
 
 impl Default for BenchmarkOptions {
@@ -24,23 +50,71 @@ impl Default for BenchmarkOptions {
             warmup: 1,
             iterations: 5,
             args: Vec::new(),
+            http_concurrency: 8,
+            startup_timeout: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(10),
+            min_iterations_for_percentiles: 20,
+            stdin_data: None,
+            pin_cpu: None,
+            retries: 0,
         }
     }
 }
 
+impl BenchmarkOptions {
+    /// Executions of the binary per [`BenchmarkRunner::benchmark`]: the cold
+    /// start, the warm-ups and the measured iterations.
+    pub fn runs(&self) -> u32 {
+        1 + self.warmup + self.iterations
+    }
+}
+
+/// Callback invoked with `(completed, total)` measured iterations.
+pub type IterationCallback = Box<dyn Fn(u32, u32) + Send + Sync>;
+
 /// Runs a target executable multiple times and aggregates latency statistics.
 #[derive(Default)]
 pub struct BenchmarkRunner {
-    opts: BenchmarkOptions,
+    pub(crate) opts: BenchmarkOptions,
+    on_iteration: Option<IterationCallback>,
+    /// Executions of the binary so far, see [`Self::executions`]
+    executions: AtomicU32,
 }
 
 impl BenchmarkRunner {
     /// Create a new BenchmarkMetrics with the given options.
     pub fn new(opts: BenchmarkOptions) -> Self {
-        Self { opts }
+        Self {
+            opts,
+            on_iteration: None,
+            executions: AtomicU32::new(0),
+        }
+    }
+
+    /// How often the binary was executed by [`Self::benchmark`] and
+    /// [`Self::benchmark_with_input_sizes`] so far, counting cold starts,
+    /// warm-ups and retried iterations; at most [`BenchmarkOptions::runs`]
+    /// per benchmark without retries.
+    pub fn executions(&self) -> u32 {
+        self.executions.load(Ordering::Relaxed)
+    }
+
+    /// Report progress after every measured iteration of [`Self::benchmark`].
+    pub fn on_iteration(mut self, callback: impl Fn(u32, u32) + Send + Sync + 'static) -> Self {
+        self.on_iteration = Some(Box::new(callback));
+        self
     }
 
     /// Benchmark the given executable and return `PerformanceMetrics`.
+    ///
+    /// A first, un-warmed run gives `cold_start_ms`; the percentiles only
+    /// cover the measured runs after `warmup`. When the cold run fails, the
+    /// first measured run stands in for it. The size of the executable file
+    /// is recorded when `executable` is a path rather than a `PATH` lookup.
+    ///
+    /// Fails with [`CrabScoreError::BinaryNotFound`] when the executable
+    /// cannot be found and [`CrabScoreError::AllIterationsFailed`] when no
+    /// measured run exits successfully.
     pub async fn benchmark<P: AsRef<std::path::Path>>(
         &self,
         executable: P,
@@ -48,34 +122,60 @@ impl BenchmarkRunner {
         let exe = executable.as_ref();
         let mut samples = Vec::with_capacity(self.opts.iterations as usize);
 
+        // Cold start: nothing cached yet by the OS or the binary
+        let start = Instant::now();
+        let cold = self.run(exe, &self.opts.args).await?;
+        let cold_start_ms = cold
+            .status
+            .success()
+            .then(|| start.elapsed().as_secs_f64() * 1000.0);
+
         // Warm-up runs (ignored)
         for _ in 0..self.opts.warmup {
-            let _ = Command::new(exe).args(&self.opts.args).status().await?;
+            let _ = self.run(exe, &self.opts.args).await?;
         }
 
         // Measured runs
-        for _ in 0..self.opts.iterations {
-            let start = Instant::now();
-            let status = Command::new(exe).args(&self.opts.args).status().await?;
-            let elapsed = start.elapsed();
-            if status.success() {
-                samples.push(elapsed.as_secs_f64() * 1000.0); // ms
+        let mut io_syscalls = Some(0);
+        let mut attempts = 0;
+        let measured = Instant::now();
+        for i in 0..self.opts.iterations {
+            let run = self.run_measured(exe, &self.opts.args).await?;
+            samples.extend(run.ms);
+            attempts += run.attempts;
+            io_syscalls = io_syscalls.zip(run.io_syscalls).map(|(a, b)| a + b);
+            if let Some(callback) = &self.on_iteration {
+                callback(i + 1, self.opts.iterations);
             }
         }
+        let measured = measured.elapsed().as_secs_f64();
+        let io_operations_per_sec = match io_syscalls {
+            Some(io) if measured > 0.0 => io as f64 / measured,
+            _ => 0.0,
+        };
 
         if samples.is_empty() {
-            return Ok(PerformanceMetrics::default());
+            if self.opts.iterations == 0 {
+                return Ok(PerformanceMetrics::default());
+            }
+            return Err(CrabScoreError::AllIterationsFailed {
+                target: exe.display().to_string(),
+                attempts,
+            }
+            .into());
         }
 
+        let cold_start_ms = cold_start_ms.unwrap_or(samples[0]);
         samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let idx =
-            |p: f64| ((p * (samples.len() as f64 - 1.0)).round() as usize).min(samples.len() - 1);
 
         let latency = LatencyMetrics {
-            p50_ms: samples[idx(0.50)],
-            p95_ms: samples[idx(0.95)],
-            p99_ms: samples[idx(0.99)],
-            cold_start_ms: samples[0],
+            p50_ms: percentile(&samples, 0.50),
+            p95_ms: percentile(&samples, 0.95),
+            p99_ms: percentile(&samples, 0.99),
+            p999_ms: percentile(&samples, 0.999),
+            stddev_ms: stddev(&samples),
+            reliable_percentiles: self.percentiles_reliable(samples.len()),
+            cold_start_ms,
             ttfb_ms: 0.0, // not measured here
         };
 
@@ -94,10 +194,947 @@ impl BenchmarkRunner {
         let perf = PerformanceMetrics {
             latency,
             throughput,
-            resource_usage: ResourceMetrics::default(),
+            resource_usage: ResourceMetrics {
+                io_operations_per_sec,
+                binary_size_bytes: binary_size(exe),
+                ..Default::default()
+            },
             scalability: ScalabilityMetrics::default(),
         };
 
         Ok(perf)
     }
+
+    /// Benchmark a long-running HTTP server binary.
+    ///
+    /// Starts `executable`, waits (up to `startup_timeout`) for the port of
+    /// `url` to accept connections, then issues `requests` GET requests with
+    /// at most `http_concurrency` in flight. Response latencies feed the
+    /// percentiles, the median time-to-first-byte becomes `ttfb_ms` and the
+    /// startup time becomes `cold_start_ms`. The server is killed once done,
+    /// including on error. Only plain `http://` URLs are supported.
+    ///
+    /// Besides the errors of [`Self::benchmark`], fails with
+    /// [`CrabScoreError::Timeout`] when the server never starts listening.
+    pub async fn benchmark_http<P: AsRef<std::path::Path>>(
+        &self,
+        executable: P,
+        url: &str,
+        requests: u32,
+    ) -> Result<PerformanceMetrics> {
+        let exe = executable.as_ref();
+        let target = HttpTarget::parse(url)?;
+        let mut command = Command::new(exe);
+        command
+            .args(&self.opts.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        let mut child = affinity::spawn(&mut command, self.opts.pin_cpu)
+            .map_err(|e| spawn_error(exe, e.into()))?;
+
+        let result = self.run_http_load(exe, &mut child, &target, requests).await;
+        let _ = child.kill().await;
+        result.map(|mut perf| {
+            perf.resource_usage.binary_size_bytes = binary_size(exe);
+            perf
+        })
+    }
+
+    /// Time the executable at increasing input sizes and fit its growth rate.
+    ///
+    /// Each size is passed as an argument: it replaces any `{n}` placeholder
+    /// in `args`, or is appended after them. The median of `iterations` runs
+    /// (after `warmup`) is recorded per size. Returns `None` when fewer than
+    /// two sizes ran successfully.
+    pub async fn benchmark_with_input_sizes<P: AsRef<std::path::Path>>(
+        &self,
+        executable: P,
+        sizes: &[u64],
+    ) -> Result<Option<ScalingFit>> {
+        let exe = executable.as_ref();
+        let mut points = Vec::with_capacity(sizes.len());
+
+        for &size in sizes {
+            let args = self.sized_args(size);
+            for _ in 0..self.opts.warmup {
+                let _ = self.run(exe, &args).await?;
+            }
+            let mut samples = Vec::with_capacity(self.opts.iterations as usize);
+            for _ in 0..self.opts.iterations {
+                samples.extend(self.run_measured(exe, &args).await?.ms);
+            }
+            if samples.is_empty() {
+                tracing::warn!("{} failed for input size {}", exe.display(), size);
+                continue;
+            }
+            samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points.push((size as f64, percentile(&samples, 0.50)));
+        }
+
+        Ok(fit_power_law(&points))
+    }
+
+    /// Run the executable once to completion, feeding it `stdin_data`.
+    ///
+    /// The data is written in full and stdin closed before waiting for the
+    /// process, so the measured time always covers consuming the whole input.
+    /// A process that exits without reading everything is not an error.
+    async fn run(&self, exe: &std::path::Path, args: &[String]) -> Result<Finished> {
+        self.executions.fetch_add(1, Ordering::Relaxed);
+        let mut command = Command::new(exe);
+        command.args(args);
+        self.run_command(command)
+            .await
+            .map_err(|e| spawn_error(exe, e))
+    }
+
+    /// Time one measured iteration, re-running it up to `retries` times
+    /// while it exits unsuccessfully.
+    async fn run_measured(&self, exe: &std::path::Path, args: &[String]) -> Result<MeasuredRun> {
+        let mut io_syscalls = Some(0);
+        for attempt in 0..=self.opts.retries {
+            if attempt > 0 {
+                tracing::warn!(
+                    "{} failed, retrying ({}/{})",
+                    exe.display(),
+                    attempt,
+                    self.opts.retries
+                );
+            }
+            let start = Instant::now();
+            let finished = self.run(exe, args).await?;
+            io_syscalls = io_syscalls.zip(finished.io_syscalls).map(|(a, b)| a + b);
+            if finished.status.success() {
+                return Ok(MeasuredRun {
+                    ms: Some(start.elapsed().as_secs_f64() * 1000.0),
+                    io_syscalls,
+                    attempts: attempt + 1,
+                });
+            }
+        }
+        Ok(MeasuredRun {
+            ms: None,
+            io_syscalls,
+            attempts: self.opts.retries + 1,
+        })
+    }
+
+    /// Run a prepared command to completion on the `pin_cpu` core, feeding it
+    /// `stdin_data` like [`Self::run`].
+    pub(crate) async fn run_command(&self, mut command: Command) -> Result<Finished> {
+        if self.opts.stdin_data.is_some() {
+            command.stdin(Stdio::piped());
+        }
+        let mut child = affinity::spawn(command.kill_on_drop(true), self.opts.pin_cpu)?;
+        if let (Some(data), Some(mut stdin)) = (&self.opts.stdin_data, child.stdin.take()) {
+            match stdin.write_all(data).await {
+                Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+                _ => {}
+            }
+        }
+        let io_syscalls = match child.id() {
+            Some(pid) => exited_io_syscalls(pid).await,
+            None => None,
+        };
+        Ok(Finished {
+            status: child.wait().await?,
+            io_syscalls,
+        })
+    }
+
+    /// Arguments for one run at input `size`
+    fn sized_args(&self, size: u64) -> Vec<String> {
+        let size = size.to_string();
+        let mut args: Vec<String> = self
+            .opts
+            .args
+            .iter()
+            .map(|a| a.replace("{n}", &size))
+            .collect();
+        if !self.opts.args.iter().any(|a| a.contains("{n}")) {
+            args.push(size);
+        }
+        args
+    }
+
+    /// Whether `samples` measurements are enough for tail percentiles; warns if not.
+    fn percentiles_reliable(&self, samples: usize) -> bool {
+        let min = self.opts.min_iterations_for_percentiles as usize;
+        if samples < min {
+            tracing::warn!(
+                "Only {} samples collected (< {}): p95/p99/p999 latencies are unreliable",
+                samples,
+                min
+            );
+            return false;
+        }
+        true
+    }
+
+    async fn run_http_load(
+        &self,
+        exe: &std::path::Path,
+        server: &mut tokio::process::Child,
+        target: &HttpTarget,
+        requests: u32,
+    ) -> Result<PerformanceMetrics> {
+        let started = Instant::now();
+        loop {
+            if TcpStream::connect(target.addr()).await.is_ok() {
+                break;
+            }
+            if let Some(status) = server.try_wait()? {
+                return Err(CrabScoreError::MeasurementError(format!(
+                    "server {} exited ({}) before listening on {}",
+                    exe.display(),
+                    status,
+                    target.addr()
+                ))
+                .into());
+            }
+            if started.elapsed() >= self.opts.startup_timeout {
+                return Err(CrabScoreError::Timeout {
+                    operation: format!("server did not start listening on {}", target.addr()),
+                    after: self.opts.startup_timeout,
+                }
+                .into());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let startup_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        let target = Arc::new(target.clone());
+        let permits = Arc::new(tokio::sync::Semaphore::new(
+            self.opts.http_concurrency.max(1),
+        ));
+        let request_timeout = self.opts.request_timeout;
+        let load_start = Instant::now();
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..requests {
+            let target = Arc::clone(&target);
+            let permit = Arc::clone(&permits).acquire_owned().await?;
+            tasks.spawn(async move {
+                let timing = tokio::time::timeout(request_timeout, http_get(&target))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(CrabScoreError::Timeout {
+                            operation: format!("HTTP request to {}", target.addr()),
+                            after: request_timeout,
+                        }
+                        .into())
+                    });
+                drop(permit);
+                timing
+            });
+        }
+
+        let mut latencies = Vec::with_capacity(requests as usize);
+        let mut ttfbs = Vec::with_capacity(requests as usize);
+        while let Some(joined) = tasks.join_next().await {
+            match joined? {
+                Ok((ttfb, total)) => {
+                    ttfbs.push(ttfb);
+                    latencies.push(total);
+                }
+                Err(e) => tracing::warn!("HTTP request failed: {}", e),
+            }
+        }
+        let wall = load_start.elapsed().as_secs_f64();
+
+        if latencies.is_empty() {
+            return Err(CrabScoreError::AllIterationsFailed {
+                target: format!("http://{}", target.addr()),
+                attempts: requests,
+            }
+            .into());
+        }
+        latencies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        ttfbs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        Ok(PerformanceMetrics {
+            latency: LatencyMetrics {
+                p50_ms: percentile(&latencies, 0.50),
+                p95_ms: percentile(&latencies, 0.95),
+                p99_ms: percentile(&latencies, 0.99),
+                p999_ms: percentile(&latencies, 0.999),
+                stddev_ms: stddev(&latencies),
+                reliable_percentiles: self.percentiles_reliable(latencies.len()),
+                cold_start_ms: startup_ms,
+                ttfb_ms: percentile(&ttfbs, 0.50),
+            },
+            throughput: ThroughputMetrics {
+                requests_per_second: if wall > 0.0 {
+                    latencies.len() as f64 / wall
+                } else {
+                    0.0
+                },
+                mb_per_second: 0.0,
+                concurrent_connections: self.opts.http_concurrency as u64,
+                queue_depth: 0.0,
+            },
+            resource_usage: ResourceMetrics::default(),
+            scalability: ScalabilityMetrics::default(),
+        })
+    }
+}
+
+/// How measurements of several benchmark targets are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregation {
+    /// Arithmetic mean of every metric
+    #[default]
+    Mean,
+    /// Worst value of every metric (highest latency, lowest throughput)
+    Worst,
+}
+
+/// Combine the performance of several benchmarked targets into one.
+///
+/// Returns default metrics for an empty slice.
+pub fn aggregate_performance(
+    samples: &[PerformanceMetrics],
+    how: Aggregation,
+) -> PerformanceMetrics {
+    let Some(first) = samples.first() else {
+        return PerformanceMetrics::default();
+    };
+    let pick = |field: fn(&PerformanceMetrics) -> f64, higher_is_worse: bool| -> f64 {
+        let values = samples.iter().map(field);
+        match how {
+            Aggregation::Mean => values.sum::<f64>() / samples.len() as f64,
+            Aggregation::Worst if higher_is_worse => values.fold(f64::MIN, f64::max),
+            Aggregation::Worst => values.fold(f64::MAX, f64::min),
+        }
+    };
+
+    PerformanceMetrics {
+        latency: LatencyMetrics {
+            p50_ms: pick(|m| m.latency.p50_ms, true),
+            p95_ms: pick(|m| m.latency.p95_ms, true),
+            p99_ms: pick(|m| m.latency.p99_ms, true),
+            p999_ms: pick(|m| m.latency.p999_ms, true),
+            stddev_ms: pick(|m| m.latency.stddev_ms, true),
+            reliable_percentiles: samples.iter().all(|m| m.latency.reliable_percentiles),
+            cold_start_ms: pick(|m| m.latency.cold_start_ms, true),
+            ttfb_ms: pick(|m| m.latency.ttfb_ms, true),
+        },
+        throughput: ThroughputMetrics {
+            requests_per_second: pick(|m| m.throughput.requests_per_second, false),
+            mb_per_second: pick(|m| m.throughput.mb_per_second, false),
+            concurrent_connections: first.throughput.concurrent_connections,
+            queue_depth: pick(|m| m.throughput.queue_depth, true),
+        },
+        resource_usage: ResourceMetrics {
+            cpu_efficiency: pick(|m| m.resource_usage.cpu_efficiency, false),
+            memory_bandwidth_gb_s: pick(|m| m.resource_usage.memory_bandwidth_gb_s, false),
+            io_operations_per_sec: pick(|m| m.resource_usage.io_operations_per_sec, false),
+            cache_hit_rate: pick(|m| m.resource_usage.cache_hit_rate, false),
+            binary_size_bytes: aggregate_binary_size(samples, how),
+        },
+        scalability: first.scalability.clone(),
+    }
+}
+
+/// Mean or largest binary size of the samples that recorded one
+fn aggregate_binary_size(samples: &[PerformanceMetrics], how: Aggregation) -> Option<u64> {
+    let sizes: Vec<u64> = samples
+        .iter()
+        .filter_map(|m| m.resource_usage.binary_size_bytes)
+        .collect();
+    match how {
+        _ if sizes.is_empty() => None,
+        Aggregation::Mean => Some(sizes.iter().sum::<u64>() / sizes.len() as u64),
+        Aggregation::Worst => sizes.into_iter().max(),
+    }
+}
+
+/// Size in bytes of the executable file at `exe`, if it exists
+fn binary_size(exe: &std::path::Path) -> Option<u64> {
+    std::fs::metadata(exe)
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+}
+
+/// Report a missing executable as [`CrabScoreError::BinaryNotFound`]
+fn spawn_error(exe: &std::path::Path, e: anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<std::io::Error>() {
+        Some(io) if io.kind() == std::io::ErrorKind::NotFound => {
+            CrabScoreError::BinaryNotFound(exe.to_path_buf()).into()
+        }
+        _ => e,
+    }
+}
+
+/// A finished run of a benchmarked executable
+pub(crate) struct Finished {
+    pub(crate) status: std::process::ExitStatus,
+    /// Read and write syscalls of the run, see [`exited_io_syscalls`]
+    pub(crate) io_syscalls: Option<u64>,
+}
+
+/// Outcome of one measured iteration, including its retries
+struct MeasuredRun {
+    /// Duration of the successful attempt; `None` when every attempt failed
+    ms: Option<f64>,
+    /// Read and write syscalls of every attempt
+    io_syscalls: Option<u64>,
+    /// Executions of the binary, including the retries
+    attempts: u32,
+}
+
+/// Read and write syscalls of the child process `pid`, read from
+/// `/proc/<pid>/io` once it has exited (Linux only).
+///
+/// The child is waited for with `WNOWAIT`, so it stays a zombie whose
+/// accounting, which includes the processes it reaped itself, can still be
+/// read; [`tokio::process::Child::wait`] reaps it afterwards. Unlike
+/// `/proc/self/io`, the count leaves out the runner's own I/O and that of
+/// the samplers running alongside the benchmark.
+#[cfg(target_os = "linux")]
+async fn exited_io_syscalls(pid: u32) -> Option<u64> {
+    use rustix::process::{waitid, Pid, WaitId, WaitIdOptions};
+
+    let pid = Pid::from_raw(i32::try_from(pid).ok()?)?;
+    tokio::task::spawn_blocking(move || {
+        let exited = loop {
+            match waitid(
+                WaitId::Pid(pid),
+                WaitIdOptions::EXITED | WaitIdOptions::NOWAIT,
+            ) {
+                Err(rustix::io::Errno::INTR) => continue,
+                result => break result,
+            }
+        };
+        exited.ok()?;
+        parse_proc_io(&std::fs::read_to_string(format!("/proc/{pid}/io")).ok()?)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn exited_io_syscalls(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// `syscr + syscw` of a `/proc/<pid>/io` file
+fn parse_proc_io(io: &str) -> Option<u64> {
+    let field = |name: &str| {
+        io.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    Some(field("syscr")? + field("syscw")?)
+}
+
+/// Value at quantile `p` (0.0-1.0) of an ascending, non-empty sample set.
+///
+/// Linearly interpolates between adjacent order statistics (Hyndman & Fan
+/// type 7, the default of R and NumPy), so nearby quantiles stay distinct
+/// even with only a handful of samples.
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let h = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lo = h.floor() as usize;
+    let hi = (lo + 1).min(sorted.len() - 1);
+    sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+/// Sample standard deviation (n - 1 denominator); 0 for fewer than two samples.
+pub(crate) fn stddev(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    var.sqrt()
+}
+
+/// Host, port and path of a plain-HTTP benchmark URL.
+#[derive(Debug, Clone, PartialEq)]
+struct HttpTarget {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl HttpTarget {
+    fn parse(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix("http://")
+            .with_context(|| format!("unsupported URL '{url}': only http:// is supported"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((h, p)) => (
+                h,
+                p.parse()
+                    .with_context(|| format!("invalid port in '{url}'"))?,
+            ),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            bail!("missing host in '{url}'");
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// Issue one GET request and return (time to first byte, total time) in ms.
+async fn http_get(target: &HttpTarget) -> Result<(f64, f64)> {
+    let start = Instant::now();
+    let mut stream = TcpStream::connect(target.addr()).await?;
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        target.path, target.host
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut buf = [0u8; 8192];
+    let first = stream.read(&mut buf).await?;
+    if first == 0 {
+        bail!("connection closed before any response bytes");
+    }
+    let ttfb = start.elapsed();
+    if !buf[..first].starts_with(b"HTTP/") {
+        bail!("response is not HTTP");
+    }
+    while stream.read(&mut buf).await? > 0 {}
+    let total = start.elapsed();
+
+    Ok((ttfb.as_secs_f64() * 1000.0, total.as_secs_f64() * 1000.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_every_measured_iteration() {
+        use std::sync::Mutex;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 0,
+            iterations: 3,
+            ..Default::default()
+        })
+        .on_iteration(move |done, total| sink.lock().unwrap().push((done, total)));
+
+        let perf = runner.benchmark("/bin/true").await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), [(1, 3), (2, 3), (3, 3)]);
+        let size = std::fs::metadata("/bin/true").unwrap().len();
+        assert_eq!(perf.resource_usage.binary_size_bytes, Some(size));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cold_start_is_measured_apart_from_warm_runs() {
+        // Slow only on the very first invocation, like a cache being filled
+        let tmp = tempfile::tempdir().unwrap();
+        let marker = tmp.path().join("warm");
+        let script = format!(
+            "[ -e '{0}' ] || {{ touch '{0}'; sleep 0.3; }}",
+            marker.display()
+        );
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 1,
+            iterations: 4,
+            args: vec!["-c".into(), script],
+            ..Default::default()
+        });
+
+        let perf = runner.benchmark("/bin/sh").await.unwrap();
+        assert!(perf.latency.cold_start_ms >= 300.0, "{:?}", perf.latency);
+        assert!(perf.latency.p99_ms < 300.0, "{:?}", perf.latency);
+        assert_eq!(runner.opts.runs(), 6);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pipes_stdin_data_to_every_run() {
+        // Succeeds only when the whole input arrives and stdin is then closed.
+        let input = vec![b'x'; 256 * 1024];
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 1,
+            iterations: 3,
+            args: vec!["-c".into(), "test \"$(wc -c)\" -eq 262144".into()],
+            stdin_data: Some(input),
+            ..Default::default()
+        });
+
+        let perf = runner.benchmark("/bin/sh").await.unwrap();
+        assert!(perf.latency.p50_ms > 0.0);
+        assert!(perf.throughput.requests_per_second > 0.0);
+    }
+
+    #[test]
+    fn parses_proc_io_syscall_counts() {
+        let io = "rchar: 4087\nwchar: 30\nsyscr: 11\nsyscw: 10\nread_bytes: 0\n\
+                  write_bytes: 8192\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_proc_io(io), Some(21));
+        assert_eq!(parse_proc_io("rchar: 1\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn measures_iops_of_a_file_writing_binary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("out.txt");
+        let script = format!(
+            "for i in $(seq 200); do echo $i >> '{}'; done",
+            out.display()
+        );
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 0,
+            iterations: 3,
+            args: vec!["-c".into(), script],
+            ..Default::default()
+        });
+
+        let perf = runner.benchmark("/bin/sh").await.unwrap();
+        // At least 600 writes, made within the measured time
+        let iops = perf.resource_usage.io_operations_per_sec;
+        let measured_secs = 3.0 * perf.latency.p50_ms.max(perf.latency.p99_ms) / 1000.0;
+        assert!(iops > 0.0);
+        assert!(iops * measured_secs * 1.5 >= 600.0, "{iops} IOPS");
+    }
+
+    #[test]
+    fn input_size_replaces_placeholder_or_is_appended() {
+        let appended = BenchmarkRunner::new(BenchmarkOptions {
+            args: vec!["--quiet".into()],
+            ..Default::default()
+        });
+        assert_eq!(appended.sized_args(100), ["--quiet", "100"]);
+
+        let placed = BenchmarkRunner::new(BenchmarkOptions {
+            args: vec!["--len={n}".into(), "-v".into()],
+            ..Default::default()
+        });
+        assert_eq!(placed.sized_args(7), ["--len=7", "-v"]);
+    }
+
+    /// Collects formatted log output for assertions.
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn warns_when_too_few_iterations_for_percentiles() {
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let run = |iterations| {
+            BenchmarkRunner::new(BenchmarkOptions {
+                warmup: 0,
+                iterations,
+                min_iterations_for_percentiles: 4,
+                ..Default::default()
+            })
+        };
+
+        let few = run(3).benchmark("/bin/true").await.unwrap();
+        assert!(!few.latency.reliable_percentiles);
+        let text = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            text.contains("WARN") && text.contains("unreliable"),
+            "{text}"
+        );
+
+        logs.0.lock().unwrap().clear();
+        let enough = run(4).benchmark("/bin/true").await.unwrap();
+        assert!(enough.latency.reliable_percentiles);
+        assert!(logs.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn computes_tail_and_spread_of_known_samples() {
+        let samples = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        // mean 5, squared deviations sum to 32 -> sample variance 32 / 7
+        assert!((stddev(&samples) - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert_eq!(stddev(&[3.0]), 0.0);
+        assert!((percentile(&samples, 0.999) - 8.986).abs() < 1e-9);
+    }
+
+    #[test]
+    fn percentiles_interpolate_like_type_7() {
+        // Reference: numpy.percentile(x, q) with the default "linear" method
+        let x = [12.0, 15.0, 11.0, 30.0, 14.0];
+        let mut sorted = x.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (p, expected) in [
+            (0.0, 11.0),
+            (0.5, 14.0),
+            (0.9, 24.0),
+            (0.95, 27.0),
+            (0.99, 29.4),
+            (1.0, 30.0),
+        ] {
+            assert!(
+                (percentile(&sorted, p) - expected).abs() < 1e-9,
+                "p{p}: {} != {expected}",
+                percentile(&sorted, p)
+            );
+        }
+        assert_ne!(percentile(&sorted, 0.95), percentile(&sorted, 0.99));
+        assert_eq!(percentile(&[7.0], 0.99), 7.0);
+    }
+
+    #[test]
+    fn aggregates_mean_and_worst() {
+        let perf = |p50: f64, rps: f64| PerformanceMetrics {
+            latency: LatencyMetrics {
+                p50_ms: p50,
+                ..Default::default()
+            },
+            throughput: ThroughputMetrics {
+                requests_per_second: rps,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut samples = [perf(10.0, 100.0), perf(30.0, 50.0)];
+        samples[0].resource_usage.binary_size_bytes = Some(1000);
+        samples[1].resource_usage.binary_size_bytes = Some(3000);
+
+        let mean = aggregate_performance(&samples, Aggregation::Mean);
+        assert_eq!(mean.latency.p50_ms, 20.0);
+        assert_eq!(mean.throughput.requests_per_second, 75.0);
+        assert_eq!(mean.resource_usage.binary_size_bytes, Some(2000));
+
+        let worst = aggregate_performance(&samples, Aggregation::Worst);
+        assert_eq!(worst.latency.p50_ms, 30.0);
+        assert_eq!(worst.throughput.requests_per_second, 50.0);
+        assert_eq!(worst.resource_usage.binary_size_bytes, Some(3000));
+    }
+
+    #[test]
+    fn parses_http_urls() {
+        let t = HttpTarget::parse("http://127.0.0.1:3000/health").unwrap();
+        assert_eq!(
+            (t.host.as_str(), t.port, t.path.as_str()),
+            ("127.0.0.1", 3000, "/health")
+        );
+        let t = HttpTarget::parse("http://localhost").unwrap();
+        assert_eq!((t.port, t.path.as_str()), (80, "/"));
+        assert!(HttpTarget::parse("https://localhost").is_err());
+    }
+
+    #[tokio::test]
+    async fn http_get_measures_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = sock.read(&mut buf).await.unwrap();
+            sock.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+        });
+
+        let target = HttpTarget::parse(&format!("http://127.0.0.1:{port}/")).unwrap();
+        let (ttfb, total) = http_get(&target).await.unwrap();
+        assert!(ttfb > 0.0 && total >= ttfb);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn http_benchmark_times_out_when_server_never_listens() {
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            args: vec!["5".to_string()],
+            startup_timeout: Duration::from_millis(200),
+            ..Default::default()
+        });
+        let err = runner
+            .benchmark_http("sleep", "http://127.0.0.1:9/", 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("did not start listening"));
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::Timeout { after, .. }) if *after == Duration::from_millis(200)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn http_benchmark_fails_fast_when_server_exits() {
+        let runner = BenchmarkRunner::new(BenchmarkOptions::default());
+        let started = Instant::now();
+        let err = runner
+            .benchmark_http("false", "http://127.0.0.1:9/", 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exited"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hung_requests_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accept connections but never answer
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((sock, _)) = listener.accept().await {
+                held.push(sock);
+            }
+        });
+
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            request_timeout: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let target = HttpTarget::parse(&format!("http://127.0.0.1:{port}/")).unwrap();
+        let mut server = Command::new("sleep")
+            .arg("5")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let err = runner
+            .run_http_load(std::path::Path::new("sleep"), &mut server, &target, 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::AllIterationsFailed { attempts: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_binary_is_reported_as_not_found() {
+        let missing = std::path::Path::new("/nonexistent/crabscore-missing-binary");
+        let runner = BenchmarkRunner::new(BenchmarkOptions::default());
+        for err in [
+            runner.benchmark(missing).await.unwrap_err(),
+            runner
+                .benchmark_http(missing, "http://127.0.0.1:9/", 1)
+                .await
+                .unwrap_err(),
+        ] {
+            assert!(matches!(
+                err.downcast_ref::<CrabScoreError>(),
+                Some(CrabScoreError::BinaryNotFound(path)) if path == missing
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_iterations_are_retried() {
+        // The second invocation (the first measured one, after the cold
+        // start) fails; every other one succeeds
+        let tmp = tempfile::tempdir().unwrap();
+        let count = tmp.path().join("count");
+        let script = format!(
+            "echo x >> '{0}'; [ \"$(wc -l < '{0}')\" -ne 2 ]",
+            count.display()
+        );
+        let runner = |retries| {
+            BenchmarkRunner::new(BenchmarkOptions {
+                warmup: 0,
+                iterations: 1,
+                args: vec!["-c".into(), script.clone()],
+                retries,
+                ..Default::default()
+            })
+        };
+
+        let err = runner(0).benchmark("/bin/sh").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::AllIterationsFailed { attempts: 1, .. })
+        ));
+
+        std::fs::remove_file(&count).unwrap();
+        let retried = runner(2);
+        let perf = retried.benchmark("/bin/sh").await.unwrap();
+        assert!(perf.latency.p50_ms > 0.0);
+        // Cold start, the failed attempt and its successful retry
+        let runs = std::fs::read_to_string(&count).unwrap().lines().count();
+        assert_eq!(runs, 3);
+        assert_eq!(retried.executions(), 3);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_every_iteration_is_an_error() {
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 0,
+            iterations: 3,
+            ..Default::default()
+        });
+        let err = runner.benchmark("/bin/false").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::AllIterationsFailed { attempts: 3, .. })
+        ));
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            warmup: 0,
+            iterations: 3,
+            retries: 1,
+            ..Default::default()
+        });
+        let err = runner.benchmark("/bin/false").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::AllIterationsFailed { attempts: 6, .. })
+        ));
+
+        // A server that hangs up on every request
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((sock, _)) = listener.accept().await {
+                drop(sock);
+            }
+        });
+        let runner = BenchmarkRunner::new(BenchmarkOptions {
+            args: vec!["5".to_string()],
+            ..Default::default()
+        });
+        let err = runner
+            .benchmark_http("sleep", &format!("http://127.0.0.1:{port}/"), 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CrabScoreError>(),
+            Some(CrabScoreError::AllIterationsFailed { attempts: 2, .. })
+        ));
+    }
 }
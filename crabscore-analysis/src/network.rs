@@ -0,0 +1,142 @@
+//! Network egress measurement by syscall tracing (Linux only).
+//!
+//! The benchmarked executable is run once under `strace`, following every
+//! thread and child process, with socket file descriptors decoded (`-yy`).
+//! The return values of successful send and write calls on TCP and UDP
+//! sockets are summed; loopback traffic is not egress and is ignored.
+//! Tracing needs `strace` on the `PATH` and permission to ptrace the child
+//! (`kernel.yama.ptrace_scope` ≤ 1 or `CAP_SYS_PTRACE`); otherwise
+//! [`CrabScoreError::UnsupportedError`] is returned so callers can keep an
+//! estimate instead.
+
+use anyhow::Result;
+use crabscore_core::error::CrabScoreError;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::metrics::BenchmarkRunner;
+
+/// Syscalls that send data on a socket passed as their first argument
+/// (`send(2)` is implemented with `sendto` on Linux)
+const SEND_SYSCALLS: [&str; 5] = ["write", "writev", "sendto", "sendmsg", "sendfile"];
+
+impl BenchmarkRunner {
+    /// Run the executable once under `strace` and return the bytes it sent
+    /// to non-loopback sockets.
+    ///
+    /// Uses the runner's arguments, stdin data and CPU pinning (which `strace`
+    /// passes on to the traced program); the program's output is
+    /// discarded and its exit status ignored.
+    pub async fn trace_network_egress<P: AsRef<Path>>(&self, executable: P) -> Result<u64> {
+        let dir = tempfile::tempdir()?;
+        self.trace_into(executable.as_ref(), dir.path()).await
+    }
+
+    async fn trace_into(&self, exe: &Path, dir: &Path) -> Result<u64> {
+        let mut command = Command::new("strace");
+        command
+            .args(["-ff", "-qq", "-yy", "-s", "0"])
+            .arg("-e")
+            .arg(format!("trace={}", SEND_SYSCALLS.join(",")))
+            .arg("-o")
+            .arg(dir.join("trace"))
+            .arg("--")
+            .arg(exe)
+            .args(&self.opts.args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        match self.run_command(command).await {
+            Err(e)
+                if e.downcast_ref::<std::io::Error>()
+                    .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound) =>
+            {
+                return Err(CrabScoreError::unsupported("strace is not installed").into())
+            }
+            result => result?,
+        };
+
+        // strace writes one file per traced process; none means it could not attach
+        let mut traces = std::fs::read_dir(dir)?.peekable();
+        if traces.peek().is_none() {
+            return Err(CrabScoreError::unsupported(format!(
+                "strace could not trace {} (is ptrace permitted?)",
+                exe.display()
+            ))
+            .into());
+        }
+        let mut bytes = 0;
+        for trace in traces {
+            bytes += parse_strace_egress(&std::fs::read_to_string(trace?.path())?);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Bytes sent to non-loopback TCP/UDP sockets according to `strace -yy` output.
+pub fn parse_strace_egress(trace: &str) -> u64 {
+    trace.lines().filter_map(sent_bytes).sum()
+}
+
+/// Bytes sent by one traced call, if it is a successful send to a remote socket.
+fn sent_bytes(line: &str) -> Option<u64> {
+    let (syscall, rest) = line.split_once('(')?;
+    if !SEND_SYSCALLS.contains(&syscall.trim()) {
+        return None;
+    }
+    // The first argument is the descriptor, decoded as e.g.
+    // `5<TCP:[10.0.0.2:51234->93.184.216.34:443]>`
+    let (_, fd) = rest.split_once('<')?;
+    let (socket, endpoints) = fd.split_once(':')?;
+    if !socket.starts_with("TCP") && !socket.starts_with("UDP") {
+        return None;
+    }
+    let remote = endpoints.split_once("->").map(|(_, remote)| remote);
+    let loopback = |addr: &str| {
+        ["127.", "[::1]", "[::ffff:127."]
+            .iter()
+            .any(|prefix| addr.starts_with(prefix))
+    };
+    if remote.is_some_and(loopback) || rest.contains("inet_addr(\"127.") || rest.contains("\"::1\"")
+    {
+        return None;
+    }
+    let (_, result) = line.rsplit_once(") = ")?;
+    result.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_successful_remote_sends() {
+        let trace = r#"write(1</dev/pts/0>, ""..., 12) = 12
+write(3<TCP:[10.0.0.2:51234->93.184.216.34:443]>, ""..., 517) = 517
+sendto(3<TCP:[10.0.0.2:51234->93.184.216.34:443]>, ""..., 100, MSG_NOSIGNAL, NULL, 0) = 64
+sendto(4<UDP:[10.0.0.2:40000]>, ""..., 30, 0, {sa_family=AF_INET, sin_port=htons(53), sin_addr=inet_addr("1.1.1.1")}, 16) = 30
+sendto(4<UDP:[10.0.0.2:40001]>, ""..., 30, 0, {sa_family=AF_INET, sin_port=htons(53), sin_addr=inet_addr("127.0.0.53")}, 16) = 30
+write(5<TCP:[127.0.0.1:40000->127.0.0.1:8080]>, ""..., 90) = 90
+writev(6<TCPv6:[[2001:db8::2]:40000->[2001:db8::1]:443]>, [{iov_base=""..., iov_len=5}], 1) = 5
+sendmsg(7<UNIX:[1234->5678]>, {msg_name=NULL}, 0) = 40
+write(3<TCP:[10.0.0.2:51234->93.184.216.34:443]>, ""..., 80) = -1 EAGAIN (Resource temporarily unavailable)
++++ exited with 0 +++"#;
+        assert_eq!(parse_strace_egress(trace), 517 + 64 + 30 + 5);
+    }
+
+    #[tokio::test]
+    async fn missing_or_failing_strace_is_unsupported() {
+        let runner = BenchmarkRunner::default();
+        // `true` sends nothing when strace can trace it at all
+        match runner.trace_network_egress("/bin/true").await {
+            Ok(bytes) => assert_eq!(bytes, 0),
+            Err(e) => assert!(
+                matches!(
+                    e.downcast_ref::<CrabScoreError>(),
+                    Some(CrabScoreError::UnsupportedError(_))
+                ),
+                "{e:#}"
+            ),
+        }
+    }
+}
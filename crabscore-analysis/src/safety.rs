@@ -5,10 +5,19 @@
 //! & `syn` to calculate:
 //!  * number of `unsafe` blocks/usages
 //!  * very rough cyclomatic complexity (branches per fn)
+//!  * panic sites: `.unwrap()`/`.expect()` calls and `panic!`, `todo!` and
+//!    `unimplemented!` outside `#[test]` functions and `#[cfg(test)]` modules
+//!    (calls inside other macros' arguments are not seen)
 //!  * stub clippy warning count (future work)
+//!
+//! Per-file results are cached by content hash (see
+//! [`crabscore_core::cache`]) so unchanged files are not re-parsed.
 
 use anyhow::Result;
+use crabscore_core::cache::{content_hash, FileCache};
 use crabscore_core::metrics::SafetyMetrics;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
 use syn::{visit::Visit, ItemFn};
 use walkdir::WalkDir;
 
@@ -21,6 +30,50 @@ impl<'ast> Visit<'ast> for UnsafeCounter {
     }
 }
 
+struct PanicCounter {
+    count: u32,
+}
+
+/// Whether `attrs` mark test-only code (`#[test]`, `#[cfg(test)]`, `#[tokio::test]`, ...)
+fn is_test_code(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let path = attr.path();
+        path.segments.last().is_some_and(|s| s.ident == "test")
+            || (path.is_ident("cfg")
+                && attr
+                    .parse_args::<syn::Ident>()
+                    .is_ok_and(|arg| arg == "test"))
+    })
+}
+
+impl<'ast> Visit<'ast> for PanicCounter {
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        if !is_test_code(&i.attrs) {
+            syn::visit::visit_item_mod(self, i);
+        }
+    }
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        if !is_test_code(&i.attrs) {
+            syn::visit::visit_item_fn(self, i);
+        }
+    }
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        if i.method == "unwrap" || i.method == "expect" {
+            self.count += 1;
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        if i.path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "panic" || s.ident == "todo" || s.ident == "unimplemented")
+        {
+            self.count += 1;
+        }
+    }
+}
+
 struct ComplexityVisitor {
     branches: u32,
 }
@@ -39,45 +92,302 @@ impl<'ast> Visit<'ast> for ComplexityVisitor {
     }
 }
 
+/// Options controlling project-wide safety analysis.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// Reuse cached per-file results for unchanged files.
+    pub use_cache: bool,
+    /// Analyse only these files (canonical paths) instead of every file found.
+    pub only_files: Option<Vec<std::path::PathBuf>>,
+    /// Descend at most this many directory levels below the root (files
+    /// directly in the root are at depth 1); unlimited when `None`.
+    pub max_depth: Option<NonZeroUsize>,
+}
+
+impl AnalysisOptions {
+    /// Whether `path` is part of the analysis
+    fn includes(&self, path: &std::path::Path) -> bool {
+        self.only_files
+            .as_ref()
+            .is_none_or(|files| path.canonicalize().is_ok_and(|path| files.contains(&path)))
+    }
+}
+
+/// Safety contribution of a single source file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileSafety {
+    /// Number of `unsafe` blocks in the file
+    pub unsafe_blocks: u32,
+    /// Panic sites outside test code
+    pub panic_sites: u32,
+    /// Sum of McCabe complexity over the file's top-level functions
+    pub total_complexity: u32,
+    /// Number of top-level functions
+    pub fn_count: u32,
+    /// Name and McCabe complexity of each top-level function
+    pub functions: Vec<(String, u32)>,
+}
+
+/// McCabe complexity of one top-level function of a project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionComplexity {
+    /// Function name
+    pub name: String,
+    /// Source file declaring the function
+    pub file: std::path::PathBuf,
+    /// Branch count + 1
+    pub complexity: u32,
+}
+
+/// Analyse a single Rust source file.
+pub fn analyse_source(src: &str) -> Result<FileSafety> {
+    let syntax = syn::parse_file(src)?;
+
+    // Unsafe counting
+    let mut counter = UnsafeCounter { count: 0 };
+    counter.visit_file(&syntax);
+
+    let mut panics = PanicCounter { count: 0 };
+    panics.visit_file(&syntax);
+
+    // Cyclomatic complexity (branch count per function + 1)
+    let mut file = FileSafety {
+        unsafe_blocks: counter.count,
+        panic_sites: panics.count,
+        ..Default::default()
+    };
+    for item in syntax.items.iter() {
+        if let syn::Item::Fn(ItemFn { sig, block, .. }) = item {
+            let mut visitor = ComplexityVisitor { branches: 0 };
+            visitor.visit_block(block);
+            let complexity = visitor.branches + 1; // +1 per McCabe
+            file.total_complexity += complexity;
+            file.fn_count += 1;
+            file.functions.push((sig.ident.to_string(), complexity));
+        }
+    }
+    Ok(file)
+}
+
 /// Analyse a Rust project directory recursively and produce `SafetyMetrics`.
 pub fn analyse_project<P: AsRef<std::path::Path>>(root: P) -> Result<SafetyMetrics> {
+    analyse_project_with(root, &AnalysisOptions::default())
+}
+
+/// Like [`analyse_project`], with explicit [`AnalysisOptions`].
+pub fn analyse_project_with<P: AsRef<std::path::Path>>(
+    root: P,
+    opts: &AnalysisOptions,
+) -> Result<SafetyMetrics> {
+    Ok(analyse_project_functions(root, opts)?.0)
+}
+
+/// Like [`analyse_project_with`], also returning the complexity of every
+/// top-level function, most complex first.
+pub fn analyse_project_functions<P: AsRef<std::path::Path>>(
+    root: P,
+    opts: &AnalysisOptions,
+) -> Result<(SafetyMetrics, Vec<FunctionComplexity>)> {
+    let mut cache = if opts.use_cache {
+        FileCache::<FileSafety>::open("safety")
+    } else {
+        FileCache::disabled()
+    };
     let mut unsafe_blocks = 0u32;
+    let mut panic_sites = 0u32;
     let mut total_complexity = 0u32;
     let mut fn_count = 0u32;
+    let mut functions = Vec::new();
 
-    for entry in WalkDir::new(root) {
-        let entry = entry?;
+    // Symlinks are not followed (so cycles cannot loop), and unreadable
+    // directories and files are skipped rather than failing the analysis
+    let entries = WalkDir::new(root)
+        .follow_links(false)
+        .max_depth(opts.max_depth.map_or(usize::MAX, NonZeroUsize::get))
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .inspect_err(|e| tracing::warn!("Skipping unreadable path: {}", e))
+                .ok()
+        });
+    for entry in entries {
         if entry.file_type().is_file()
             && entry.path().extension().and_then(|s| s.to_str()) == Some("rs")
+            && opts.includes(entry.path())
         {
-            let src = std::fs::read_to_string(entry.path())?;
-            let syntax = syn::parse_file(&src)?;
-
-            // Unsafe counting
-            let mut counter = UnsafeCounter { count: 0 };
-            counter.visit_file(&syntax);
-            unsafe_blocks += counter.count;
-
-            // Cyclomatic complexity (branch count per function + 1)
-            for item in syntax.items.iter() {
-                if let syn::Item::Fn(ItemFn { block, .. }) = item {
-                    let mut visitor = ComplexityVisitor { branches: 0 };
-                    visitor.visit_block(block);
-                    total_complexity += visitor.branches + 1; // +1 per McCabe
-                    fn_count += 1;
+            let src = match std::fs::read_to_string(entry.path()) {
+                Ok(src) => src,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable file {}: {}", entry.path().display(), e);
+                    continue;
+                }
+            };
+            let hash = content_hash(src.as_bytes());
+            let file = match cache.get(entry.path(), hash) {
+                Some(file) => file,
+                None => {
+                    let file = analyse_source(&src)?;
+                    cache.insert(entry.path(), hash, file.clone());
+                    file
                 }
-            }
+            };
+            unsafe_blocks += file.unsafe_blocks;
+            panic_sites += file.panic_sites;
+            total_complexity += file.total_complexity;
+            fn_count += file.fn_count;
+            functions.extend(file.functions.into_iter().map(|(name, complexity)| {
+                FunctionComplexity {
+                    name,
+                    file: entry.path().to_path_buf(),
+                    complexity,
+                }
+            }));
         }
     }
+    if let Err(e) = cache.save() {
+        tracing::warn!("Failed to write safety analysis cache: {}", e);
+    }
+
     let avg_cyclo = if fn_count > 0 {
         total_complexity as f64 / fn_count as f64
     } else {
         1.0
     };
 
-    Ok(SafetyMetrics {
+    // Ties keep walk order so the ranking is stable between runs
+    functions.sort_by_key(|f| std::cmp::Reverse(f.complexity));
+
+    let metrics = SafetyMetrics {
         unsafe_blocks,
         clippy_warnings: 0, // TODO: invoke clippy or parse warnings file
         avg_cyclomatic: avg_cyclo,
-    })
+        panic_sites,
+        build_warnings: None,
+    };
+    Ok((metrics, functions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_symlink_cycles_and_unreadable_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "fn f() { unsafe {} }\n").unwrap();
+        std::os::unix::fs::symlink(tmp.path(), src.join("cycle")).unwrap();
+        let locked = tmp.path().join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::write(locked.join("hidden.rs"), "fn g() {}\n").unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        let opts = AnalysisOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        let result = analyse_project_with(tmp.path(), &opts);
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        // `locked` is still readable when the tests run as root
+        assert_eq!(result.unwrap().unsafe_blocks, 1);
+    }
+
+    #[test]
+    fn ranks_functions_by_complexity() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("a.rs"),
+            "fn flat() {}
+             fn branchy(x: u8) { if x > 1 {} for _ in 0..x {} match x { _ => {} } }
+",
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join("b.rs"),
+            "fn one(x: bool) { if x {} }
+",
+        )
+        .unwrap();
+
+        let opts = AnalysisOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        let (metrics, functions) = analyse_project_functions(tmp.path(), &opts).unwrap();
+        let ranked: Vec<_> = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.complexity))
+            .collect();
+        assert_eq!(ranked, [("branchy", 4), ("one", 2), ("flat", 1)]);
+        assert_eq!(functions[1].file, tmp.path().join("b.rs"));
+        assert!((metrics.avg_cyclomatic - 7.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn files_below_max_depth_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let deep = tmp.path().join("vendor/a/b/c");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(tmp.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(deep.join("lib.rs"), "pub fn f() { unsafe {} }\n").unwrap();
+
+        let unlimited = AnalysisOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            analyse_project_with(tmp.path(), &unlimited)
+                .unwrap()
+                .unsafe_blocks,
+            1
+        );
+        for (max_depth, unsafe_blocks) in [(4, 0), (5, 1)] {
+            let opts = AnalysisOptions {
+                max_depth: NonZeroUsize::new(max_depth),
+                ..unlimited.clone()
+            };
+            let metrics = analyse_project_with(tmp.path(), &opts).unwrap();
+            assert_eq!(metrics.unsafe_blocks, unsafe_blocks, "depth {max_depth}");
+        }
+    }
+
+    #[test]
+    fn counts_panic_sites_outside_tests() {
+        use crabscore_core::scoring::SafetyScoringConfig;
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("lib.rs"),
+            include_str!("../tests/fixtures/panics.rs"),
+        )
+        .unwrap();
+        let opts = AnalysisOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        let metrics = analyse_project_with(tmp.path(), &opts).unwrap();
+        // two unwraps, one expect, panic! and todo!; unwrap_or and the test module don't count
+        assert_eq!(metrics.panic_sites, 5);
+
+        let total = |config: SafetyScoringConfig| -> f64 {
+            config.bonuses(&metrics).iter().map(|b| b.points).sum()
+        };
+        let lenient = total(SafetyScoringConfig::default());
+        let strict = total(SafetyScoringConfig {
+            max_panic_sites: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(lenient - strict, 2.0);
+        let harsh = total(SafetyScoringConfig {
+            max_panic_sites: Some(0),
+            panic_site_penalty: 100.0,
+            ..Default::default()
+        });
+        assert_eq!(harsh, 0.0);
+    }
 }
@@ -0,0 +1,124 @@
+//! Empirical growth estimation from scaling benchmarks.
+//!
+//! Timings taken at increasing input sizes are fitted to a power law
+//! `t = c * n^k` by least squares on `ln t = ln c + k ln n`. The exponent `k`
+//! is the observed growth rate and is mapped to the nearest familiar
+//! complexity class.
+
+use crabscore_core::metrics::AlgorithmEfficiency;
+use serde::{Deserialize, Serialize};
+
+/// Power-law fit of run time against input size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingFit {
+    /// Fitted exponent `k` (1.0 ≈ linear, 2.0 ≈ quadratic)
+    pub exponent: f64,
+    /// Fitted constant `c` in milliseconds
+    pub coefficient: f64,
+    /// Coefficient of determination of the log-log fit (1.0 = perfect)
+    pub r_squared: f64,
+    /// Complexity class closest to `exponent`, e.g. `O(n^2)`
+    pub time_complexity: String,
+}
+
+impl ScalingFit {
+    /// Record the fitted time complexity in `efficiency`.
+    pub fn apply(&self, efficiency: &mut AlgorithmEfficiency) {
+        efficiency.time_complexity = self.time_complexity.clone();
+        efficiency.actual_time_coefficient = self.exponent;
+    }
+}
+
+/// Fit `(input size, time in ms)` points to a power law.
+///
+/// Points with non-positive size or time are ignored; returns `None` when
+/// fewer than two distinct sizes remain.
+pub fn fit_power_law(points: &[(f64, f64)]) -> Option<ScalingFit> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(n, t)| *n > 0.0 && *t > 0.0)
+        .map(|(n, t)| (n.ln(), t.ln()))
+        .collect();
+    if logs.len() < 2 {
+        return None;
+    }
+
+    let len = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / len;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / len;
+    let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let exponent = sxy / sxx;
+    let intercept = mean_y - exponent * mean_x;
+
+    let ss_tot: f64 = logs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let ss_res: f64 = logs
+        .iter()
+        .map(|(x, y)| (y - (intercept + exponent * x)).powi(2))
+        .sum();
+    let r_squared = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else {
+        1.0
+    };
+
+    Some(ScalingFit {
+        exponent,
+        coefficient: intercept.exp(),
+        r_squared,
+        time_complexity: classify(exponent).to_string(),
+    })
+}
+
+/// Complexity class for a log-log growth exponent.
+///
+/// `n log n` has a local log-log slope of roughly `1 + 1/ln n`, i.e. about
+/// 1.1–1.2 at typical benchmark sizes, hence the narrow linear band.
+pub fn classify(exponent: f64) -> &'static str {
+    match exponent {
+        k if k < 0.2 => "O(1)",
+        k if k < 0.6 => "O(log n)",
+        k if k < 1.1 => "O(n)",
+        k if k < 1.5 => "O(n log n)",
+        k if k < 2.5 => "O(n^2)",
+        k if k < 3.5 => "O(n^3)",
+        _ => "O(2^n)",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_synthetic_quadratic_timings() {
+        let points: Vec<(f64, f64)> = [100.0, 200.0, 400.0, 800.0, 1600.0]
+            .iter()
+            // ~3 µs * n^2 with a little multiplicative noise
+            .zip([1.02, 0.97, 1.01, 0.99, 1.03])
+            .map(|(n, noise)| (*n, 3e-3 * n * n * noise))
+            .collect();
+
+        let fit = fit_power_law(&points).unwrap();
+        assert!((fit.exponent - 2.0).abs() < 0.05, "{fit:?}");
+        assert_eq!(fit.time_complexity, "O(n^2)");
+        assert!(fit.r_squared > 0.99);
+
+        let mut efficiency = AlgorithmEfficiency::default();
+        fit.apply(&mut efficiency);
+        assert_eq!(efficiency.time_complexity, "O(n^2)");
+        assert_eq!(efficiency.actual_time_coefficient, fit.exponent);
+    }
+
+    #[test]
+    fn distinguishes_linear_and_constant_growth() {
+        let linear: Vec<_> = [10.0, 100.0, 1000.0].map(|n| (n, 0.5 * n)).to_vec();
+        assert_eq!(fit_power_law(&linear).unwrap().time_complexity, "O(n)");
+        let flat: Vec<_> = [10.0, 100.0, 1000.0].map(|n| (n, 4.0)).to_vec();
+        assert_eq!(fit_power_law(&flat).unwrap().time_complexity, "O(1)");
+        assert!(fit_power_law(&[(10.0, 1.0)]).is_none());
+    }
+}
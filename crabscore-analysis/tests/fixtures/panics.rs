@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+pub fn parse_port(s: &str) -> u16 {
+    s.parse().unwrap()
+}
+
+pub fn lookup(map: &HashMap<String, u32>, key: &str) -> u32 {
+    *map.get(key).expect("key must exist")
+}
+
+pub fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap()
+}
+
+pub fn checked_div(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        panic!("division by zero");
+    }
+    a / b
+}
+
+pub fn later() {
+    todo!()
+}
+
+pub fn fallback(s: &str) -> u16 {
+    s.parse().unwrap_or(8080)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parses() {
+        assert_eq!(super::parse_port("80"), 80);
+        "1".parse::<u8>().unwrap();
+    }
+}
@@ -1,99 +1,354 @@
 //! Binary discovery with graceful fallbacks
 
 use colored::*;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::SystemTime;
 use tokio::process::Command;
-use tracing::warn;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use crate::{cli::TargetKind, output, progress::Progress};
+
+/// How benchmarked binaries are built
+#[derive(Debug, Clone)]
+pub struct BuildOptions {
+    /// Rebuild a given executable from a Cargo `target/` directory when the
+    /// project's sources are newer than it
+    pub rebuild_stale: bool,
+    /// Cargo features to enable
+    pub features: Vec<String>,
+    /// Enable every feature of the project
+    pub all_features: bool,
+    /// Disable the project's default features
+    pub no_default_features: bool,
+    /// Cargo profile to build with
+    pub profile: String,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            rebuild_stale: true,
+            features: Vec::new(),
+            all_features: false,
+            no_default_features: false,
+            profile: "release".to_string(),
+        }
+    }
+}
+
+impl BuildOptions {
+    /// Profile and feature arguments for `cargo build`
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = vec!["--profile".to_string(), self.profile.clone()];
+        args.extend(self.feature_args());
+        args
+    }
+
+    /// Feature selection arguments for `cargo build`
+    pub fn feature_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.all_features {
+            args.push("--all-features".to_string());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        args
+    }
+
+    /// Directory under the [`target_dir`] that cargo writes the profile's
+    /// output to
+    pub fn profile_dir(&self) -> &str {
+        match self.profile.as_str() {
+            "dev" | "test" => "debug",
+            "bench" => "release",
+            profile => profile,
+        }
+    }
+
+    /// Check for combinations cargo would reject or silently ignore
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.profile.is_empty() || self.profile.contains(char::is_whitespace) {
+            anyhow::bail!("invalid cargo profile name '{}'", self.profile);
+        }
+        if self.all_features && (!self.features.is_empty() || self.no_default_features) {
+            anyhow::bail!(
+                "--all-features cannot be combined with --features or --no-default-features"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Warning recorded when the `cargo` program itself cannot be found
+pub const CARGO_NOT_FOUND_WARNING: &str =
+    "cargo not found on PATH; falling back to static analysis";
+
+/// Problems met while locating and building the binaries to benchmark
+#[derive(Debug, Default)]
+pub struct BuildLog {
+    /// Failed builds and missing targets, for the score's warnings
+    pub warnings: Vec<String>,
+    /// Distinct compiler warnings emitted by the cargo builds that ran;
+    /// `None` when nothing was built
+    pub compiler_warnings: Option<u32>,
+    /// `cargo` could not be found, so nothing was built
+    pub cargo_missing: bool,
+    /// Rendered diagnostics already counted and printed, so a later build
+    /// replaying them (e.g. the examples fallback) does not count them twice
+    seen_diagnostics: HashSet<String>,
+}
+
+impl BuildLog {
+    /// Record that cargo could not be started at all.
+    ///
+    /// A missing `cargo` program is reported once as
+    /// [`CARGO_NOT_FOUND_WARNING`] rather than as a failed build.
+    fn cargo_not_run(&mut self, e: &std::io::Error) {
+        self.cargo_not_run_with(e, CARGO_NOT_FOUND_WARNING);
+    }
+
+    /// Like [`Self::cargo_not_run`], reporting a missing `cargo` as `missing`
+    fn cargo_not_run_with(&mut self, e: &std::io::Error, missing: &str) {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            if !self.cargo_missing {
+                warn!("{}", missing);
+                self.warnings.push(missing.to_string());
+            }
+            self.cargo_missing = true;
+        } else {
+            warn!("Failed to run cargo build: {}", e);
+            self.warnings
+                .push(format!("Failed to run cargo build: {e}"));
+        }
+    }
+
+    /// Warnings to attach to the score, including the compiler warning count
+    pub fn score_warnings(&self) -> Vec<String> {
+        let mut warnings = self.warnings.clone();
+        if let Some(n @ 1..) = self.compiler_warnings {
+            warnings.push(format!("Build emitted {n} compiler warnings"));
+        }
+        warnings
+    }
+}
+
+/// A Cargo target selected by kind and name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoTarget {
+    /// Target kind
+    pub kind: TargetKind,
+    /// Target name, e.g. the file stem under `examples/` or `benches/`
+    pub name: String,
+}
+
+impl CargoTarget {
+    /// Target selection arguments for `cargo build`
+    pub fn cargo_args(&self) -> Vec<String> {
+        vec![self.kind.cargo_flag().to_string(), self.name.clone()]
+    }
+}
+
+/// Outcome of a cargo build
+struct CargoBuild {
+    status: ExitStatus,
+    /// `(target name, path)` of every executable cargo reported
+    executables: Vec<(String, PathBuf)>,
+}
+
+/// Run a cargo build in `dir`, counting its compiler warnings into `log`.
+///
+/// Diagnostics an earlier build of `log` already reported are neither counted
+/// nor printed again.
+///
+/// Diagnostics and artifacts are read from `--message-format=json`;
+/// diagnostics are printed to stderr as cargo would. While the spinner is
+/// drawn, cargo's own output is captured so it does not fight with the
+/// spinner; it is replayed only if the build fails.
+async fn cargo_build(
+    mut cmd: Command,
+    dir: &Path,
+    progress: &Progress,
+    log: &mut BuildLog,
+) -> std::io::Result<CargoBuild> {
+    cmd.current_dir(dir)
+        .kill_on_drop(true)
+        .arg(if output::color_enabled() {
+            "--message-format=json-diagnostic-rendered-ansi"
+        } else {
+            "--message-format=json"
+        })
+        .stdout(Stdio::piped())
+        .stderr(if progress.is_active() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        });
+    let output = cmd.output().await?;
+    let messages = String::from_utf8_lossy(&output.stdout);
+    let mut diagnostics = compiler_diagnostics(&messages);
+    diagnostics.retain(|(_, rendered)| log.seen_diagnostics.insert(rendered.clone()));
+    let warnings = diagnostics
+        .iter()
+        .filter(|(level, _)| level == "warning")
+        .count();
+    *log.compiler_warnings.get_or_insert(0) += warnings as u32;
+
+    if !progress.is_active() {
+        for (_, rendered) in &diagnostics {
+            eprint!("{rendered}");
+        }
+    } else if !output.status.success() {
+        progress.println(String::from_utf8_lossy(&output.stderr));
+        for (_, rendered) in diagnostics.iter().filter(|(level, _)| level == "error") {
+            progress.println(rendered);
+        }
+    }
+    Ok(CargoBuild {
+        status: output.status,
+        executables: executables(&messages),
+    })
+}
+
+/// `(target name, path)` of the executables in cargo's
+/// `--message-format=json` output
+fn executables(messages: &str) -> Vec<(String, PathBuf)> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter_map(|message| {
+            Some((
+                message["target"]["name"].as_str()?.to_string(),
+                PathBuf::from(message["executable"].as_str()?),
+            ))
+        })
+        .collect()
+}
+
+/// `(level, rendered text)` of the compiler diagnostics in cargo's
+/// `--message-format=json` output
+fn compiler_diagnostics(messages: &str) -> Vec<(String, String)> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .filter_map(|message| {
+            let diagnostic = &message["message"];
+            Some((
+                diagnostic["level"].as_str()?.to_string(),
+                diagnostic["rendered"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            ))
+        })
+        .collect()
+}
 
 /// Enhanced binary discovery with graceful fallbacks
+///
+/// Built binaries are the executables cargo reports, wherever its target
+/// directory is. Build failures are appended to `log`.
 pub async fn find_or_build_binary(
     input_path: &Path,
     bin: &Option<String>,
     is_cargo_project: bool,
+    build: &BuildOptions,
+    progress: &Progress,
+    log: &mut BuildLog,
 ) -> Option<PathBuf> {
     // 1. Check if user provided a direct binary path
     if let Some(b) = bin.as_ref() {
         let p = Path::new(b);
         if p.is_file() && is_executable(p) {
+            rebuild_if_stale(p, build, progress, log).await;
             return Some(p.to_path_buf());
         }
     }
 
     // 2. If it's already an executable file
     if input_path.is_file() && is_executable(input_path) {
+        rebuild_if_stale(input_path, build, progress, log).await;
         return Some(input_path.to_path_buf());
     }
 
     // 3. Try to build if it's a Cargo project
     if is_cargo_project && input_path.is_dir() {
-        println!("{}", "Attempting to build Cargo project...".bright_cyan());
+        progress.println(
+            "Attempting to build Cargo project..."
+                .bright_cyan()
+                .to_string(),
+        );
+        progress.phase(format!(
+            "Building (cargo build --profile {})",
+            build.profile
+        ));
 
         let mut cmd = Command::new("cargo");
-        cmd.arg("build").arg("--release");
+        cmd.arg("build").args(build.cargo_args());
         if let Some(bin_name) = bin {
             if !Path::new(bin_name).exists() {
                 cmd.arg("--bin").arg(bin_name);
             }
         }
 
-        match cmd.current_dir(input_path).status().await {
-            Ok(status) if status.success() => {
-                // Try to find the built binary
-                let target_dir = input_path.join("target").join("release");
-
-                if let Some(bin_name) = bin {
-                    let candidate = target_dir.join(bin_name);
-                    if candidate.exists() && is_executable(&candidate) {
-                        return Some(candidate);
-                    }
-                }
-
-                // Scan for any executable
-                if let Ok(entries) = std::fs::read_dir(&target_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() && is_executable(&path) {
-                            return Some(path);
-                        }
-                    }
+        match cargo_build(cmd, input_path, progress, log).await {
+            Ok(built) if built.status.success() => {
+                // The named binary, or else any executable cargo built
+                let named = bin.as_ref().and_then(|bin_name| {
+                    built.executables.iter().find(|(name, _)| name == bin_name)
+                });
+                if let Some((_, path)) = named.or(built.executables.first()) {
+                    return Some(path.clone());
                 }
             }
             Ok(_) => {
                 warn!("Cargo build failed - continuing with static analysis");
-                println!("{}", "Build failed - using static analysis".yellow());
+                progress.println("Build failed - using static analysis".yellow().to_string());
+                log.warnings.push("Cargo build failed".to_string());
             }
             Err(e) => {
-                warn!(
-                    "Failed to run cargo build: {} - continuing with static analysis",
-                    e
-                );
-                println!("{}", "Build failed - using static analysis".yellow());
+                log.cargo_not_run(&e);
+                let message = if log.cargo_missing {
+                    CARGO_NOT_FOUND_WARNING
+                } else {
+                    "Build failed - using static analysis"
+                };
+                progress.println(message.yellow().to_string());
             }
         }
     }
 
     // 4. Try to find examples or tests as fallback
-    if is_cargo_project && input_path.is_dir() {
+    if is_cargo_project && input_path.is_dir() && !log.cargo_missing {
         // Try building examples
         let examples_dir = input_path.join("examples");
         if examples_dir.exists() {
-            println!("{}", "Attempting to build examples...".bright_cyan());
+            progress.println("Attempting to build examples...".bright_cyan().to_string());
+            progress.phase(format!(
+                "Building examples (cargo build --examples --profile {})",
+                build.profile
+            ));
             let mut cmd = Command::new("cargo");
-            cmd.arg("build").arg("--examples").arg("--release");
-
-            if let Ok(status) = cmd.current_dir(input_path).status().await {
-                if status.success() {
-                    let target_examples =
-                        input_path.join("target").join("release").join("examples");
-                    if let Ok(entries) = std::fs::read_dir(&target_examples) {
-                        for entry in entries.flatten() {
-                            let path = entry.path();
-                            if path.is_file() && is_executable(&path) {
-                                println!("{}", "Using example binary for analysis".yellow());
-                                return Some(path);
-                            }
-                        }
-                    }
+            cmd.arg("build").arg("--examples").args(build.cargo_args());
+
+            if let Ok(built) = cargo_build(cmd, input_path, progress, log).await {
+                if let Some((_, path)) = built
+                    .executables
+                    .into_iter()
+                    .next()
+                    .filter(|_| built.status.success())
+                {
+                    progress.println("Using example binary for analysis".yellow().to_string());
+                    return Some(path);
                 }
             }
         }
@@ -102,6 +357,526 @@ pub async fn find_or_build_binary(
     None
 }
 
+/// Names of the binary targets declared by the Cargo project at `project`.
+///
+/// Covers the implicit `src/main.rs` target, `src/bin/*.rs`,
+/// `src/bin/*/main.rs` and explicit `[[bin]]` sections.
+pub fn bin_targets(project: &Path) -> Vec<String> {
+    let manifest = std::fs::read_to_string(project.join("Cargo.toml"))
+        .ok()
+        .and_then(|c| c.parse::<toml::Value>().ok());
+    let mut names = Vec::new();
+    let mut push = |name: String| {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    };
+
+    if let Some(bins) = manifest
+        .as_ref()
+        .and_then(|m| m.get("bin"))
+        .and_then(|b| b.as_array())
+    {
+        for name in bins
+            .iter()
+            .filter_map(|b| b.get("name").and_then(|n| n.as_str()))
+        {
+            push(name.to_string());
+        }
+    }
+
+    if project.join("src").join("main.rs").is_file() {
+        if let Some(name) = manifest
+            .as_ref()
+            .and_then(|m| m.get("package"))
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            push(name.to_string());
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(project.join("src").join("bin")) {
+        let mut found: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("rs") {
+                    path.file_stem().map(|s| s.to_string_lossy().into_owned())
+                } else if path.join("main.rs").is_file() {
+                    path.file_name().map(|s| s.to_string_lossy().into_owned())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        found.sort();
+        found.into_iter().for_each(&mut push);
+    }
+
+    names
+}
+
+/// Target directory of the Cargo project at `project`, honouring
+/// `CARGO_TARGET_DIR` and `build.target-dir` through `cargo metadata`.
+///
+/// Falls back to `project/target` when cargo is unavailable or fails.
+pub async fn target_dir(project: &Path) -> PathBuf {
+    let output = Command::new("cargo")
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .current_dir(project)
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    output
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+            Some(PathBuf::from(metadata["target_directory"].as_str()?))
+        })
+        .unwrap_or_else(|| project.join("target"))
+}
+
+/// A binary target reported by [`list_bin_targets`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct BinTarget {
+    /// Package declaring the target
+    pub package: String,
+    /// Target name, as passed to `--bin`
+    pub name: String,
+}
+
+/// Binary targets of the Cargo project at `project`, and of every other
+/// member when it belongs to a workspace, from `cargo metadata`.
+///
+/// When cargo is unavailable or fails, falls back to the [`bin_targets`] of
+/// `project` alone.
+pub async fn list_bin_targets(project: &Path) -> anyhow::Result<Vec<BinTarget>> {
+    let manifest = project.join("Cargo.toml");
+    if !manifest.is_file() {
+        anyhow::bail!("{} is not a Cargo project", project.display());
+    }
+    let output = Command::new("cargo")
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--no-deps",
+            "--manifest-path",
+        ])
+        .arg(&manifest)
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    match output {
+        Ok(output) if output.status.success() => {
+            if let Some(bins) = metadata_bin_targets(&String::from_utf8_lossy(&output.stdout)) {
+                return Ok(bins);
+            }
+            warn!("Unreadable cargo metadata output - reading the manifest instead");
+        }
+        Ok(output) => warn!(
+            "cargo metadata failed ({}) - reading the manifest instead",
+            output.status
+        ),
+        Err(e) => warn!(
+            "Could not run cargo metadata: {} - reading the manifest instead",
+            e
+        ),
+    }
+    let package = std::fs::read_to_string(&manifest)
+        .ok()
+        .and_then(|c| c.parse::<toml::Value>().ok())
+        .and_then(|m| Some(m.get("package")?.get("name")?.as_str()?.to_string()))
+        .unwrap_or_default();
+    Ok(bin_targets(project)
+        .into_iter()
+        .map(|name| BinTarget {
+            package: package.clone(),
+            name,
+        })
+        .collect())
+}
+
+/// Binary targets in the JSON printed by `cargo metadata`, sorted by
+/// package and name
+fn metadata_bin_targets(json: &str) -> Option<Vec<BinTarget>> {
+    let metadata: serde_json::Value = serde_json::from_str(json).ok()?;
+    let mut bins = Vec::new();
+    for package in metadata.get("packages")?.as_array()? {
+        let package_name = package.get("name")?.as_str()?;
+        for target in package.get("targets")?.as_array()? {
+            let is_bin = target
+                .get("kind")
+                .and_then(|k| k.as_array())
+                .is_some_and(|kinds| kinds.iter().any(|k| k == "bin"));
+            if is_bin {
+                bins.push(BinTarget {
+                    package: package_name.to_string(),
+                    name: target.get("name")?.as_str()?.to_string(),
+                });
+            }
+        }
+    }
+    bins.sort();
+    Some(bins)
+}
+
+/// Build and locate several binary targets at once.
+///
+/// `bins` may mix Cargo target names and paths to existing executables; with
+/// `all_bins` every target reported by [`bin_targets`] is included. Targets
+/// that fail to build or cannot be located are skipped with a warning, which
+/// is also appended to `log`.
+pub async fn find_or_build_binaries(
+    input_path: &Path,
+    bins: &[String],
+    all_bins: bool,
+    build: &BuildOptions,
+    progress: &Progress,
+    log: &mut BuildLog,
+) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut names = Vec::new();
+    for bin in bins {
+        let p = Path::new(bin);
+        if p.is_file() && is_executable(p) {
+            rebuild_if_stale(p, build, progress, log).await;
+            found.push(p.to_path_buf());
+        } else if !names.contains(bin) {
+            names.push(bin.clone());
+        }
+    }
+    if all_bins {
+        for name in bin_targets(input_path) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    if names.is_empty() || !input_path.join("Cargo.toml").is_file() {
+        return found;
+    }
+
+    progress.println(
+        format!("Building {} binary targets...", names.len())
+            .bright_cyan()
+            .to_string(),
+    );
+    progress.phase(format!(
+        "Building {} (cargo build --profile {})",
+        names.join(", "),
+        build.profile
+    ));
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build").args(build.cargo_args());
+    for name in &names {
+        cmd.arg("--bin").arg(name);
+    }
+
+    match cargo_build(cmd, input_path, progress, log).await {
+        Ok(built) if built.status.success() => {
+            for name in &names {
+                match built.executables.iter().find(|(built, _)| built == name) {
+                    Some((_, path)) => found.push(path.clone()),
+                    None => {
+                        warn!("Cargo reported no executable for target '{}'", name);
+                        log.warnings
+                            .push(format!("Built target '{name}' not found"));
+                    }
+                }
+            }
+        }
+        Ok(_) => {
+            warn!("Cargo build of selected targets failed");
+            log.warnings
+                .push(format!("Cargo build of {} failed", names.join(", ")));
+        }
+        Err(e) => {
+            log.cargo_not_run(&e);
+        }
+    }
+
+    found
+}
+
+/// Build exactly `target` of the Cargo project at `input_path` and return its
+/// executable, as reported by cargo.
+///
+/// Unlike [`find_or_build_binary`] nothing is scanned for or guessed: a
+/// failed build or a target without an executable is appended to `log` and
+/// yields `None`.
+pub async fn build_target(
+    input_path: &Path,
+    target: &CargoTarget,
+    build: &BuildOptions,
+    progress: &Progress,
+    log: &mut BuildLog,
+) -> Option<PathBuf> {
+    let args = target.cargo_args();
+    progress.phase(format!(
+        "Building (cargo build --profile {} {})",
+        build.profile,
+        args.join(" ")
+    ));
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build").args(build.cargo_args()).args(&args);
+
+    match cargo_build(cmd, input_path, progress, log).await {
+        Ok(built) if built.status.success() => {
+            let executable = built
+                .executables
+                .into_iter()
+                .find(|(name, _)| *name == target.name)
+                .map(|(_, path)| path);
+            if executable.is_none() {
+                warn!("Cargo reported no executable for {}", args.join(" "));
+                log.warnings
+                    .push(format!("No executable built for {}", args.join(" ")));
+            }
+            executable
+        }
+        Ok(_) => {
+            warn!("Cargo build of {} failed", args.join(" "));
+            log.warnings
+                .push(format!("Cargo build of {} failed", args.join(" ")));
+            None
+        }
+        Err(e) => {
+            log.cargo_not_run(&e);
+            None
+        }
+    }
+}
+
+/// What binary discovery would do for one target, without doing it
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BinaryPlan {
+    /// Benchmark an existing executable as is
+    Use {
+        /// Executable path
+        path: PathBuf,
+    },
+    /// Rebuild an out-of-date executable, then benchmark it
+    Rebuild {
+        /// Executable path
+        path: PathBuf,
+    },
+    /// Run a cargo build and benchmark its output
+    Build {
+        /// The cargo command line
+        command: String,
+    },
+}
+
+impl std::fmt::Display for BinaryPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Use { path } => write!(f, "use existing executable {}", path.display()),
+            Self::Rebuild { path } => write!(f, "rebuild stale executable {}", path.display()),
+            Self::Build { command } => write!(f, "build with `{command}`"),
+        }
+    }
+}
+
+/// Plan the work of [`find_or_build_binary`] / [`find_or_build_binaries`] /
+/// [`build_target`] for the same arguments without building anything.
+///
+/// An empty plan means no binary is available and scoring falls back to
+/// static analysis. A planned build may still fail at run time.
+pub fn plan_binaries(
+    input_path: &Path,
+    bins: &[String],
+    all_bins: bool,
+    target: Option<&CargoTarget>,
+    is_cargo_project: bool,
+    build: &BuildOptions,
+) -> Vec<BinaryPlan> {
+    let existing = |p: &Path| {
+        let path = p.to_path_buf();
+        if build.rebuild_stale && stale_project(p).is_some() {
+            BinaryPlan::Rebuild { path }
+        } else {
+            BinaryPlan::Use { path }
+        }
+    };
+    let cargo_build = |extra: &[String]| {
+        let mut args = vec!["cargo".to_string(), "build".to_string()];
+        args.extend(build.cargo_args());
+        args.extend_from_slice(extra);
+        BinaryPlan::Build {
+            command: args.join(" "),
+        }
+    };
+
+    if let Some(target) = target {
+        return if is_cargo_project && input_path.is_dir() {
+            vec![cargo_build(&target.cargo_args())]
+        } else {
+            Vec::new()
+        };
+    }
+    if bins.len() > 1 || all_bins {
+        let mut plan = Vec::new();
+        let mut names = Vec::new();
+        for bin in bins {
+            let p = Path::new(bin);
+            if p.is_file() && is_executable(p) {
+                plan.push(existing(p));
+            } else if !names.contains(bin) {
+                names.push(bin.clone());
+            }
+        }
+        if all_bins {
+            for name in bin_targets(input_path) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        if !names.is_empty() && input_path.join("Cargo.toml").is_file() {
+            let args: Vec<String> = names
+                .iter()
+                .flat_map(|n| ["--bin".to_string(), n.clone()])
+                .collect();
+            plan.push(cargo_build(&args));
+        }
+        return plan;
+    }
+
+    if let Some(p) = bins.first().map(Path::new) {
+        if p.is_file() && is_executable(p) {
+            return vec![existing(p)];
+        }
+    }
+    if input_path.is_file() && is_executable(input_path) {
+        return vec![existing(input_path)];
+    }
+    if is_cargo_project && input_path.is_dir() {
+        let args = match bins.first() {
+            Some(name) if !Path::new(name).exists() => vec!["--bin".to_string(), name.clone()],
+            _ => Vec::new(),
+        };
+        return vec![cargo_build(&args)];
+    }
+    Vec::new()
+}
+
+/// Cargo project owning `binary` (built into its `target/` directory) when
+/// any of the project's sources changed after the binary was built.
+pub fn stale_project(binary: &Path) -> Option<PathBuf> {
+    let binary = binary.canonicalize().ok()?;
+    let project = binary
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|n| n == "target"))?
+        .parent()?;
+    if !project.join("Cargo.toml").is_file() {
+        return None;
+    }
+    let built = binary.metadata().and_then(|m| m.modified()).ok()?;
+    let newest = newest_source_mtime(project)?;
+    (newest > built).then(|| project.to_path_buf())
+}
+
+/// Latest modification time of the `.rs` files and manifests under `project`
+fn newest_source_mtime(project: &Path) -> Option<SystemTime> {
+    WalkDir::new(project)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0 || {
+                let name = e.file_name().to_string_lossy();
+                name != "target" && !name.starts_with('.')
+            }
+        })
+        .flatten()
+        .filter(|e| {
+            e.file_type().is_file()
+                && (e.path().extension().is_some_and(|ext| ext == "rs")
+                    || e.file_name() == "Cargo.toml")
+        })
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Rebuild `binary` with cargo when [`stale_project`] finds newer sources.
+///
+/// The build profile (and `--example`) is derived from where the binary
+/// lives under `target/`; features come from `build`. A failed rebuild is
+/// appended to `log` and the old binary is used.
+async fn rebuild_if_stale(
+    binary: &Path,
+    build: &BuildOptions,
+    progress: &Progress,
+    log: &mut BuildLog,
+) {
+    if !build.rebuild_stale {
+        return;
+    }
+    let Some(project) = stale_project(binary) else {
+        return;
+    };
+    let Some(name) = binary.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+        return;
+    };
+    info!(
+        "{} is older than the sources of {} - rebuilding",
+        binary.display(),
+        project.display()
+    );
+    progress.println(
+        format!("{name} is out of date - rebuilding...")
+            .bright_cyan()
+            .to_string(),
+    );
+    progress.phase(format!("Rebuilding {name}"));
+
+    let mut dir = binary.parent();
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build");
+    if dir
+        .and_then(|d| d.file_name())
+        .is_some_and(|n| n == "examples")
+    {
+        cmd.arg("--example").arg(&name);
+        dir = dir.and_then(Path::parent);
+    } else {
+        cmd.arg("--bin").arg(&name);
+    }
+    match dir.and_then(|d| d.file_name()).and_then(|n| n.to_str()) {
+        Some("debug") => {}
+        Some("release") => {
+            cmd.arg("--release");
+        }
+        Some(profile) => {
+            cmd.arg("--profile").arg(profile);
+        }
+        None => {}
+    }
+    cmd.args(build.feature_args());
+
+    match cargo_build(cmd, &project, progress, log).await {
+        Ok(built) if built.status.success() => {}
+        Ok(_) => {
+            warn!("Rebuilding {} failed - benchmarking the old binary", name);
+            log.warnings
+                .push(format!("Rebuilding stale binary {name} failed"));
+        }
+        Err(e) => {
+            // The old binary is still benchmarked, so nothing falls back to
+            // static analysis
+            log.cargo_not_run_with(
+                &e,
+                &format!("cargo not found on PATH; benchmarking stale binary {name} as is"),
+            );
+        }
+    }
+}
+
 /// Check if a path is an executable file
 #[cfg(unix)]
 pub fn is_executable(path: &Path) -> bool {
@@ -119,3 +894,351 @@ pub fn is_executable(path: &Path) -> bool {
 pub fn is_executable(path: &Path) -> bool {
     path.extension().and_then(|e| e.to_str()) == Some("exe")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_all_bin_targets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src/bin/server")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"multi\"\nversion = \"0.1.0\"\n\n\
+             [[bin]]\nname = \"custom\"\npath = \"tools/custom.rs\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/bin/client.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/bin/server/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/bin/README.md"), "not a target").unwrap();
+
+        assert_eq!(bin_targets(root), ["custom", "multi", "client", "server"]);
+    }
+
+    #[tokio::test]
+    async fn lists_bin_targets_of_every_workspace_member() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multi_bin");
+        let bin = |package: &str, name: &str| BinTarget {
+            package: package.into(),
+            name: name.into(),
+        };
+        let expected = [
+            bin("server", "migrate"),
+            bin("server", "server"),
+            bin("tools", "crab-lint"),
+        ];
+        assert_eq!(list_bin_targets(&fixture).await.unwrap(), expected);
+        // A member lists the whole workspace
+        assert_eq!(
+            list_bin_targets(&fixture.join("tools")).await.unwrap(),
+            expected
+        );
+        assert!(list_bin_targets(&fixture.join("tools/src")).await.is_err());
+    }
+
+    #[test]
+    fn reads_bin_targets_from_cargo_metadata() {
+        let json = r#"{"packages": [
+            {"name": "app", "targets": [
+                {"name": "app", "kind": ["lib"]},
+                {"name": "cli", "kind": ["bin"]},
+                {"name": "bench", "kind": ["bench"]}
+            ]}
+        ]}"#;
+        assert_eq!(
+            metadata_bin_targets(json).unwrap(),
+            [BinTarget {
+                package: "app".into(),
+                name: "cli".into(),
+            }]
+        );
+        assert_eq!(metadata_bin_targets("not json"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn explicit_executables_are_selected_without_building() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bins = vec!["/bin/true".to_string(), "/bin/false".to_string()];
+        let found = find_or_build_binaries(
+            tmp.path(),
+            &bins,
+            false,
+            &BuildOptions::default(),
+            &Progress::hidden(),
+            &mut BuildLog::default(),
+        )
+        .await;
+        assert_eq!(
+            found,
+            [PathBuf::from("/bin/true"), PathBuf::from("/bin/false")]
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_compiler_warnings_of_the_build() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            include_str!("../tests/fixtures/warning_crate/Cargo.toml"),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/main.rs"),
+            include_str!("../tests/fixtures/warning_crate/src/main.rs"),
+        )
+        .unwrap();
+        let build = BuildOptions {
+            profile: "dev".into(),
+            ..Default::default()
+        };
+
+        let mut log = BuildLog::default();
+        let found =
+            find_or_build_binary(root, &None, true, &build, &Progress::hidden(), &mut log).await;
+        assert!(found.is_some_and(|p| p.file_stem().is_some_and(|s| s == "warning_crate")));
+        assert_eq!(log.compiler_warnings, Some(2));
+        assert_eq!(log.score_warnings(), ["Build emitted 2 compiler warnings"]);
+
+        // Cargo replays the warnings of an up-to-date build
+        let mut log = BuildLog::default();
+        find_or_build_binary(root, &None, true, &build, &Progress::hidden(), &mut log).await;
+        assert_eq!(log.compiler_warnings, Some(2));
+    }
+
+    #[tokio::test]
+    async fn examples_fallback_counts_library_warnings_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("examples")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"lib_with_example\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn never_called() {}\n").unwrap();
+        std::fs::write(root.join("examples/demo.rs"), "fn main() {}\n").unwrap();
+        let build = BuildOptions {
+            profile: "dev".into(),
+            ..Default::default()
+        };
+
+        // The library builds without an executable, then again with the example
+        let mut log = BuildLog::default();
+        let found =
+            find_or_build_binary(root, &None, true, &build, &Progress::hidden(), &mut log).await;
+        assert!(found.is_some_and(|p| p.file_stem().is_some_and(|s| s == "demo")));
+        assert_eq!(log.compiler_warnings, Some(1));
+    }
+
+    #[tokio::test]
+    async fn builds_into_the_configured_target_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join(".cargo")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            include_str!("../tests/fixtures/warning_crate/Cargo.toml"),
+        )
+        .unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(
+            root.join(".cargo/config.toml"),
+            "[build]\ntarget-dir = \"out\"\n",
+        )
+        .unwrap();
+        let build = BuildOptions {
+            profile: "dev".into(),
+            ..Default::default()
+        };
+
+        let out = root.canonicalize().unwrap().join("out");
+        assert_eq!(target_dir(root).await.canonicalize().ok(), None);
+        let mut log = BuildLog::default();
+        let found =
+            find_or_build_binary(root, &None, true, &build, &Progress::hidden(), &mut log).await;
+        let found = found.unwrap().canonicalize().unwrap();
+        assert!(found.starts_with(&out), "{}", found.display());
+        assert_eq!(target_dir(root).await.canonicalize().unwrap(), out);
+        assert!(!root.join("target").exists());
+    }
+
+    #[tokio::test]
+    async fn builds_the_selected_target_of_each_kind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for file in [
+            "Cargo.toml",
+            "src/main.rs",
+            "examples/demo.rs",
+            "benches/throughput.rs",
+            "tests/smoke.rs",
+        ] {
+            let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures/targets_crate")
+                .join(file);
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::copy(fixture, root.join(file)).unwrap();
+        }
+        let build = BuildOptions {
+            profile: "dev".into(),
+            ..Default::default()
+        };
+
+        for (kind, name, dir) in [
+            (TargetKind::Bin, "targets_crate", "debug"),
+            (TargetKind::Example, "demo", "examples"),
+            (TargetKind::Bench, "throughput", "deps"),
+            (TargetKind::Test, "smoke", "deps"),
+        ] {
+            let target = CargoTarget {
+                kind,
+                name: name.into(),
+            };
+            let mut log = BuildLog::default();
+            let exe = build_target(root, &target, &build, &Progress::hidden(), &mut log)
+                .await
+                .unwrap_or_else(|| panic!("{kind:?} {name}: {:?}", log.warnings));
+            assert!(is_executable(&exe), "{}", exe.display());
+            assert!(exe.parent().unwrap().ends_with(dir), "{}", exe.display());
+            let stem = exe.file_stem().unwrap().to_string_lossy();
+            assert!(
+                stem == name || stem.starts_with(&format!("{name}-")),
+                "{stem}"
+            );
+            assert_eq!(
+                plan_binaries(root, &[], false, Some(&target), true, &build),
+                [BinaryPlan::Build {
+                    command: format!("cargo build --profile dev {} {name}", kind.cargo_flag())
+                }]
+            );
+        }
+
+        let missing = CargoTarget {
+            kind: TargetKind::Example,
+            name: "nope".into(),
+        };
+        let mut log = BuildLog::default();
+        assert!(
+            build_target(root, &missing, &build, &Progress::hidden(), &mut log)
+                .await
+                .is_none()
+        );
+        assert_eq!(log.warnings, ["Cargo build of --example nope failed"]);
+    }
+
+    #[test]
+    fn binaries_older_than_sources_are_stale() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/release")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        let binary = root.join("target/release/app");
+        std::fs::write(&binary, "").unwrap();
+
+        let set_mtime = |path: &Path, time: SystemTime| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        let now = SystemTime::now();
+        let hour = std::time::Duration::from_secs(3600);
+
+        // Source edited after the build: rebuild needed
+        set_mtime(&binary, now - hour);
+        set_mtime(&root.join("src/main.rs"), now);
+        set_mtime(&root.join("Cargo.toml"), now - 2 * hour);
+        assert_eq!(stale_project(&binary), Some(root.canonicalize().unwrap()));
+
+        // Fresh build: nothing to do
+        set_mtime(&binary, now + hour);
+        assert_eq!(stale_project(&binary), None);
+
+        // Executables outside a Cargo target directory are never rebuilt
+        assert_eq!(stale_project(&root.join("src/main.rs")), None);
+    }
+
+    #[test]
+    fn build_options_map_to_cargo_arguments() {
+        let build = BuildOptions {
+            features: vec!["simd".into(), "tls".into()],
+            no_default_features: true,
+            profile: "dev".into(),
+            ..Default::default()
+        };
+        build.validate().unwrap();
+        assert_eq!(
+            build.cargo_args(),
+            [
+                "--profile",
+                "dev",
+                "--no-default-features",
+                "--features",
+                "simd,tls"
+            ]
+        );
+        assert_eq!(build.profile_dir(), "debug");
+
+        let defaults = BuildOptions::default();
+        assert_eq!(defaults.cargo_args(), ["--profile", "release"]);
+        assert_eq!(defaults.profile_dir(), "release");
+
+        let conflicting = BuildOptions {
+            all_features: true,
+            features: vec!["simd".into()],
+            ..Default::default()
+        };
+        assert!(conflicting.validate().is_err());
+        let unnamed = BuildOptions {
+            profile: " ".into(),
+            ..Default::default()
+        };
+        assert!(unnamed.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plans_builds_without_running_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src/bin")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/bin/tool.rs"), "fn main() {}").unwrap();
+        let build = BuildOptions::default();
+
+        assert_eq!(
+            plan_binaries(root, &[], false, None, true, &build),
+            [BinaryPlan::Build {
+                command: "cargo build --profile release".into()
+            }]
+        );
+        assert_eq!(
+            plan_binaries(root, &["/bin/true".into()], true, None, true, &build),
+            [
+                BinaryPlan::Use {
+                    path: "/bin/true".into()
+                },
+                BinaryPlan::Build {
+                    command: "cargo build --profile release --bin app --bin tool".into()
+                },
+            ]
+        );
+        assert!(
+            plan_binaries(&root.join("src/main.rs"), &[], false, None, false, &build).is_empty()
+        );
+        assert!(!root.join("target").exists());
+    }
+}
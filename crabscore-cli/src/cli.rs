@@ -1,6 +1,7 @@
 //! CLI argument parser for CrabScore
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use crabscore_core::IndustryProfile;
 
 /// CrabScore – The Rust Efficiency Standard CLI
 #[derive(Parser, Debug)]
@@ -14,37 +15,555 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
+    /// Disable colored output (also honored via the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
     /// Subcommands
     #[command(subcommand)]
     pub command: Commands,
 }
 
 /// Available subcommands
+// Parsed once per process, so the size of the `Score` variant does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Calculate a CrabScore for the current project
     Score {
-        /// Path to the project or binary to score.
+        /// Path to the project or binary to score, `-` to score a single
+        /// source file read from stdin, or a glob (e.g. `'crates/*'`) to score
+        /// every matching directory as NDJSON like `crabscore batch`.
         #[clap(value_parser, default_value = ".")]
         path: String,
-        /// Specific binary name or path to benchmark (for Cargo projects).
+        /// Specific binary names or paths to benchmark (for Cargo projects).
         #[clap(
             long,
             value_name = "BIN",
-            help = "Name (Cargo bin target) or path of executable to benchmark"
+            help = "Name (Cargo bin target) or path of executable to benchmark; repeat to benchmark several"
+        )]
+        bin: Vec<String>,
+        /// Benchmark every binary target of the Cargo project
+        #[arg(long)]
+        all_bins: bool,
+        /// Print the binary targets of the Cargo project (every member of a
+        /// workspace) and exit
+        #[arg(long, conflicts_with_all = ["dry_run", "explain", "quiet"])]
+        list_bins: bool,
+        /// Kind of the Cargo target named by --target-name, built and
+        /// benchmarked instead of discovering a binary
+        #[arg(
+            long,
+            value_enum,
+            value_name = "KIND",
+            requires = "target_name",
+            conflicts_with_all = ["bin", "all_bins"]
+        )]
+        target_kind: Option<TargetKind>,
+        /// Name of the Cargo target to benchmark (see --target-kind)
+        #[arg(long, value_name = "NAME", requires = "target_kind")]
+        target_name: Option<String>,
+        /// How per-target performance is combined when several binaries are benchmarked
+        #[arg(long, value_enum, default_value_t = BinAggregate::Mean)]
+        bin_aggregate: BinAggregate,
+        /// Benchmark a server binary by sending HTTP GET requests to this URL
+        #[arg(long, value_name = "URL")]
+        http_endpoint: Option<String>,
+        /// Number of requests to send in HTTP benchmark mode
+        #[arg(long, default_value_t = 100, requires = "http_endpoint")]
+        http_requests: u32,
+        /// Samples required before tail latency percentiles are reported as reliable
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        min_iterations_for_percentiles: u32,
+        /// Re-run a failed measured iteration up to N times before recording
+        /// it as a failure
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        retries: u32,
+        /// Re-analyze every file instead of reusing cached per-file results
+        #[arg(long)]
+        no_cache: bool,
+        /// Also count lines of files with these extensions (e.g. `rs,c,toml`) toward
+        /// project size; only Rust files are analyzed for functions and tests
+        #[arg(long, value_name = "EXT,EXT,...", value_delimiter = ',')]
+        include_ext: Vec<String>,
+        /// Which lines count toward project size
+        #[arg(long, value_enum, value_name = "MODE", default_value_t = CountMode::Physical)]
+        count_mode: CountMode,
+        /// Analyze files at most N directory levels below the project root
+        /// (1 = only files in the root); deeper files, such as nested vendored
+        /// crates, are not counted
+        #[arg(long, value_name = "N")]
+        max_depth: Option<std::num::NonZeroUsize>,
+        /// Run `cargo bench` and score the Criterion results instead of timing a binary
+        #[arg(long, conflicts_with = "http_endpoint")]
+        use_criterion: bool,
+        /// Also run the binary with each of these input sizes (passed as its last
+        /// argument) and infer its time complexity from how run time grows
+        #[arg(
+            long,
+            value_name = "N,N,...",
+            value_delimiter = ',',
+            conflicts_with_all = ["http_endpoint", "use_criterion"]
         )]
-        bin: Option<String>,
+        input_sizes: Vec<u64>,
+        /// Pipe the contents of this file to the binary's stdin on every benchmark run
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["http_endpoint", "use_criterion"])]
+        stdin_file: Option<std::path::PathBuf>,
+        /// Run each binary once under `strace` (Linux) and derive the network egress
+        /// cost from the bytes it sends; keeps the cost model's figure when tracing
+        /// is unavailable
+        #[arg(long, conflicts_with_all = ["http_endpoint", "use_criterion"])]
+        trace_network: bool,
+        /// Egress price in USD per GB sent, used with --trace-network
+        #[arg(
+            long,
+            value_name = "USD",
+            default_value_t = 0.09,
+            requires = "trace_network"
+        )]
+        egress_price_per_gb: f64,
+        /// Runs per month the traced per-run egress is multiplied by, used with
+        /// --trace-network; the default is an assumed workload, not a measurement
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = crate::command::DEFAULT_RUNS_PER_MONTH,
+            requires = "trace_network"
+        )]
+        runs_per_month: u64,
+        /// Time a built-in reference workload and normalize measured latencies
+        /// by this machine's speed, so scores are comparable across hardware
+        #[arg(long)]
+        calibrate: bool,
+        /// Pin the benchmarked processes to this CPU core (Linux only) to
+        /// reduce noise from scheduler migration
+        #[arg(long, value_name = "CORE", conflicts_with = "use_criterion")]
+        pin_cpu: Option<usize>,
+        /// Industry profile whose weights combine the scores (default: web-services)
+        #[arg(long, value_enum)]
+        profile: Option<Profile>,
+        /// Infer the industry profile from the `keywords` and `categories` in
+        /// Cargo.toml and a `#![no_std]` crate root; an explicit --profile
+        /// takes precedence
+        #[arg(long)]
+        profile_from_cargo_metadata: bool,
+        /// Cargo features to enable when building the project (comma-separated
+        /// or repeated)
+        #[arg(long, value_name = "FEATURES", value_delimiter = ',')]
+        features: Vec<String>,
+        /// Enable all Cargo features when building the project
+        #[arg(long, conflicts_with_all = ["features", "no_default_features"])]
+        all_features: bool,
+        /// Disable the project's default Cargo features when building
+        #[arg(long)]
+        no_default_features: bool,
+        /// Cargo profile to build the project with
+        #[arg(long, value_name = "PROFILE", default_value = "release")]
+        cargo_profile: String,
+        /// Never rebuild a given executable whose Cargo sources are newer than it
+        #[arg(long)]
+        no_rebuild: bool,
+        /// Cost configuration file (default: crabscore-cost.json in the project root)
+        #[arg(long, value_name = "PATH")]
+        cost_file: Option<std::path::PathBuf>,
+        /// Only analyze files changed since this git revision (e.g. `origin/main`);
+        /// the result is marked as a partial score
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
+        /// Exit with an error when the overall score is below this value
+        #[arg(long, value_name = "SCORE")]
+        fail_under: Option<f64>,
+        /// Exit with an error when the performance score is below this value
+        #[arg(long, value_name = "SCORE")]
+        min_performance: Option<f64>,
+        /// Exit with an error when the energy score is below this value
+        #[arg(long, value_name = "SCORE")]
+        min_energy: Option<f64>,
+        /// Exit with an error when the cost score is below this value
+        #[arg(long, value_name = "SCORE")]
+        min_cost: Option<f64>,
+        /// Report what would be built, benchmarked and measured, then exit
+        #[arg(long, conflicts_with = "explain")]
+        dry_run: bool,
+        /// Fail instead of falling back to estimates or defaults when a build,
+        /// a benchmark, the cost file or the safety analysis fails
+        #[arg(long)]
+        strict: bool,
+        /// When a binary is benchmarked, also score the static estimates and
+        /// record how far each dimension was off (under `calibration` in the
+        /// metadata)
+        #[arg(long)]
+        check_estimates: bool,
+        /// Show the change since the last `--baseline-auto` run on this path,
+        /// then record this score in the history as the next baseline
+        #[arg(long, conflicts_with_all = ["dry_run", "list_bins"])]
+        baseline_auto: bool,
+        /// Print how each score was computed instead of the normal report
+        #[arg(long)]
+        explain: bool,
+        /// Most points the complexity bonuses can add (overrides `complexity_cap`
+        /// under `[bonuses]` in the configuration; default 10)
+        #[arg(long, value_name = "POINTS")]
+        max_bonus: Option<f64>,
+        /// p95 latency target in milliseconds: latencies within it score full
+        /// marks and the latency score drops steeply beyond it, instead of the
+        /// fixed curve
+        #[arg(long, value_name = "MS")]
+        latency_sla_ms: Option<f64>,
+        /// Compute only this dimension, skipping the collection of the others
+        /// (which keep their defaults, so the overall score is not meaningful)
+        #[arg(long, value_enum, value_name = "DIMENSION", conflicts_with_all = ["quiet", "dry_run"])]
+        only: Option<Dimension>,
+        /// Also list the N functions with the highest cyclomatic complexity
+        #[arg(long, value_name = "N", conflicts_with_all = ["quiet", "format"])]
+        top_complex: Option<usize>,
+        /// Also show what it takes to reach the next certification level and
+        /// which dimension to improve
+        #[arg(long, conflicts_with_all = ["quiet", "format", "only"])]
+        next_tier: bool,
+        /// Print only the overall score and certification on one line
+        #[arg(short, long, conflicts_with_all = ["explain", "dry_run", "format"])]
+        quiet: bool,
+        /// Output format for the result (default: human; a glob path is
+        /// always scored as NDJSON)
+        #[arg(long, value_enum)]
+        format: Option<ScoreFormat>,
+        /// Keep every float of the JSON or YAML report at full precision
+        /// instead of rounding scores to 2 and latencies to 3 decimals
+        #[arg(long)]
+        raw: bool,
+        /// With a glob path, most directories scored at once (default: the
+        /// number of CPUs)
+        #[arg(long, short = 'j', value_name = "N")]
+        jobs: Option<std::num::NonZeroUsize>,
+    },
+    /// Score many paths, streaming one JSON report per line (NDJSON)
+    Batch {
+        /// File listing one path per line (`-` reads the list from stdin);
+        /// blank lines and lines starting with `#` are ignored
+        paths_file: std::path::PathBuf,
+        /// Most paths scored at once (default: the number of CPUs)
+        #[arg(long, short = 'j', value_name = "N")]
+        jobs: Option<std::num::NonZeroUsize>,
+        /// Keep every float of the reports at full precision instead of
+        /// rounding scores to 2 and latencies to 3 decimals
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Score every member of a Cargo workspace and roll the results up
+    Workspace {
+        /// Path to the workspace root.
+        #[clap(value_parser, default_value = ".")]
+        path: String,
+        /// How member scores are weighted in the aggregate
+        #[arg(long, value_enum, default_value_t = WeightBy::Loc)]
+        weight_by: WeightBy,
+        /// Most members scored at once (default: the number of CPUs)
+        #[arg(long, short = 'j', value_name = "N")]
+        jobs: Option<std::num::NonZeroUsize>,
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
     },
-    /// Print the current version information
     /// Generate / serve reports
     Report {
+        /// Path to the project or binary to report on.
+        #[clap(value_parser, default_value = ".")]
+        path: String,
+        /// Specific binary names or paths to benchmark (for Cargo projects).
+        #[clap(
+            long,
+            value_name = "BIN",
+            help = "Name (Cargo bin target) or path of executable to benchmark; repeat to benchmark several"
+        )]
+        bin: Vec<String>,
         /// Serve a web dashboard instead of just generating files
         #[arg(long)]
         serve: bool,
         /// Port to bind the dashboard to
         #[arg(long, default_value_t = 8080)]
         port: u16,
+        /// Print the JSON Schema of the report format and exit
+        #[arg(long, conflicts_with = "serve")]
+        json_schema: bool,
+        /// Open the HTML report (or the dashboard) in the default browser
+        #[arg(long, conflicts_with = "json_schema")]
+        open: bool,
+        /// Format of the data file written next to the HTML report
+        #[arg(long, value_enum, default_value_t = ReportFormat::Json, conflicts_with = "serve")]
+        format: ReportFormat,
+        /// Compress the data file (`crabscore_report.json.gz` or `.br`); the
+        /// dashboard compresses `/data.json` per `Accept-Encoding` regardless
+        #[arg(long, value_enum, value_name = "ALGORITHM", conflicts_with = "serve")]
+        compress: Option<ReportCompression>,
+        /// Write the JSON or YAML data file at full precision instead of
+        /// rounding scores to 2 and latencies to 3 decimals
+        #[arg(long, conflicts_with = "serve")]
+        raw: bool,
+        /// Directory to write the report files to (created if missing)
+        #[arg(
+            long,
+            value_name = "DIR",
+            default_value = ".",
+            conflicts_with = "serve"
+        )]
+        output: std::path::PathBuf,
+        /// Compliance exports to write next to the report, comma-separated
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMATS",
+            value_delimiter = ',',
+            default_value = "csrd",
+            conflicts_with = "serve"
+        )]
+        export: Vec<ExportFormat>,
+    },
+    /// Score pre-collected metrics from a JSON file, skipping analysis and benchmarks
+    ScoreMetrics {
+        /// JSON file with `performance`, `energy`, `cost` and `safety` sections
+        input: std::path::PathBuf,
+        /// Industry profile used to weight the dimensions
+        #[arg(long, value_enum, default_value_t = Profile::WebServices)]
+        profile: Profile,
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Compare a report against a baseline report, dimension by dimension
+    Compare {
+        /// Baseline JSON report (from `crabscore score --format json`)
+        baseline: std::path::PathBuf,
+        /// Current JSON report
+        current: std::path::PathBuf,
+        /// Changes of at most this percentage of a dimension's baseline value
+        /// are shown as unchanged rather than as a regression or improvement
+        #[arg(long, value_name = "PCT", default_value_t = crate::compare::DEFAULT_TOLERANCE_PCT)]
+        tolerance: f64,
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Inspect the industry profiles
+    Profiles {
+        /// Profile action
+        #[command(subcommand)]
+        command: ProfilesCommand,
+    },
+    /// Write a commented crabscore.toml with the default settings to the
+    /// current directory
+    Init {
+        /// Overwrite an existing crabscore.toml
+        #[arg(long)]
+        force: bool,
     },
     /// Print the current version information
     Version,
 }
+
+/// `crabscore profiles` actions
+#[derive(Subcommand, Debug)]
+pub enum ProfilesCommand {
+    /// List every profile with its performance, energy and cost weights
+    List {
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+}
+
+/// Score dimension that can be computed on its own
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    /// Benchmarked latency and throughput (builds and runs the binary)
+    Performance,
+    /// Energy use, estimated from project complexity
+    Energy,
+    /// Infrastructure, operational and development cost
+    Cost,
+    /// Safety bonuses from static analysis
+    Safety,
+}
+
+impl Dimension {
+    /// Lowercase name, as accepted by `--only`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Performance => "performance",
+            Self::Energy => "energy",
+            Self::Cost => "cost",
+            Self::Safety => "safety",
+        }
+    }
+}
+
+/// Output format for command results
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored, human-readable terminal output
+    Human,
+    /// Machine-readable JSON
+    Json,
+}
+
+/// Kind of Cargo target to build and benchmark
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    /// A binary (`src/main.rs`, `src/bin/`, `[[bin]]`)
+    Bin,
+    /// An example (`examples/`)
+    Example,
+    /// A benchmark harness (`benches/`)
+    Bench,
+    /// An integration test (`tests/`)
+    Test,
+}
+
+impl TargetKind {
+    /// Cargo flag selecting a target of this kind, e.g. `--example`
+    pub fn cargo_flag(self) -> &'static str {
+        match self {
+            Self::Bin => "--bin",
+            Self::Example => "--example",
+            Self::Bench => "--bench",
+            Self::Test => "--test",
+        }
+    }
+}
+
+/// Output format of `crabscore score`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreFormat {
+    /// Colored, human-readable terminal output
+    Human,
+    /// Machine-readable JSON
+    Json,
+    /// TOML, with the timestamp as a native datetime
+    Toml,
+    /// YAML, e.g. for diffing scores in pull requests
+    Yaml,
+}
+
+/// Format of the score file written by `crabscore report`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// `crabscore_report.json`
+    Json,
+    /// `crabscore_report.toml`
+    Toml,
+    /// `crabscore_report.yaml`
+    Yaml,
+}
+
+/// Compliance export written by `crabscore report`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// CSRD sustainability summary (`report_csrd.json`)
+    Csrd,
+    /// SPDX SBOM fragment (`report_sbom.json`)
+    Sbom,
+    /// EU Cyber Resilience Act summary (`report_cra.json`)
+    Cra,
+    /// SARIF 2.1.0 for code scanning (`report.sarif`)
+    Sarif,
+}
+
+/// Compression of the data file written by `crabscore report`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportCompression {
+    /// Gzip (`.gz`)
+    Gzip,
+    /// Brotli (`.br`)
+    Brotli,
+}
+
+/// Weighting used when aggregating member scores of a workspace
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightBy {
+    /// Weight each member by its lines of code
+    Loc,
+    /// Weight each member by its number of functions
+    Functions,
+    /// Give every member the same weight
+    Equal,
+}
+
+/// Definition of a line when measuring project size
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CountMode {
+    /// Every line, including blank and comment lines
+    Physical,
+    /// Source lines only, without blank and comment-only lines
+    Sloc,
+}
+
+/// Combination of per-target performance when several binaries are benchmarked
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinAggregate {
+    /// Average every metric across targets
+    Mean,
+    /// Use the worst value of every metric across targets
+    Worst,
+}
+
+/// Industry profile selecting the dimension weights
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// Web services and APIs
+    WebServices,
+    /// IoT and embedded systems
+    IotEmbedded,
+    /// Financial applications
+    Financial,
+    /// Game development
+    Gaming,
+    /// Enterprise software
+    Enterprise,
+}
+
+/// A profile as shown by `crabscore profiles list`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ProfileSummary {
+    /// Name accepted by `--profile`
+    pub name: String,
+    /// What the profile is meant for
+    pub description: String,
+    /// Weight of the performance score
+    pub performance: f64,
+    /// Weight of the energy score
+    pub energy: f64,
+    /// Weight of the cost score
+    pub cost: f64,
+}
+
+impl Profile {
+    /// Every selectable profile with its weights, in declaration order
+    pub fn summaries() -> Vec<ProfileSummary> {
+        Self::value_variants()
+            .iter()
+            .filter_map(|profile| {
+                let value = profile.to_possible_value()?;
+                let weights = IndustryProfile::from(*profile).weights();
+                Some(ProfileSummary {
+                    name: value.get_name().to_string(),
+                    description: value.get_help().map(|h| h.to_string()).unwrap_or_default(),
+                    performance: weights.performance,
+                    energy: weights.energy,
+                    cost: weights.cost,
+                })
+            })
+            .collect()
+    }
+}
+
+impl From<Profile> for IndustryProfile {
+    fn from(profile: Profile) -> Self {
+        match profile {
+            Profile::WebServices => Self::WebServices,
+            Profile::IotEmbedded => Self::IotEmbedded,
+            Profile::Financial => Self::Financial,
+            Profile::Gaming => Self::Gaming,
+            Profile::Enterprise => Self::Enterprise,
+        }
+    }
+}
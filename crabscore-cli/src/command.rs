@@ -4,137 +4,2055 @@
 //! It analyzes project complexity and provides meaningful scores even for
 //! simple Rust files without runnable binaries.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::*;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crabscore_analysis::analysis;
-use crabscore_analysis::metrics::BenchmarkRunner;
+use crabscore_analysis::criterion::{self, CriterionBenchmark};
+use crabscore_analysis::metrics::{
+    aggregate_performance, Aggregation, BenchmarkOptions, BenchmarkRunner,
+};
+use crabscore_analysis::safety::{AnalysisOptions, FunctionComplexity};
+use crabscore_analysis::{affinity, calibration};
 use crabscore_core::{
-    metrics::{CostMetrics, PerformanceMetrics, SafetyMetrics},
-    IndustryProfile,
+    error::CrabScoreError,
+    metrics::{CostMetrics, EnergyMetrics, PerformanceMetrics, SafetyMetrics},
+    scoring::{BinarySizeConfig, SafetyScoringConfig, ScoreExplanation, ScoringEngine, Thresholds},
+    CertificationBands, Confidence, CrabScore, IndustryProfile, TargetMeasurement,
+};
+use crabscore_cost::provider::{CostProvider, StaticCostProvider, DEFAULT_COST_FILE};
+use crabscore_energy::{
+    interface::{per_operation, EnergyMonitor, PlatformMonitor},
+    thermal,
 };
-use crabscore_cost::provider::{CostProvider, StaticCostProvider};
-use crabscore_energy::interface::EnergyMonitor;
+use crabscore_report::{compression::Compression, formats, generator};
 
 use crate::{
-    binary_discovery::find_or_build_binary,
-    complexity::{analyze_project_complexity, ProjectComplexity},
+    binary_discovery::{
+        build_target, find_or_build_binaries, find_or_build_binary, list_bin_targets,
+        plan_binaries, target_dir, BinTarget, BinaryPlan, BuildLog, BuildOptions, CargoTarget,
+    },
+    cli::{
+        BinAggregate, CountMode, Dimension, ExportFormat, OutputFormat, Profile, ProfilesCommand,
+        ReportCompression, ReportFormat, ScoreFormat,
+    },
+    compare,
+    complexity::{
+        analyze_project_complexity, is_no_std, ComplexityOptions, DependencyGraph, LineCount,
+        ProjectComplexity,
+    },
+    config::{BinaryBench, BonusConfig, CarbonConfig, CrabScoreConfig},
     estimation::*,
-    scoring_engine::ComplexityAwareScoringEngine,
+    history, output, profile_inference,
+    progress::Progress,
+    scoring_engine::{ComplexityAwareScoringEngine, DEFAULT_MAX_COMPLEXITY_BONUS},
+    workspace::{self, MemberScore, WorkspaceScore},
 };
 
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Runs per month assumed when costing traced per-run network egress and
+/// `--runs-per-month` is not given. There is no measured source for it: a
+/// million runs (about one every 2.6 seconds) stands for a modest production
+/// workload, so scores are only comparable when they assume the same figure.
+pub const DEFAULT_RUNS_PER_MONTH: u64 = 1_000_000;
+
+/// How often the CPU clock and temperature are sampled while benchmarking
+const THERMAL_SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Days of git history counted as a month of code churn
+const CHURN_WINDOW_DAYS: u32 = 30;
+
+/// Warning recorded when no binary could be benchmarked
+const STATIC_ONLY_WARNING: &str =
+    "No executable found - metrics are estimated from static analysis";
+
+/// Warning recorded when the host has no power telemetry
+const NO_TELEMETRY_WARNING: &str = "No power telemetry - energy metrics are defaults";
+
+/// Everything produced by scoring a single project, file or binary, from
+/// [`score_path_detailed`]
+#[non_exhaustive]
+pub struct ScoredProject {
+    /// The score itself
+    pub score: CrabScore,
+    /// Size and structure of the analyzed sources
+    pub complexity: ProjectComplexity,
+    /// Whether every metric was estimated because nothing could be benchmarked
+    pub static_only: bool,
+    /// Engine that produced the score, with its complexity bonuses
+    pub engine: ComplexityAwareScoringEngine,
+    /// How each dimension and bonus contributed to the score
+    pub explanation: ScoreExplanation,
+    /// Safety metrics behind the safety bonuses
+    pub safety: SafetyMetrics,
+    /// Top-level functions, most complex first
+    pub functions: Vec<FunctionComplexity>,
+}
+
+/// Metrics gathered for a project, plus how they were obtained
+struct CollectedMetrics {
+    perf: PerformanceMetrics,
+    energy: EnergyMetrics,
+    safety: SafetyMetrics,
+    functions: Vec<FunctionComplexity>,
+    cost: CostMetrics,
+    confidence: Confidence,
+    targets: Vec<TargetMeasurement>,
+    /// Reasons the metrics may be imprecise
+    warnings: Vec<String>,
+}
+
+impl CollectedMetrics {
+    /// Reset every dimension but `only` to its defaults
+    fn keep_only(&mut self, only: Dimension) {
+        if only != Dimension::Performance {
+            self.perf = PerformanceMetrics::default();
+            self.targets.clear();
+        }
+        if only != Dimension::Energy {
+            self.energy = EnergyMetrics::default();
+        }
+        if only != Dimension::Cost {
+            self.cost = CostMetrics::default();
+        }
+        if only != Dimension::Safety {
+            self.safety = SafetyMetrics::default();
+            self.functions.clear();
+        }
+        self.confidence = Confidence::Partial;
+        self.warnings.push(format!(
+            "Only the {} dimension was computed - the overall score is not meaningful",
+            only.name()
+        ));
+    }
+}
+
+/// Print the JSON Schema of the report format to `out`
+#[cfg(feature = "schema")]
+fn print_json_schema(out: &mut dyn Write) -> Result<()> {
+    writeln!(
+        out,
+        "{}",
+        serde_json::to_string_pretty(&generator::json_schema())?
+    )?;
+    Ok(())
+}
+
+#[cfg(not(feature = "schema"))]
+fn print_json_schema(_out: &mut dyn Write) -> Result<()> {
+    anyhow::bail!("crabscore was built without the `schema` feature")
+}
+
+/// Options controlling how a single project is scored
+///
+/// Start from [`ScoreOptions::default`] and set the fields to change.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ScoreOptions {
+    /// Cargo bin target names or executable paths to benchmark
+    pub bins: Vec<String>,
+    /// Benchmark every binary target of the project
+    pub all_bins: bool,
+    /// Build and benchmark exactly this Cargo target instead of discovering one
+    pub target: Option<CargoTarget>,
+    /// How per-target performance is combined
+    pub bin_aggregate: Aggregation,
+    /// Benchmark this HTTP endpoint instead of process run time
+    pub http_endpoint: Option<String>,
+    /// Number of requests sent in HTTP benchmark mode
+    pub http_requests: u32,
+    /// Samples required for reliable tail percentiles
+    pub min_iterations_for_percentiles: u32,
+    /// Re-runs of a failed measured iteration
+    pub retries: u32,
+    /// Reuse cached per-file analysis results
+    pub use_cache: bool,
+    /// Non-Rust file extensions whose lines count toward project size
+    pub include_extensions: Vec<String>,
+    /// Which lines count toward project size
+    pub count_mode: LineCount,
+    /// Score in-process Criterion benchmarks when available
+    pub use_criterion: bool,
+    /// Input sizes for inferring time complexity from scaling runs
+    pub input_sizes: Vec<u64>,
+    /// Data piped to each benchmarked binary's stdin
+    pub stdin_data: Option<Vec<u8>>,
+    /// Measure network egress by tracing the binaries' socket writes
+    pub trace_network: bool,
+    /// Egress price in USD per GB, applied to traced traffic
+    pub egress_price_per_gb: f64,
+    /// Runs per month the traced per-run egress is costed for
+    pub runs_per_month: u64,
+    /// Normalize measured latencies by this machine's speed relative to a
+    /// reference machine
+    pub calibrate: bool,
+    /// Pin benchmarked processes to this CPU core (Linux only)
+    pub pin_cpu: Option<usize>,
+    /// Configured arguments, stdin and iterations per binary name
+    pub bench: BTreeMap<String, BinaryBench>,
+    /// Industry profile whose weights combine the scores
+    pub profile: IndustryProfile,
+    /// Cost configuration file overriding the project's own
+    pub cost_file: Option<PathBuf>,
+    /// Only analyze files changed since this git revision
+    pub since: Option<String>,
+    /// Only analyze these files (canonical paths); resolved from `since` when
+    /// that is set
+    pub only_files: Option<Vec<PathBuf>>,
+    /// Directory levels below the project root that are analyzed
+    pub max_depth: Option<std::num::NonZeroUsize>,
+    /// Dependency graph resolved once for all members of a workspace
+    pub dependency_graph: Option<std::sync::Arc<DependencyGraph>>,
+    /// How binaries are (re)built
+    pub build: BuildOptions,
+    /// Grid carbon profile applied to energy metrics
+    pub carbon: CarbonConfig,
+    /// Safety bonus points and falloff
+    pub safety: SafetyScoringConfig,
+    /// Energy penalty for oversized binaries
+    pub binary_size: BinarySizeConfig,
+    /// Most points the complexity bonuses can add
+    pub max_complexity_bonus: f64,
+    /// Collect only this dimension; the others keep their defaults
+    pub only: Option<Dimension>,
+    /// Spinner showing the current phase
+    pub progress: Progress,
+    /// Fail instead of degrading gracefully when a build, benchmark, the cost
+    /// file or the safety analysis fails
+    pub strict: bool,
+    /// p95 latency target (ms) that anchors the latency score
+    pub latency_sla_ms: Option<f64>,
+    /// Compare the static estimates with the measured scores
+    pub check_estimates: bool,
+    /// Held while building and benchmarking, so projects scored concurrently
+    /// with clones of these options measure one at a time while their static
+    /// analysis still runs in parallel
+    pub measurement_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+}
+
+impl ScoreOptions {
+    fn complexity_options(&self) -> ComplexityOptions {
+        ComplexityOptions {
+            use_cache: self.use_cache,
+            cache_dir: None,
+            progress: self.progress.clone(),
+            include_extensions: self.include_extensions.clone(),
+            only_files: self.only_files.clone(),
+            count_mode: self.count_mode,
+            max_depth: self.max_depth,
+            dependency_graph: self.dependency_graph.clone(),
+        }
+    }
+
+    /// Whether `dimension` is collected (everything is, unless `only` is set)
+    fn collects(&self, dimension: Dimension) -> bool {
+        self.only.is_none_or(|only| only == dimension)
+    }
+
+    fn analysis_options(&self) -> AnalysisOptions {
+        AnalysisOptions {
+            use_cache: self.use_cache,
+            only_files: self.only_files.clone(),
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+impl Default for ScoreOptions {
+    fn default() -> Self {
+        Self {
+            bins: Vec::new(),
+            all_bins: false,
+            target: None,
+            bin_aggregate: Aggregation::Mean,
+            http_endpoint: None,
+            http_requests: 100,
+            min_iterations_for_percentiles: BenchmarkOptions::default()
+                .min_iterations_for_percentiles,
+            retries: 0,
+            use_cache: true,
+            include_extensions: Vec::new(),
+            count_mode: LineCount::Physical,
+            use_criterion: false,
+            input_sizes: Vec::new(),
+            stdin_data: None,
+            trace_network: false,
+            egress_price_per_gb: 0.09,
+            runs_per_month: DEFAULT_RUNS_PER_MONTH,
+            calibrate: false,
+            pin_cpu: None,
+            bench: BTreeMap::new(),
+            profile: IndustryProfile::default(),
+            cost_file: None,
+            since: None,
+            only_files: None,
+            max_depth: None,
+            dependency_graph: None,
+            build: BuildOptions::default(),
+            carbon: CarbonConfig::default(),
+            safety: SafetyScoringConfig::default(),
+            binary_size: BinarySizeConfig::default(),
+            max_complexity_bonus: DEFAULT_MAX_COMPLEXITY_BONUS,
+            only: None,
+            progress: Progress::hidden(),
+            strict: false,
+            latency_sla_ms: None,
+            check_estimates: false,
+            measurement_lock: Default::default(),
+        }
+    }
+}
+
+/// Score the project, single source file or executable at `path`.
+///
+/// Runs the whole pipeline behind `crabscore score` — complexity analysis,
+/// binary discovery and building, benchmarking, energy and cost collection
+/// and scoring — without printing anything (unless `options.progress` is a
+/// visible spinner). Anything that made the score less precise is listed in
+/// `metadata.warnings` of the result.
+pub async fn score_path(path: &Path, options: &ScoreOptions) -> Result<CrabScore> {
+    Ok(score_path_detailed(path, options).await?.score)
+}
 
-/// Execute a CLI command
+/// Execute a CLI command, printing its output to stdout
 pub async fn execute(cmd: crate::cli::Commands, verbosity: u8) -> Result<()> {
+    execute_to(cmd, verbosity, &mut std::io::stdout()).await
+}
+
+/// Execute a CLI command, writing its output to `out` instead of stdout.
+///
+/// Logs, warnings and the progress spinner still go to stderr; everything
+/// [`execute`] would print to stdout is written to `out`.
+pub async fn execute_to(
+    cmd: crate::cli::Commands,
+    verbosity: u8,
+    out: &mut (dyn Write + Send),
+) -> Result<()> {
     init_logging(verbosity);
 
     match cmd {
-        crate::cli::Commands::Score { path, bin } => {
-            let input_path = Path::new(&path);
-
-            // Check if this is a Cargo project
-            let is_cargo_project = input_path.join("Cargo.toml").exists()
-                || input_path
-                    .parent()
-                    .map(|p| p.join("Cargo.toml").exists())
-                    .unwrap_or(false);
-
-            // Analyze project complexity for better scoring
-            let project_complexity = analyze_project_complexity(input_path).await?;
-
-            println!("{}", "Analyzing Rust project...".bright_cyan());
-            println!("  Files: {}", project_complexity.file_count);
-            println!("  Lines of code: {}", project_complexity.total_lines);
-            println!("  Functions: {}", project_complexity.function_count);
-
-            // Try to find or build a binary, but don't fail if we can't
-            let binary_path = find_or_build_binary(input_path, &bin, is_cargo_project).await;
-
-            // Collect metrics - with graceful degradation
-            let (perf, energy, safety, cost) = if let Some(ref exe_path) = binary_path {
-                info!("Found executable {} for benchmarking", exe_path.display());
-                collect_full_metrics(exe_path, input_path, is_cargo_project).await?
+        crate::cli::Commands::Score {
+            path,
+            bin,
+            all_bins,
+            list_bins,
+            target_kind,
+            target_name,
+            bin_aggregate,
+            http_endpoint,
+            http_requests,
+            min_iterations_for_percentiles,
+            retries,
+            no_cache,
+            include_ext,
+            count_mode,
+            max_depth,
+            use_criterion,
+            input_sizes,
+            stdin_file,
+            trace_network,
+            egress_price_per_gb,
+            runs_per_month,
+            calibrate,
+            pin_cpu,
+            profile,
+            profile_from_cargo_metadata,
+            features,
+            all_features,
+            no_default_features,
+            cargo_profile,
+            no_rebuild,
+            cost_file,
+            since,
+            fail_under,
+            min_performance,
+            min_energy,
+            min_cost,
+            dry_run,
+            strict,
+            check_estimates,
+            baseline_auto,
+            explain,
+            max_bonus,
+            latency_sla_ms,
+            only,
+            top_complex,
+            next_tier,
+            quiet,
+            format,
+            raw,
+            jobs,
+        } => {
+            // A glob scores every matching directory like `crabscore batch`
+            let glob_dirs = expand_path_glob(&path)?;
+            if glob_dirs.is_some() {
+                let per_path_output = [
+                    ("--list-bins", list_bins),
+                    ("--dry-run", dry_run),
+                    ("--explain", explain),
+                    ("--quiet", quiet),
+                    ("--top-complex", top_complex.is_some()),
+                    ("--next-tier", next_tier),
+                    ("--baseline-auto", baseline_auto),
+                ];
+                if let Some((flag, _)) = per_path_output.iter().find(|(_, set)| *set) {
+                    anyhow::bail!("{flag} cannot be used with a glob path");
+                }
+                if let Some(format) = format.filter(|format| *format != ScoreFormat::Json) {
+                    anyhow::bail!(
+                        "--format {} cannot be used with a glob path, which is scored as NDJSON",
+                        format!("{format:?}").to_lowercase()
+                    );
+                }
+            } else if jobs.is_some() {
+                anyhow::bail!("--jobs only applies to a glob path");
+            }
+            let format = format.unwrap_or(ScoreFormat::Human);
+            // `-` scores a single source file piped on stdin
+            let stdin_source = if path == "-" {
+                Some(read_stdin_source(std::io::stdin().lock())?)
             } else {
-                info!("No executable found - using static analysis only");
-                collect_static_metrics(input_path, is_cargo_project, &project_complexity).await?
+                None
             };
+            let input_path = match &stdin_source {
+                Some((_dir, file)) => file.as_path(),
+                None => Path::new(&path),
+            };
+            if list_bins {
+                let bins = list_bin_targets(input_path).await?;
+                return display_bin_targets(out, &bins, format);
+            }
+            let flag_thresholds = Thresholds {
+                overall: fail_under,
+                performance: min_performance,
+                energy: min_energy,
+                cost: min_cost,
+            };
+            let build = BuildOptions {
+                rebuild_stale: !no_rebuild,
+                features,
+                all_features,
+                no_default_features,
+                profile: cargo_profile,
+            };
+            build.validate()?;
+            if let Some(sla) = latency_sla_ms {
+                anyhow::ensure!(
+                    sla.is_finite() && sla > 0.0,
+                    "--latency-sla-ms must be a positive number of milliseconds, got {sla}"
+                );
+            }
+            let stdin_data = stdin_file
+                .map(|file| {
+                    std::fs::read(&file)
+                        .with_context(|| format!("failed to read {}", file.display()))
+                })
+                .transpose()?;
+            let cost_file = cost_file.map(std::path::absolute).transpose()?;
+            let target = target_kind
+                .zip(target_name)
+                .map(|(kind, name)| CargoTarget { kind, name });
+            // Everything but the project's configuration, the profile and the
+            // spinner comes from the flags
+            let score_options = |config: &CrabScoreConfig,
+                                 profile: IndustryProfile,
+                                 progress: Progress|
+             -> Result<ScoreOptions> {
+                let bonuses = BonusConfig {
+                    complexity_cap: max_bonus.unwrap_or(config.bonuses.complexity_cap),
+                };
+                bonuses.validate()?;
+                Ok(ScoreOptions {
+                    bins: bin.clone(),
+                    all_bins,
+                    target: target.clone(),
+                    bin_aggregate: match bin_aggregate {
+                        BinAggregate::Mean => Aggregation::Mean,
+                        BinAggregate::Worst => Aggregation::Worst,
+                    },
+                    http_endpoint: http_endpoint.clone(),
+                    http_requests,
+                    min_iterations_for_percentiles,
+                    retries,
+                    use_cache: !no_cache,
+                    include_extensions: include_ext.clone(),
+                    count_mode: match count_mode {
+                        CountMode::Physical => LineCount::Physical,
+                        CountMode::Sloc => LineCount::Sloc,
+                    },
+                    use_criterion,
+                    input_sizes: input_sizes.clone(),
+                    stdin_data: stdin_data.clone(),
+                    trace_network,
+                    egress_price_per_gb,
+                    runs_per_month,
+                    calibrate,
+                    pin_cpu,
+                    bench: config.bench.clone(),
+                    profile,
+                    cost_file: cost_file.clone(),
+                    since: since.clone(),
+                    only_files: None,
+                    max_depth,
+                    dependency_graph: None,
+                    build: build.clone(),
+                    carbon: config.carbon,
+                    safety: config.safety,
+                    binary_size: config.binary_size,
+                    max_complexity_bonus: bonuses.complexity_cap,
+                    only,
+                    progress,
+                    strict,
+                    latency_sla_ms,
+                    check_estimates,
+                    measurement_lock: Default::default(),
+                })
+            };
+            if let Some(dirs) = glob_dirs {
+                let paths = dirs.iter().map(|dir| dir.display().to_string()).collect();
+                let setup = |dir: &Path| -> Result<PathSetup> {
+                    let config = CrabScoreConfig::load(dir)?;
+                    let profile = match profile {
+                        Some(profile) => profile.into(),
+                        None if profile_from_cargo_metadata => {
+                            profile_inference::infer_from_project(dir).unwrap_or_default()
+                        }
+                        None => IndustryProfile::default(),
+                    };
+                    Ok(PathSetup {
+                        options: score_options(&config, profile, Progress::hidden())?,
+                        thresholds: thresholds_for(flag_thresholds.or(config.thresholds), only),
+                        config,
+                    })
+                };
+                return score_paths(paths, jobs_or_cpus(jobs), raw, true, setup, out).await;
+            }
+            let config = CrabScoreConfig::load(input_path)?;
+            let profile = match profile {
+                Some(profile) => profile.into(),
+                None if profile_from_cargo_metadata => {
+                    let inferred = profile_inference::infer_from_project(input_path);
+                    let profile = inferred.unwrap_or_default();
+                    let source = if inferred.is_some() {
+                        "inferred from Cargo metadata"
+                    } else {
+                        "no Cargo keyword or category matched, using the default"
+                    };
+                    if format == ScoreFormat::Human && !quiet {
+                        writeln!(out, "{}: {:?} ({})", "Profile".bold(), profile, source)?;
+                    }
+                    profile
+                }
+                None => {
+                    if format == ScoreFormat::Human && !quiet && is_no_std(input_path) {
+                        writeln!(
+                            out,
+                            "{}",
+                            "Hint: this is a #![no_std] crate - `--profile iot-embedded` scores \
+                             it as embedded software"
+                                .dimmed()
+                        )?;
+                    }
+                    IndustryProfile::default()
+                }
+            };
+            let opts = score_options(
+                &config,
+                profile,
+                Progress::new(format == ScoreFormat::Human && !quiet && output::color_enabled()),
+            )?;
+            if dry_run {
+                let plan = plan_score(input_path, &opts).await?;
+                opts.progress.finish();
+                match format {
+                    ScoreFormat::Human => display_dry_run(out, &plan)?,
+                    ScoreFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&plan)?)?,
+                    ScoreFormat::Toml => write!(out, "{}", toml::to_string_pretty(&plan)?)?,
+                    ScoreFormat::Yaml => write!(out, "{}", serde_yaml::to_string(&plan)?)?,
+                }
+                return Ok(());
+            }
+            let result = score_path_detailed(input_path, &opts).await;
+            opts.progress.finish();
+            let result = result?;
+            if format == ScoreFormat::Human && !quiet {
+                display_analysis_summary(out, &result.complexity)?;
+            }
+            let certification = result.score.certification_with(&config.certifications);
 
-            // Calculate score with complexity-aware engine
-            let engine = ComplexityAwareScoringEngine::new(
-                IndustryProfile::default(),
-                project_complexity.clone(),
-            );
-            let score = engine.calculate_score(&perf, &energy, &cost, &safety);
-
-            // Display results
-            display_results(&score, &project_complexity, binary_path.is_none(), &engine);
+            match (format, explain, only) {
+                _ if quiet => display_quiet(out, &result.score, &certification)?,
+                (ScoreFormat::Human, true, _) => display_explanation(out, &result.explanation)?,
+                (ScoreFormat::Json, true, _) => writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string_pretty(&result.explanation)?
+                )?,
+                (ScoreFormat::Toml, true, _) => {
+                    write!(out, "{}", toml::to_string_pretty(&result.explanation)?)?
+                }
+                (ScoreFormat::Yaml, true, _) => {
+                    write!(out, "{}", serde_yaml::to_string(&result.explanation)?)?
+                }
+                (ScoreFormat::Human, false, Some(dimension)) => {
+                    display_dimension(out, &result, dimension, &opts.safety)?
+                }
+                (ScoreFormat::Human, false, None) => display_results(
+                    out,
+                    &result.score,
+                    result.score.grade_with(&config.grades),
+                    &certification,
+                    &result.complexity,
+                    result.static_only,
+                    &result.engine,
+                )?,
+                (ScoreFormat::Json, false, _) => writeln!(
+                    out,
+                    "{}",
+                    json_report(&result.score, raw, &config).to_pretty_string()
+                )?,
+                (ScoreFormat::Toml, false, _) => write!(
+                    out,
+                    "{}",
+                    generator::generate_toml_with(&result.score, &config.certifications)?
+                )?,
+                (ScoreFormat::Yaml, false, _) => {
+                    write!(out, "{}", report_yaml(&result.score, raw, &config)?)?
+                }
+            }
+            if let Some(n) = top_complex {
+                display_complex_functions(out, &result.functions, n)?;
+            }
+            if next_tier {
+                display_next_tier(out, &result.score, &config.certifications)?;
+            }
+            if baseline_auto {
+                compare_with_history(
+                    out,
+                    input_path,
+                    &result.score,
+                    format == ScoreFormat::Human && !quiet,
+                )?;
+            }
+            enforce_thresholds(
+                &result.score,
+                &thresholds_for(flag_thresholds.or(config.thresholds), only),
+            )?;
         }
-        crate::cli::Commands::Report { serve, port } => {
-            // Reuse Score flow to gather metrics then generate/serve
-            let project_complexity = analyze_project_complexity(Path::new(".")).await?;
-            let binary_path = find_or_build_binary(Path::new("."), &None, true).await;
-
-            let (perf, energy, safety, cost) = if let Some(ref exe_path) = binary_path {
-                collect_full_metrics(exe_path, Path::new("."), true).await?
+        crate::cli::Commands::Batch {
+            paths_file,
+            jobs,
+            raw,
+        } => {
+            let list = if paths_file.as_os_str() == "-" {
+                std::io::read_to_string(std::io::stdin())?
             } else {
-                collect_static_metrics(Path::new("."), true, &project_complexity).await?
+                std::fs::read_to_string(&paths_file)
+                    .with_context(|| format!("failed to read {}", paths_file.display()))?
+            };
+            score_batch(&list, jobs_or_cpus(jobs), raw, out).await?;
+        }
+        crate::cli::Commands::Workspace {
+            path,
+            weight_by,
+            jobs,
+            format,
+        } => {
+            let root = Path::new(&path);
+            let members = workspace::discover_members(root)?;
+            if members.is_empty() {
+                anyhow::bail!("No workspace members found under {}", root.display());
+            }
+
+            let config = CrabScoreConfig::load(root)?;
+            let opts = ScoreOptions {
+                bench: config.bench,
+                carbon: config.carbon,
+                safety: config.safety,
+                binary_size: config.binary_size,
+                max_complexity_bonus: config.bonuses.complexity_cap,
+                progress: Progress::new(format == OutputFormat::Human && output::color_enabled()),
+                // Members share the workspace's graph instead of each
+                // running cargo metadata
+                dependency_graph: DependencyGraph::resolve(root)
+                    .await
+                    .map(std::sync::Arc::new),
+                ..Default::default()
+            };
+            let scored = score_members(
+                members,
+                opts.clone(),
+                jobs_or_cpus(jobs),
+                format,
+                weight_by,
+                out,
+            )
+            .await;
+            opts.progress.finish();
+            let scored = scored?;
+
+            let aggregate = workspace::aggregate(&workspace::package_name(root), &scored)
+                .expect("at least one member was scored");
+            let report = WorkspaceScore {
+                members: scored,
+                aggregate,
             };
 
-            let engine =
-                ComplexityAwareScoringEngine::new(IndustryProfile::default(), project_complexity);
-            let score = engine.calculate_score(&perf, &energy, &cost, &safety);
+            match format {
+                OutputFormat::Human => {
+                    // Each member is certified against the tiers of its own
+                    // configuration, the roll-up against the workspace's
+                    let member_bands = report
+                        .members
+                        .iter()
+                        .map(|member| Ok(CrabScoreConfig::load(&member.path)?.certifications))
+                        .collect::<Result<Vec<_>>>()?;
+                    display_workspace_results(
+                        out,
+                        &report,
+                        &member_bands,
+                        &config.certifications,
+                        weight_by,
+                    )?
+                }
+                OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?,
+            }
+        }
+        crate::cli::Commands::Report {
+            path,
+            bin,
+            serve,
+            port,
+            json_schema,
+            open,
+            format,
+            compress,
+            raw,
+            output,
+            export,
+        } => {
+            if json_schema {
+                return print_json_schema(out);
+            }
+            let score = report_score(Path::new(&path), bin).await?;
+            let config = CrabScoreConfig::load(Path::new(&path))?;
 
             if serve {
                 use crabscore_report::web;
                 let addr = ([0, 0, 0, 0], port).into();
-                web::serve(score, addr).await?;
+                if open {
+                    // Give the server a moment to bind before the browser connects
+                    tokio::spawn(async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                        open_in_browser(format!("http://localhost:{port}")).await;
+                    });
+                }
+                let history = report_history(Path::new(&path));
+                web::serve(score, history, config.certifications, addr).await?;
             } else {
-                use crabscore_report::{formats, generator};
-                std::fs::write(
-                    "crabscore_report.json",
-                    generator::generate_json(&score).to_pretty_string(),
+                let files = ReportFiles {
+                    format,
+                    compress,
+                    raw,
+                    exports: export,
+                };
+                let written = files.write(&output, &score, &config)?;
+                writeln!(
+                    out,
+                    "Reports written to {} and {}",
+                    written.data.display(),
+                    written.html.display()
                 )?;
-                std::fs::write("crabscore_report.html", generator::generate_html(&score))?;
-                println!("Reports written to crabscore_report.(json|html)");
-                // example exporters
-                std::fs::write("report_csrd.json", formats::export_csrd(&score))?;
+                if !written.exports.is_empty() {
+                    let exports: Vec<_> = written
+                        .exports
+                        .iter()
+                        .map(|file| file.display().to_string())
+                        .collect();
+                    writeln!(out, "Exports written to {}", exports.join(", "))?;
+                }
+                if open {
+                    let html = std::path::absolute(&written.html)?;
+                    open_in_browser(html.display().to_string()).await;
+                }
+            }
+        }
+        crate::cli::Commands::ScoreMetrics {
+            input,
+            profile,
+            format,
+        } => {
+            let score = score_metrics_file(&input, profile.into())?;
+            match format {
+                OutputFormat::Human => {
+                    let color = output::color_enabled();
+                    writeln!(out, "\n{}", "CrabScore Report".bold().bright_white())?;
+                    writeln!(out, "{}", output::rule(50, color).bright_white())?;
+                    writeln!(
+                        out,
+                        "{}: {:.0}/100 [{}]",
+                        "Overall Score".bold(),
+                        score.overall,
+                        format!("{:?}", score.certification).bright_yellow()
+                    )?;
+                    writeln!(out, "{}: {:?}", "Profile".bold(), score.metadata.profile)?;
+                    writeln!(out, "\n{}", "Breakdown:".bold())?;
+                    writeln!(out, "{}", output::breakdown_table(&score, color))?;
+                    if score.bonuses > 0.0 {
+                        writeln!(out, "\n{}: +{:.1}", "Bonuses".bold(), score.bonuses)?;
+                    }
+                }
+                OutputFormat::Json => writeln!(
+                    out,
+                    "{}",
+                    generator::generate_json(&score).to_pretty_string()
+                )?,
             }
         }
+        crate::cli::Commands::Compare {
+            baseline,
+            current,
+            tolerance,
+            format,
+        } => {
+            compare::validate_tolerance(tolerance)?;
+            let comparison = compare::compare(
+                &compare::load_report(&baseline)?,
+                &compare::load_report(&current)?,
+                tolerance,
+            );
+            match format {
+                OutputFormat::Human => display_comparison(out, &comparison)?,
+                OutputFormat::Json => {
+                    // Keep the warnings visible when stdout goes to a file.
+                    for warning in &comparison.warnings {
+                        warn!("{}", warning);
+                    }
+                    writeln!(out, "{}", serde_json::to_string_pretty(&comparison)?)?
+                }
+            }
+        }
+        crate::cli::Commands::Profiles {
+            command: ProfilesCommand::List { format },
+        } => {
+            let profiles = Profile::summaries();
+            match format {
+                OutputFormat::Human => {
+                    writeln!(
+                        out,
+                        "{}",
+                        output::profiles_table(&profiles, output::color_enabled())
+                    )?;
+                    writeln!(
+                        out,
+                        "\nCustom weights are available to library users through \
+                         crabscore_core::profiles::IndustryProfile::Custom."
+                    )?;
+                }
+                OutputFormat::Json => {
+                    writeln!(out, "{}", serde_json::to_string_pretty(&profiles)?)?
+                }
+            }
+        }
+        crate::cli::Commands::Init { force } => {
+            let path = CrabScoreConfig::write_template(Path::new("."), force)?;
+            writeln!(out, "Wrote {}", path.display())?;
+        }
         crate::cli::Commands::Version => {
-            println!("CrabScore CLI {}", env!("CARGO_PKG_VERSION"));
+            writeln!(out, "CrabScore CLI {}", env!("CARGO_PKG_VERSION"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// One NDJSON line of `crabscore batch` output
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum BatchLine<'a> {
+    /// The JSON report of a scored path, with the path added
+    Scored {
+        path: &'a str,
+        #[serde(flatten)]
+        report: Box<generator::JsonReport>,
+    },
+    /// Why a path could not be scored
+    Failed { path: &'a str, error: String },
+}
+
+/// The files `crabscore report` writes
+struct ReportFiles {
+    /// Format of the data file
+    format: ReportFormat,
+    /// Compression of the data file
+    compress: Option<ReportCompression>,
+    /// Keep the JSON or YAML data file at full precision
+    raw: bool,
+    /// Compliance exports written next to the report
+    exports: Vec<ExportFormat>,
+}
+
+/// Paths written by [`ReportFiles::write`]
+struct WrittenReports {
+    data: PathBuf,
+    html: PathBuf,
+    exports: Vec<PathBuf>,
+}
+
+impl ReportFiles {
+    /// Write the data file, the HTML report and the exports for `score` into
+    /// `dir`, creating it if missing
+    fn write(
+        &self,
+        dir: &Path,
+        score: &CrabScore,
+        config: &CrabScoreConfig,
+    ) -> Result<WrittenReports> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let write = |name: &str, data: &[u8]| -> Result<PathBuf> {
+            let file = dir.join(name);
+            std::fs::write(&file, data)
+                .with_context(|| format!("failed to write {}", file.display()))?;
+            Ok(file)
+        };
+
+        let (data_file, data) = match self.format {
+            ReportFormat::Json => (
+                "crabscore_report.json",
+                json_report(score, self.raw, config).to_pretty_string(),
+            ),
+            ReportFormat::Toml => (
+                "crabscore_report.toml",
+                generator::generate_toml_with(score, &config.certifications)?,
+            ),
+            ReportFormat::Yaml => (
+                "crabscore_report.yaml",
+                report_yaml(score, self.raw, config)?,
+            ),
+        };
+        let (data_file, data) = match self.compress {
+            Some(algorithm) => {
+                let compression = match algorithm {
+                    ReportCompression::Gzip => Compression::Gzip,
+                    ReportCompression::Brotli => Compression::Brotli,
+                };
+                (
+                    format!("{data_file}.{}", compression.extension()),
+                    compression.compress(data.as_bytes())?,
+                )
+            }
+            None => (data_file.to_string(), data.into_bytes()),
+        };
+        let data = write(&data_file, &data)?;
+        let html = write(
+            "crabscore_report.html",
+            generator::generate_html_with(score, &config.certifications).as_bytes(),
+        )?;
+        let exports = self
+            .exports
+            .iter()
+            .map(|export| {
+                let (name, content) = match export {
+                    ExportFormat::Csrd => ("report_csrd.json", formats::export_csrd(score)),
+                    ExportFormat::Sbom => ("report_sbom.json", formats::export_sbom(score)),
+                    ExportFormat::Cra => ("report_cra.json", formats::export_cra(score)),
+                    ExportFormat::Sarif => ("report.sarif", formats::export_sarif(score)),
+                };
+                write(name, content.as_bytes())
+            })
+            .collect::<Result<_>>()?;
+        Ok(WrittenReports {
+            data,
+            html,
+            exports,
+        })
+    }
+}
+
+/// Score `path` for `crabscore report` with the project's configuration,
+/// benchmarking the given `bins` (or the discovered binary when empty)
+async fn report_score(path: &Path, bins: Vec<String>) -> Result<CrabScore> {
+    let config = CrabScoreConfig::load(path)?;
+    let opts = ScoreOptions {
+        bins,
+        bench: config.bench,
+        carbon: config.carbon,
+        safety: config.safety,
+        binary_size: config.binary_size,
+        max_complexity_bonus: config.bonuses.complexity_cap,
+        ..Default::default()
+    };
+    score_path(path, &opts).await
+}
+
+/// The JSON report of `score`, graded and certified with the project's
+/// configuration and rounded unless `raw`
+fn json_report(score: &CrabScore, raw: bool, config: &CrabScoreConfig) -> generator::JsonReport {
+    let mut report = if raw {
+        generator::generate_json_raw(score)
+    } else {
+        generator::generate_json(score)
+    };
+    report.grade = report.score.grade_with(&config.grades);
+    report.with_certification_bands(&config.certifications)
+}
+
+/// The JSON report of `score` (see [`json_report`]) as YAML
+fn report_yaml(score: &CrabScore, raw: bool, config: &CrabScoreConfig) -> Result<String> {
+    Ok(serde_yaml::to_string(&json_report(score, raw, config))?)
+}
+
+/// `--jobs`, or the number of CPUs when not given
+fn jobs_or_cpus(jobs: Option<std::num::NonZeroUsize>) -> usize {
+    jobs.or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, |n| n.get())
+}
+
+/// Run `job` for every item, at most `jobs` at a time, and pass the results
+/// to `emit` in item order as soon as all earlier items are done.
+///
+/// The jobs run as separate tasks; an error from `emit` (or a panicking job)
+/// stops the remaining ones.
+async fn for_each_bounded<T, R, Fut>(
+    items: Vec<T>,
+    jobs: usize,
+    job: impl Fn(T) -> Fut,
+    mut emit: impl FnMut(R) -> Result<()>,
+) -> Result<()>
+where
+    Fut: std::future::Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(jobs.max(1)));
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let permits = std::sync::Arc::clone(&permits);
+        let run = job(item);
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, run.await)
+        });
+    }
+    let mut finished = BTreeMap::new();
+    let mut next = 0;
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) = joined?;
+        finished.insert(index, result);
+        while let Some(result) = finished.remove(&next) {
+            emit(result)?;
+            next += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Score workspace `members`, `jobs` at a time, in member order.
+///
+/// Human output of each member (its header and source summary) is buffered
+/// and written to `out` in one piece once the member is done, so parallel
+/// members never interleave. Each member shows its phases on a spinner of
+/// its own, and members are built and benchmarked one at a time (see
+/// [`ScoreOptions::measurement_lock`]).
+async fn score_members(
+    members: Vec<PathBuf>,
+    opts: ScoreOptions,
+    jobs: usize,
+    format: OutputFormat,
+    weight_by: crate::cli::WeightBy,
+    out: &mut dyn Write,
+) -> Result<Vec<MemberScore>> {
+    let announce = format == OutputFormat::Human;
+    opts.progress
+        .phase(format!("Scoring {} workspace members", members.len()));
+    let opts = std::sync::Arc::new(opts);
+    let mut scored = Vec::new();
+    let job = |member: PathBuf| {
+        let opts = std::sync::Arc::clone(&opts);
+        async move {
+            let name = workspace::package_name(&member);
+            let mut log = Vec::new();
+            if announce {
+                let _ = writeln!(log, "\n{} {}", "Scoring member".bright_cyan(), name.bold());
+            }
+            let opts = ScoreOptions {
+                progress: opts.progress.job(name.clone()),
+                ..(*opts).clone()
+            };
+            let result = score_path_detailed(&member, &opts).await;
+            opts.progress.finish();
+            if let (true, Ok(result)) = (announce, &result) {
+                let _ = display_analysis_summary(&mut log, &result.complexity);
+            }
+            (log, result.map(|result| (name, member, result)))
+        }
+    };
+    for_each_bounded(members, jobs, job, |(log, result)| {
+        opts.progress.suspend(|| out.write_all(&log))?;
+        let (name, path, mut result) = result?;
+        result.score.metadata.project_name = name.clone();
+        scored.push(MemberScore {
+            name,
+            weight: workspace::member_weight(&result.complexity, weight_by),
+            path,
+            score: result.score,
+        });
+        Ok(())
+    })
+    .await?;
+    Ok(scored)
+}
+
+/// Score every path listed in `list` with [`score_paths`] and the path's
+/// configuration; blank lines and `#` comments are skipped.
+async fn score_batch(list: &str, jobs: usize, raw: bool, out: &mut dyn Write) -> Result<()> {
+    let paths: Vec<String> = list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    let setup = |path: &Path| -> Result<PathSetup> {
+        let config = CrabScoreConfig::load(path)?;
+        Ok(PathSetup {
+            options: ScoreOptions {
+                bench: config.bench.clone(),
+                carbon: config.carbon,
+                safety: config.safety,
+                binary_size: config.binary_size,
+                max_complexity_bonus: config.bonuses.complexity_cap,
+                ..Default::default()
+            },
+            thresholds: Thresholds::default(),
+            config,
+        })
+    };
+    score_paths(paths, jobs, raw, false, setup, out).await
+}
+
+/// How [`score_paths`] scores one path
+struct PathSetup {
+    options: ScoreOptions,
+    /// Minimum scores the path must reach
+    thresholds: Thresholds,
+    /// The path's configuration, for its grades and certification tiers
+    config: CrabScoreConfig,
+}
+
+/// Score `paths` as `setup` says, `jobs` at a time, writing one NDJSON line
+/// per path to `out` in order as soon as it (and every path before it) is
+/// scored; reports are rounded unless `raw`.
+///
+/// Only static analysis runs concurrently: paths are built and benchmarked
+/// one at a time. A path that cannot be scored yields an error line. Once
+/// every path is written, fails if a path fell below its thresholds, or (when
+/// `fail_on_error`) could not be scored.
+async fn score_paths(
+    paths: Vec<String>,
+    jobs: usize,
+    raw: bool,
+    fail_on_error: bool,
+    setup: impl Fn(&Path) -> Result<PathSetup>,
+    out: &mut dyn Write,
+) -> Result<()> {
+    // Paths are built and benchmarked one at a time
+    let measurement_lock = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+    let job = |path: String| {
+        let setup = setup(Path::new(&path)).map(|mut setup| {
+            setup.options.measurement_lock = std::sync::Arc::clone(&measurement_lock);
+            setup
+        });
+        async move {
+            let scored = async {
+                let setup = setup?;
+                let score = score_path(Path::new(&path), &setup.options).await?;
+                anyhow::Ok((score, setup))
+            };
+            let scored = scored.await;
+            (path, scored)
         }
+    };
+    let mut failed = Vec::new();
+    for_each_bounded(paths, jobs, job, |(path, scored)| {
+        let line = match scored {
+            Ok((score, setup)) => {
+                if let Some(failures) = threshold_failures(&score, &setup.thresholds) {
+                    failed.push(format!("{path}: {failures}"));
+                }
+                BatchLine::Scored {
+                    path: &path,
+                    report: Box::new(json_report(&score, raw, &setup.config)),
+                }
+            }
+            Err(e) => {
+                if fail_on_error {
+                    failed.push(format!("{path}: {e:#}"));
+                }
+                BatchLine::Failed {
+                    path: &path,
+                    error: format!("{e:#}"),
+                }
+            }
+        };
+        serde_json::to_writer(&mut *out, &line)?;
+        writeln!(out)?;
+        out.flush()?;
+        Ok(())
+    })
+    .await?;
+    if !failed.is_empty() {
+        anyhow::bail!("{} path(s) failed: {}", failed.len(), failed.join("; "));
+    }
+    Ok(())
+}
+
+/// Pre-collected metrics accepted by `crabscore score-metrics`
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetricsInput {
+    performance: PerformanceMetrics,
+    energy: EnergyMetrics,
+    cost: CostMetrics,
+    safety: SafetyMetrics,
+}
+
+/// Score the metrics stored in the JSON file at `path` with `profile`.
+fn score_metrics_file(path: &Path, profile: IndustryProfile) -> Result<CrabScore> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read metrics file {}", path.display()))?;
+    let input: MetricsInput = serde_json::from_str(&content)
+        .with_context(|| format!("invalid metrics file {}", path.display()))?;
+    Ok(ScoringEngine::new(profile).calculate_score(
+        &input.performance,
+        &input.energy,
+        &input.cost,
+        &input.safety,
+    ))
+}
+
+/// Fail with one error listing every dimension of `score` below its threshold.
+fn enforce_thresholds(score: &CrabScore, thresholds: &Thresholds) -> Result<()> {
+    match threshold_failures(score, thresholds) {
+        Some(list) => anyhow::bail!("Score below threshold: {list}"),
+        None => Ok(()),
+    }
+}
+
+/// Every dimension of `score` below its threshold, as `cost 40.0 < 50.0`;
+/// `None` when all pass.
+fn threshold_failures(score: &CrabScore, thresholds: &Thresholds) -> Option<String> {
+    let failures = thresholds.failures(score);
+    (!failures.is_empty()).then(|| {
+        failures
+            .iter()
+            .map(|f| format!("{} {:.1} < {:.1}", f.dimension, f.actual, f.minimum))
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+/// `thresholds` that apply when only `only` is computed: just that
+/// dimension's minimum is meaningful
+fn thresholds_for(thresholds: Thresholds, only: Option<Dimension>) -> Thresholds {
+    match only {
+        Some(dimension) => Thresholds {
+            overall: None,
+            performance: thresholds
+                .performance
+                .filter(|_| dimension == Dimension::Performance),
+            energy: thresholds.energy.filter(|_| dimension == Dimension::Energy),
+            cost: thresholds.cost.filter(|_| dimension == Dimension::Cost),
+        },
+        None => thresholds,
+    }
+}
+
+/// Open `target` (a file path or URL) in the default browser.
+///
+/// Failure, e.g. on a headless machine, is only logged.
+async fn open_in_browser(target: String) {
+    let opened = tokio::task::spawn_blocking({
+        let target = target.clone();
+        move || open::that(target)
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+    match opened {
+        Ok(()) => info!("Opened {} in the browser", target),
+        Err(e) => warn!("Could not open {} in a browser: {}", target, e),
+    }
+}
+
+/// File name given to source piped to `crabscore score -`
+const STDIN_SOURCE_FILE: &str = "stdin.rs";
+
+/// The directories matching `path` when it is a shell-style glob such as
+/// `crates/*`, in sorted order; `None` for a literal path (including an
+/// existing path that merely contains glob characters).
+///
+/// Fails when the pattern is invalid or matches no directory.
+fn expand_path_glob(path: &str) -> Result<Option<Vec<PathBuf>>> {
+    if !path.contains(['*', '?', '[']) || Path::new(path).exists() {
+        return Ok(None);
+    }
+    let dirs: Vec<PathBuf> = glob::glob(path)
+        .with_context(|| format!("invalid glob pattern {path:?}"))?
+        .flatten()
+        .filter(|entry| entry.is_dir())
+        .collect();
+    if dirs.is_empty() {
+        anyhow::bail!("No directory matches {path:?}");
+    }
+    Ok(Some(dirs))
+}
+
+/// Copy source read from `stdin` into [`STDIN_SOURCE_FILE`] in a fresh
+/// temporary directory, returning the directory (which removes the file when
+/// dropped) and the file.
+///
+/// Outside any Cargo project and not executable, the file is scored from
+/// static analysis alone, with no dependencies.
+fn read_stdin_source(mut stdin: impl std::io::Read) -> Result<(tempfile::TempDir, PathBuf)> {
+    let dir = tempfile::tempdir().context("failed to create a directory for stdin source")?;
+    let file = dir.path().join(STDIN_SOURCE_FILE);
+    let mut source = Vec::new();
+    stdin
+        .read_to_end(&mut source)
+        .context("failed to read source from stdin")?;
+    std::fs::write(&file, source).with_context(|| format!("failed to write {}", file.display()))?;
+    Ok((dir, file))
+}
+
+/// Whether `input_path` is, or sits directly in, a Cargo project
+fn is_cargo_project(input_path: &Path) -> bool {
+    input_path.join("Cargo.toml").exists()
+        || input_path
+            .parent()
+            .map(|p| p.join("Cargo.toml").exists())
+            .unwrap_or(false)
+}
+
+/// What `crabscore score` would do for a path, reported by `--dry-run`
+#[derive(Debug, serde::Serialize)]
+struct ScorePlan {
+    path: String,
+    cargo_project: bool,
+    files: usize,
+    lines: usize,
+    functions: usize,
+    /// `criterion`, `benchmark` or `static`
+    mode: &'static str,
+    criterion: bool,
+    binaries: Vec<BinaryPlan>,
+    http_endpoint: Option<String>,
+    energy: String,
+    cost: String,
+    profile: IndustryProfile,
+}
+
+/// Work out what [`score_path_detailed`] would do, without building, benchmarking
+/// or running the safety analysis.
+async fn plan_score(input_path: &Path, opts: &ScoreOptions) -> Result<ScorePlan> {
+    let is_cargo_project = is_cargo_project(input_path);
+    let complexity = analyze_project_complexity(input_path, &opts.complexity_options()).await?;
+
+    let criterion = opts.use_criterion && input_path.is_dir() && criterion::has_benches(input_path);
+    let binaries = if criterion {
+        Vec::new()
+    } else {
+        plan_binaries(
+            input_path,
+            &opts.bins,
+            opts.all_bins,
+            opts.target.as_ref(),
+            is_cargo_project,
+            &opts.build,
+        )
+    };
+    let mode = match (criterion, binaries.is_empty()) {
+        (true, _) => "criterion",
+        (false, false) => "benchmark",
+        (false, true) => "static",
+    };
+
+    let (energy, cost) = if mode == "static" {
+        let estimated = "estimated from project complexity".to_string();
+        (estimated.clone(), estimated)
+    } else {
+        let analysis_root = if is_cargo_project {
+            input_path
+        } else {
+            input_path.parent().unwrap_or(Path::new("."))
+        };
+        let path = StaticCostProvider::new(
+            opts.cost_file
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_COST_FILE)),
+        )
+        .resolve(analysis_root);
+        let cost = if path.is_file() {
+            format!("{}", path.display())
+        } else {
+            format!("defaults ({} not found)", path.display())
+        };
+        (PlatformMonitor::detect().await.name().to_string(), cost)
+    };
+
+    Ok(ScorePlan {
+        path: input_path.display().to_string(),
+        cargo_project: is_cargo_project,
+        files: complexity.file_count,
+        lines: complexity.total_lines,
+        functions: complexity.function_count,
+        mode,
+        criterion,
+        binaries,
+        http_endpoint: opts.http_endpoint.clone(),
+        energy,
+        cost,
+        profile: opts.profile,
+    })
+}
+
+/// Display the result of `--list-bins`
+fn display_bin_targets(out: &mut dyn Write, bins: &[BinTarget], format: ScoreFormat) -> Result<()> {
+    match format {
+        ScoreFormat::Human if bins.is_empty() => {
+            writeln!(out, "{}", "No binary targets found".yellow())?
+        }
+        ScoreFormat::Human => {
+            writeln!(out, "{}", "Binary targets:".bold())?;
+            for bin in bins {
+                writeln!(
+                    out,
+                    "  {} {}",
+                    bin.name,
+                    format!("({})", bin.package).dimmed()
+                )?;
+            }
+            writeln!(out, "\nBenchmark one with `--bin <NAME>`")?;
+        }
+        ScoreFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(bins)?)?,
+        ScoreFormat::Toml => {
+            #[derive(serde::Serialize)]
+            struct BinList<'a> {
+                bins: &'a [BinTarget],
+            }
+            write!(out, "{}", toml::to_string_pretty(&BinList { bins })?)?
+        }
+        ScoreFormat::Yaml => write!(out, "{}", serde_yaml::to_string(bins)?)?,
+    }
+    Ok(())
+}
+
+/// Display the result of `--dry-run`
+fn display_dry_run(out: &mut dyn Write, plan: &ScorePlan) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    writeln!(out, "\n{}", "CrabScore Dry Run".bold().bright_white())?;
+    writeln!(out, "{}", output::rule(50, color).bright_white())?;
+    writeln!(
+        out,
+        "{}: {}{}",
+        "Path".bold(),
+        plan.path,
+        if plan.cargo_project {
+            " (Cargo project)"
+        } else {
+            ""
+        }
+    )?;
+    writeln!(
+        out,
+        "  Files: {}, lines: {}, functions: {}",
+        plan.files, plan.lines, plan.functions
+    )?;
+
+    writeln!(out, "\n{}", "Benchmarks:".bold())?;
+    if plan.criterion {
+        writeln!(out, "  - run `cargo bench` and score the Criterion results")?;
+    }
+    for binary in &plan.binaries {
+        writeln!(out, "  - {binary}")?;
     }
+    if let Some(url) = &plan.http_endpoint {
+        writeln!(out, "  - send HTTP GET requests to {url}")?;
+    }
+    if plan.mode == "static" {
+        writeln!(
+            out,
+            "  - {}",
+            "none: no executable available, metrics will be estimated".yellow()
+        )?;
+    }
+
+    writeln!(out, "\n{}: {}", "Energy".bold(), plan.energy)?;
+    writeln!(out, "{}: {}", "Cost".bold(), plan.cost)?;
+    writeln!(out, "{}: {:?}", "Profile".bold(), plan.profile)
+}
 
+/// Print a short summary of the analyzed sources
+fn display_analysis_summary(out: &mut dyn Write, complexity: &ProjectComplexity) -> Result<()> {
+    writeln!(out, "{}", "Analyzing Rust project...".bright_cyan())?;
+    writeln!(out, "  Files: {}", complexity.file_count)?;
+    writeln!(out, "  Lines of code: {}", complexity.total_lines)?;
+    writeln!(out, "  Functions: {}", complexity.function_count)?;
     Ok(())
 }
 
+/// Like [`score_path`], but also return the analysis behind the score.
+///
+/// This is the pipeline behind `crabscore score` and `crabscore workspace`.
+pub async fn score_path_detailed(input_path: &Path, opts: &ScoreOptions) -> Result<ScoredProject> {
+    if !input_path.exists() {
+        anyhow::bail!("{} does not exist", input_path.display());
+    }
+    let is_cargo_project = is_cargo_project(input_path);
+    let mut warnings = Vec::new();
+    if workspace::is_virtual_workspace(input_path) {
+        let warning = format!(
+            "{} is a virtual workspace: this score aggregates all of its members - \
+             run `crabscore workspace` for a score per crate",
+            input_path.display()
+        );
+        warn!("{}", warning);
+        warnings.push(warning);
+    }
+
+    // Restrict analysis to changed files, or fall back to the whole project
+    let scoped;
+    let mut diff_base = None;
+    let opts = match &opts.since {
+        Some(base) => match crate::git::changed_files(input_path, base).await {
+            Ok(files) => {
+                warnings.push(format!(
+                    "Partial score: only the {} files changed since {base} were analyzed",
+                    files.len()
+                ));
+                diff_base = Some(base.clone());
+                scoped = ScoreOptions {
+                    only_files: Some(files),
+                    ..opts.clone()
+                };
+                &scoped
+            }
+            Err(e) => {
+                let warning = format!("Cannot diff against {base}: {e:#} - analyzed all files");
+                warn!("{}", warning);
+                warnings.push(warning);
+                opts
+            }
+        },
+        None => opts,
+    };
+
+    // Analyze project complexity for better scoring
+    let project_complexity =
+        analyze_project_complexity(input_path, &opts.complexity_options()).await?;
+
+    // Only static analysis runs concurrently with other projects
+    let mut measuring = Some(opts.measurement_lock.lock().await);
+
+    // In-process benchmarks take precedence over timing a binary
+    let criterion =
+        if opts.use_criterion && input_path.is_dir() && opts.collects(Dimension::Performance) {
+            opts.progress.phase("Running cargo bench");
+            let target_dir = target_dir(input_path).await;
+            criterion::run_criterion(input_path, &target_dir)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Criterion benchmarks failed: {}", e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+    if opts.use_criterion && criterion.is_empty() {
+        warn!("No Criterion results found - falling back to binary benchmarks");
+        warnings.push("No Criterion results found".to_string());
+    }
+
+    // Try to find or build the binaries, but don't fail if we can't
+    let mut build_log = BuildLog::default();
+    let binary_paths = if !criterion.is_empty() || !opts.collects(Dimension::Performance) {
+        Vec::new()
+    } else if let Some(target) = &opts.target {
+        build_target(
+            input_path,
+            target,
+            &opts.build,
+            &opts.progress,
+            &mut build_log,
+        )
+        .await
+        .into_iter()
+        .collect()
+    } else if opts.bins.len() > 1 || opts.all_bins {
+        find_or_build_binaries(
+            input_path,
+            &opts.bins,
+            opts.all_bins,
+            &opts.build,
+            &opts.progress,
+            &mut build_log,
+        )
+        .await
+    } else {
+        find_or_build_binary(
+            input_path,
+            &opts.bins.first().cloned(),
+            is_cargo_project,
+            &opts.build,
+            &opts.progress,
+            &mut build_log,
+        )
+        .await
+        .into_iter()
+        .collect()
+    };
+    if opts.strict && !build_log.warnings.is_empty() {
+        anyhow::bail!("Build failed (--strict): {}", build_log.warnings.join("; "));
+    }
+    warnings.extend(build_log.score_warnings());
+    if binary_paths.is_empty() && criterion.is_empty() {
+        drop(measuring.take());
+    }
+
+    // Pin the benchmarks to a core, or run them unpinned when that is impossible
+    let unpinned;
+    let opts = match opts.pin_cpu.filter(|_| !binary_paths.is_empty()) {
+        Some(cpu) => match affinity::check_pinning(cpu).await {
+            Ok(()) => opts,
+            Err(e) => {
+                let warning =
+                    format!("CPU pinning unavailable: {e:#} - benchmarks were not pinned");
+                warn!("{}", warning);
+                warnings.push(warning);
+                unpinned = ScoreOptions {
+                    pin_cpu: None,
+                    ..opts.clone()
+                };
+                &unpinned
+            }
+        },
+        None => opts,
+    };
+
+    // Collect metrics - with graceful degradation
+    let mut metrics = if !criterion.is_empty() {
+        info!("Scoring {} Criterion benchmarks", criterion.len());
+        collect_criterion_metrics(&criterion, input_path, is_cargo_project, opts).await?
+    } else if !binary_paths.is_empty() {
+        for exe_path in &binary_paths {
+            info!("Found executable {} for benchmarking", exe_path.display());
+        }
+        collect_full_metrics(&binary_paths, input_path, is_cargo_project, opts).await?
+    } else if opts.only == Some(Dimension::Cost) {
+        // A measured run reads cost from the cost file, which needs no benchmark
+        collect_measured_metrics(
+            PerformanceMetrics::default(),
+            EnergyMetrics::default(),
+            Vec::new(),
+            Confidence::Measured,
+            input_path,
+            is_cargo_project,
+            opts,
+        )
+        .await?
+    } else {
+        if opts.collects(Dimension::Performance) {
+            info!("No executable found - using static analysis only");
+            warnings.push(STATIC_ONLY_WARNING.to_string());
+        }
+        collect_static_metrics(input_path, is_cargo_project, &project_complexity, opts).await?
+    };
+    metrics.safety.build_warnings = build_log.compiler_warnings;
+    if let Some(only) = opts.only {
+        metrics.keep_only(only);
+    }
+    let measured = !binary_paths.is_empty() || !criterion.is_empty();
+    let speed_factor = if opts.calibrate && measured {
+        opts.progress.phase("Calibrating machine speed");
+        let factor = tokio::task::spawn_blocking(calibration::speed_factor).await?;
+        info!(
+            "Normalizing latencies by machine speed factor {:.2}",
+            factor
+        );
+        calibration::normalize_performance(&mut metrics.perf, factor);
+        for target in &mut metrics.targets {
+            calibration::normalize_latency(&mut target.latency, factor);
+        }
+        Some(factor)
+    } else {
+        if opts.calibrate {
+            warnings.push("Nothing was benchmarked - calibration skipped".to_string());
+        }
+        None
+    };
+    drop(measuring);
+
+    // Calculate score with complexity-aware engine
+    let mut engine = ComplexityAwareScoringEngine::new(opts.profile, project_complexity.clone())
+        .with_safety_config(opts.safety)
+        .with_binary_size_config(opts.binary_size)
+        .with_max_bonus(opts.max_complexity_bonus);
+    if let Some(sla_ms) = opts.latency_sla_ms {
+        engine = engine.with_latency_sla(sla_ms);
+    }
+    let mut score = engine.calculate_score(
+        &metrics.perf,
+        &metrics.energy,
+        &metrics.cost,
+        &metrics.safety,
+    );
+    score.metadata.confidence = metrics.confidence;
+    score.metadata.measurements.targets = metrics.targets;
+    warnings.append(&mut metrics.warnings);
+    score.metadata.warnings = warnings;
+    score.metadata.diff_base = diff_base;
+    score.metadata.measurements.environment.speed_factor = speed_factor;
+    score.metadata.measurements.environment.pinned_cpu =
+        opts.pin_cpu.filter(|_| !binary_paths.is_empty());
+    if opts.check_estimates && measured {
+        score.metadata.calibration = Some(check_estimates(
+            &engine,
+            &project_complexity,
+            &opts.carbon,
+            &metrics.safety,
+            &score,
+        ));
+    }
+    let explanation = engine.explain(
+        &metrics.perf,
+        &metrics.energy,
+        &metrics.cost,
+        &metrics.safety,
+    );
+
+    Ok(ScoredProject {
+        score,
+        complexity: project_complexity,
+        static_only: !measured,
+        engine,
+        explanation,
+        safety: metrics.safety,
+        functions: metrics.functions,
+    })
+}
+
 // -----------------------------------------------------------------------------
 // Metrics Collection Functions
 // -----------------------------------------------------------------------------
 
-/// Collect full metrics when binary is available
+/// Warning for a failed benchmark of `exe`, telling a missing binary, a
+/// binary that always fails and a server that never came up apart.
+fn benchmark_failure(exe: &Path, e: &anyhow::Error) -> String {
+    let exe = exe.display();
+    match e.downcast_ref::<CrabScoreError>() {
+        Some(CrabScoreError::BinaryNotFound(_)) => {
+            format!("Benchmark of {exe} failed: the binary does not exist - build it first")
+        }
+        Some(CrabScoreError::AllIterationsFailed { attempts, .. }) => format!(
+            "Benchmark of {exe} failed: all {attempts} runs exited with an error - check its \
+             arguments and stdin"
+        ),
+        Some(CrabScoreError::Timeout { operation, after }) => {
+            format!("Benchmark of {exe} timed out: {operation} within {after:?}")
+        }
+        _ => format!("Benchmark of {exe} failed: {e}"),
+    }
+}
+
+/// `base` with the configured settings of one binary applied. `--stdin-file`
+/// (already in `base`) takes precedence over a configured stdin file.
+fn bench_options(base: &BenchmarkOptions, bench: &BinaryBench) -> Result<BenchmarkOptions> {
+    let mut options = base.clone();
+    if !bench.args.is_empty() {
+        options.args = bench.args.clone();
+    }
+    if let Some(iterations) = bench.iterations {
+        options.iterations = iterations;
+    }
+    if let Some(file) = bench.stdin.as_ref().filter(|_| base.stdin_data.is_none()) {
+        options.stdin_data = Some(
+            std::fs::read(file).with_context(|| format!("failed to read {}", file.display()))?,
+        );
+    }
+    Ok(options)
+}
+
+/// A binary to benchmark with its resolved options
+struct BenchTarget<'a> {
+    path: &'a Path,
+    name: String,
+    options: BenchmarkOptions,
+}
+
+/// Collect full metrics when one or more binaries are available
 async fn collect_full_metrics(
-    exe_path: &Path,
+    exe_paths: &[std::path::PathBuf],
     project_root: &Path,
     is_cargo_project: bool,
-) -> Result<(
-    PerformanceMetrics,
-    crabscore_core::metrics::EnergyMetrics,
-    SafetyMetrics,
-    CostMetrics,
-)> {
-    // Measure performance metrics
-    let runner = BenchmarkRunner::default();
-    let perf = runner.benchmark(exe_path).await.unwrap_or_else(|e| {
-        error!("Performance benchmark failed: {}", e);
-        PerformanceMetrics::default()
-    });
-
-    // Collect energy metrics
-    let monitor = crabscore_energy::interface::NullMonitor;
+    opts: &ScoreOptions,
+) -> Result<CollectedMetrics> {
+    // Measure performance metrics of every target
+    let mut confidence = Confidence::Measured;
+    let mut targets = Vec::with_capacity(exe_paths.len());
+    let mut samples = Vec::with_capacity(exe_paths.len());
+    let mut failures = Vec::new();
+    let mut first_error = None;
+    let bench_opts = BenchmarkOptions {
+        min_iterations_for_percentiles: opts.min_iterations_for_percentiles,
+        stdin_data: opts.stdin_data.clone(),
+        pin_cpu: opts.pin_cpu,
+        retries: opts.retries,
+        ..Default::default()
+    };
+    let bench_targets = exe_paths
+        .iter()
+        .map(|exe_path| {
+            let name = exe_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let options = match opts.bench.get(&name) {
+                Some(bench) => bench_options(&bench_opts, bench)?,
+                None => bench_opts.clone(),
+            };
+            Ok(BenchTarget {
+                path: exe_path,
+                name,
+                options,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    // Resolves to the number of measured operations
+    let benchmarks = async {
+        let mut operations = 0u64;
+        for target in &bench_targets {
+            let (exe_path, name) = (target.path, target.name.clone());
+            let progress = opts.progress.clone();
+            let label = name.clone();
+            let runner =
+                BenchmarkRunner::new(target.options.clone()).on_iteration(move |done, total| {
+                    progress.phase(format!("Benchmarking {label}: iteration {done}/{total}"))
+                });
+            let result = match &opts.http_endpoint {
+                Some(url) => {
+                    opts.progress.phase(format!(
+                        "Benchmarking {name}: {} HTTP requests",
+                        opts.http_requests
+                    ));
+                    runner
+                        .benchmark_http(exe_path, url, opts.http_requests)
+                        .await
+                }
+                None => runner.benchmark(exe_path).await,
+            };
+            match result {
+                Ok(perf) => {
+                    operations += match opts.http_endpoint {
+                        Some(_) => u64::from(opts.http_requests),
+                        None => u64::from(runner.executions()),
+                    };
+                    targets.push(TargetMeasurement {
+                        name,
+                        latency: perf.latency.clone(),
+                        binary_size_bytes: perf.resource_usage.binary_size_bytes,
+                    });
+                    samples.push(perf);
+                }
+                Err(e) => {
+                    let failure = benchmark_failure(exe_path, &e);
+                    error!("{}", failure);
+                    failures.push(failure);
+                    first_error.get_or_insert(e);
+                    confidence = Confidence::Partial;
+                }
+            }
+        }
+        operations
+    };
+
+    // Energy and CPU throttling are sampled over exactly the benchmark runs
+    let benchmarks = thermal::sample_during(benchmarks, THERMAL_SAMPLE_INTERVAL);
+    let monitor = PlatformMonitor::detect().await;
+    let energy = match monitor.sample_during(benchmarks).await {
+        Ok((window, (samples, operations))) => {
+            let mut energy = per_operation(window, operations);
+            energy.hardware_lifecycle.thermal_efficiency = thermal::thermal_efficiency(&samples)
+                .unwrap_or(thermal::DEFAULT_THERMAL_EFFICIENCY);
+            energy
+        }
+        Err(e) => {
+            warn!("Energy sampling failed: {}", e);
+            EnergyMetrics::default()
+        }
+    };
+    if let Some(e) = first_error.filter(|_| opts.strict) {
+        // Keep the first error as the cause, it selects the exit code
+        return Err(e.context(format!(
+            "Benchmark failed (--strict): {}",
+            failures.join("; ")
+        )));
+    }
+    if !monitor.has_telemetry() {
+        confidence = Confidence::Partial;
+        failures.push(NO_TELEMETRY_WARNING.to_string());
+    }
+    let perf = aggregate_performance(&samples, opts.bin_aggregate);
+
+    let mut metrics = collect_measured_metrics(
+        perf,
+        energy,
+        targets,
+        confidence,
+        project_root,
+        is_cargo_project,
+        opts,
+    )
+    .await?;
+    metrics.warnings.extend(failures);
+
+    // Replace the assumed algorithmic efficiency with the observed growth
+    if let Some(target) = bench_targets
+        .first()
+        .filter(|_| !opts.input_sizes.is_empty())
+    {
+        let exe_path = target.path;
+        opts.progress.phase(format!(
+            "Measuring scaling of {} over {} input sizes",
+            exe_path.display(),
+            opts.input_sizes.len()
+        ));
+        let runner = BenchmarkRunner::new(target.options.clone());
+        match runner
+            .benchmark_with_input_sizes(exe_path, &opts.input_sizes)
+            .await
+        {
+            Ok(Some(fit)) => {
+                info!(
+                    "{} scales as {} (exponent {:.2}, r² {:.2})",
+                    exe_path.display(),
+                    fit.time_complexity,
+                    fit.exponent,
+                    fit.r_squared
+                );
+                fit.apply(&mut metrics.energy.algorithmic_efficiency);
+            }
+            Ok(None) => {
+                warn!("Not enough successful runs to infer time complexity");
+                metrics
+                    .warnings
+                    .push("Not enough successful runs to infer time complexity".to_string());
+            }
+            Err(e) => {
+                warn!("Scaling benchmark of {} failed: {}", exe_path.display(), e);
+                metrics
+                    .warnings
+                    .push(format!("Scaling benchmark failed: {e}"));
+            }
+        }
+    }
+
+    if opts.trace_network {
+        opts.progress.phase("Tracing network egress");
+        match trace_egress_bytes(&bench_targets).await {
+            Ok(bytes) => {
+                info!("Traced {} bytes of network egress per run", bytes);
+                metrics.cost.infrastructure.network_egress_usd =
+                    egress_cost_usd(bytes, opts.egress_price_per_gb, opts.runs_per_month);
+            }
+            Err(e) => {
+                let warning =
+                    format!("Network tracing unavailable: {e:#} - egress cost not measured");
+                warn!("{}", warning);
+                metrics.warnings.push(warning);
+            }
+        }
+    }
+    Ok(metrics)
+}
+
+/// Bytes sent to the network by one run of every binary
+#[cfg(target_os = "linux")]
+async fn trace_egress_bytes(targets: &[BenchTarget<'_>]) -> Result<u64> {
+    let mut bytes = 0;
+    for target in targets {
+        bytes += BenchmarkRunner::new(target.options.clone())
+            .trace_network_egress(target.path)
+            .await?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn trace_egress_bytes(_targets: &[BenchTarget<'_>]) -> Result<u64> {
+    Err(CrabScoreError::unsupported("syscall tracing requires Linux").into())
+}
+
+/// Monthly egress cost of sending `bytes_per_run` on each of `runs_per_month`
+/// runs at `price_per_gb`.
+fn egress_cost_usd(bytes_per_run: u64, price_per_gb: f64, runs_per_month: u64) -> f64 {
+    bytes_per_run as f64 / 1e9 * price_per_gb * runs_per_month as f64
+}
+
+/// Collect metrics from Criterion benchmark results
+async fn collect_criterion_metrics(
+    benches: &[CriterionBenchmark],
+    project_root: &Path,
+    is_cargo_project: bool,
+    opts: &ScoreOptions,
+) -> Result<CollectedMetrics> {
+    let samples: Vec<_> = benches.iter().map(|b| b.performance.clone()).collect();
+    let targets = benches
+        .iter()
+        .map(|b| TargetMeasurement {
+            name: b.name.clone(),
+            latency: b.performance.latency.clone(),
+            binary_size_bytes: None,
+        })
+        .collect();
+    let perf = aggregate_performance(&samples, opts.bin_aggregate);
+    let monitor = PlatformMonitor::detect().await;
     let energy = monitor.collect().await.unwrap_or_default();
+    let confidence = if monitor.has_telemetry() {
+        Confidence::Measured
+    } else {
+        Confidence::Partial
+    };
+
+    let mut metrics = collect_measured_metrics(
+        perf,
+        energy,
+        targets,
+        confidence,
+        project_root,
+        is_cargo_project,
+        opts,
+    )
+    .await?;
+    if !monitor.has_telemetry() {
+        metrics.warnings.push(NO_TELEMETRY_WARNING.to_string());
+    }
+    Ok(metrics)
+}
+
+/// Complete measured performance and energy with safety and cost metrics
+async fn collect_measured_metrics(
+    perf: PerformanceMetrics,
+    mut energy: EnergyMetrics,
+    targets: Vec<TargetMeasurement>,
+    mut confidence: Confidence,
+    project_root: &Path,
+    is_cargo_project: bool,
+    opts: &ScoreOptions,
+) -> Result<CollectedMetrics> {
+    // Monitors measure power, not the grid it is drawn from
+    opts.carbon.apply(&mut energy.carbon_efficiency);
 
     // Safety metrics via static analysis
     let analysis_root = if is_cargo_project {
@@ -142,118 +2060,1536 @@ async fn collect_full_metrics(
     } else {
         project_root.parent().unwrap_or(Path::new("."))
     };
-    let safety = analysis::run(analysis_root.to_str().unwrap()).unwrap_or_default();
+    let (safety, functions) = safety_analysis(analysis_root, opts)?;
 
     // Cost metrics
-    let cost_provider = StaticCostProvider::new("cost.json");
-    let cost = cost_provider
-        .collect(analysis_root.to_str().unwrap())
-        .await
-        .unwrap_or_else(|_| {
-            warn!("Cost provider returned no data – using defaults");
-            CostMetrics::default()
-        });
+    let mut warnings = Vec::new();
+    let mut cost = if opts.collects(Dimension::Cost) {
+        collect_cost(analysis_root, opts, &mut warnings)
+            .await?
+            .unwrap_or_else(|| {
+                confidence = Confidence::Partial;
+                CostMetrics::default()
+            })
+    } else {
+        CostMetrics::default()
+    };
+    // Real churn from git history, unless the cost file gives one
+    if opts.collects(Dimension::Cost) && is_cargo_project && cost.development.code_churn == 0.0 {
+        if let Some(churn) = git_churn(analysis_root).await {
+            cost.development.code_churn = churn;
+        }
+    }
+
+    Ok(CollectedMetrics {
+        perf,
+        energy,
+        safety,
+        functions,
+        cost,
+        confidence,
+        targets,
+        warnings,
+    })
+}
+
+/// Safety metrics and ranked functions of the sources under `root`.
+///
+/// Sources that fail to parse leave the defaults, or fail with `--strict`.
+fn safety_analysis(
+    root: &Path,
+    opts: &ScoreOptions,
+) -> Result<(SafetyMetrics, Vec<FunctionComplexity>)> {
+    if !opts.collects(Dimension::Safety) {
+        return Ok(Default::default());
+    }
+    opts.progress.phase("Running safety analysis");
+    match analysis::run_ranked(&root.to_string_lossy(), &opts.analysis_options()) {
+        Ok(analysis) => Ok(analysis),
+        Err(e) if opts.strict => Err(e.context("Safety analysis failed (--strict)")),
+        Err(e) => {
+            warn!(
+                "Safety analysis failed, using default safety metrics: {:#}",
+                e
+            );
+            Ok(Default::default())
+        }
+    }
+}
+
+/// Lines changed in the Rust files under `root` over the last
+/// [`CHURN_WINDOW_DAYS`] days, or `None` without git history
+async fn git_churn(root: &Path) -> Option<f64> {
+    match crate::git::code_churn(root, CHURN_WINDOW_DAYS).await {
+        Ok(lines) => Some(lines as f64),
+        Err(e) => {
+            debug!("No git history for code churn: {e:#}");
+            None
+        }
+    }
+}
 
-    Ok((perf, energy, safety, cost))
+/// Read the project's cost file; `None` (with a warning) when it is missing
+/// or invalid and default cost metrics must be used, or an error with
+/// `--strict`
+async fn collect_cost(
+    analysis_root: &Path,
+    opts: &ScoreOptions,
+    warnings: &mut Vec<String>,
+) -> Result<Option<CostMetrics>> {
+    let cost_provider = StaticCostProvider::new(
+        opts.cost_file
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_COST_FILE)),
+    );
+    let e = match cost_provider.collect(analysis_root).await {
+        Ok(cost) => return Ok(Some(cost)),
+        Err(e) => e,
+    };
+    let path = cost_provider.resolve(analysis_root);
+    let problem = if path.is_file() {
+        format!("Invalid cost file {}: {e:#}", path.display())
+    } else {
+        format!("Cost file {} not found", path.display())
+    };
+    if opts.strict {
+        anyhow::bail!("{problem} (--strict)");
+    }
+    let warning = format!("{problem} - using default cost metrics");
+    warn!("{}", warning);
+    warnings.push(warning);
+    Ok(None)
 }
 
 /// Collect static metrics when no binary is available
 async fn collect_static_metrics(
     project_root: &Path,
-    _is_cargo_project: bool,
+    is_cargo_project: bool,
     complexity: &ProjectComplexity,
-) -> Result<(
-    PerformanceMetrics,
-    crabscore_core::metrics::EnergyMetrics,
-    SafetyMetrics,
-    CostMetrics,
-)> {
+    opts: &ScoreOptions,
+) -> Result<CollectedMetrics> {
     // Estimate performance based on code complexity
     let estimated_perf = estimate_performance_from_complexity(complexity);
 
     // Energy metrics - estimate based on complexity
-    let estimated_energy = estimate_energy_from_complexity(complexity);
+    let estimated_energy = estimate_energy_from_complexity(complexity, &opts.carbon);
 
     // Safety metrics via static analysis
-    let safety = analysis::run(project_root.to_str().unwrap()).unwrap_or_default();
+    let (safety, functions) = safety_analysis(project_root, opts)?;
+
+    // Cost metrics - estimate based on complexity, with real churn from git history
+    let mut estimated_cost = estimate_cost_from_complexity(complexity);
+    if is_cargo_project {
+        if let Some(churn) = git_churn(project_root).await {
+            estimated_cost.development.code_churn = churn;
+        }
+    }
+    let mut warnings = Vec::new();
+    if opts.trace_network {
+        warnings.push("No binary to trace - network egress cost is estimated".to_string());
+    }
+
+    Ok(CollectedMetrics {
+        perf: estimated_perf,
+        energy: estimated_energy,
+        safety,
+        functions,
+        cost: estimated_cost,
+        confidence: Confidence::Estimated,
+        targets: Vec::new(),
+        warnings,
+    })
+}
+
+/// Print the per-dimension table of `comparison`, after any warning that the
+/// reports come from different machines
+fn display_comparison(
+    out: &mut dyn Write,
+    comparison: &compare::Comparison,
+) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    for warning in &comparison.warnings {
+        writeln!(out, "{} {}", "WARNING:".bold().red(), warning.bold())?;
+    }
+    writeln!(out, "\n{}", "CrabScore Comparison".bold().bright_white())?;
+    writeln!(out, "{}", output::rule(50, color).bright_white())?;
+    writeln!(
+        out,
+        "{}",
+        output::comparison_table(&comparison.dimensions, color)
+    )?;
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "Changes within {}% of the baseline are shown as unchanged",
+            comparison.tolerance_pct
+        )
+        .dimmed()
+    )
+}
+
+/// Show how `score` of `project` moved since the last `--baseline-auto` run
+/// recorded in the history (when `show`), then record it as the next
+/// baseline.
+///
+/// Failing to record is only logged.
+fn compare_with_history(
+    out: &mut dyn Write,
+    project: &Path,
+    score: &CrabScore,
+    show: bool,
+) -> Result<()> {
+    let Some(history) = history::default_path() else {
+        warn!("No home directory for the score history - --baseline-auto ignored");
+        return Ok(());
+    };
+    let project = std::fs::canonicalize(project)
+        .with_context(|| format!("failed to resolve {}", project.display()))?;
+    let baseline = history::latest_for(&history, &project)?;
+    if show {
+        match &baseline {
+            Some(baseline) => display_comparison(
+                out,
+                &compare::compare(baseline, score, compare::DEFAULT_TOLERANCE_PCT),
+            )?,
+            None => writeln!(
+                out,
+                "\n{}",
+                format!(
+                    "No earlier score of {} in the history - this run is the new baseline",
+                    project.display()
+                )
+                .dimmed()
+            )?,
+        }
+    }
+    if let Err(e) = history::record(&history, &project, score) {
+        warn!("Could not record the score history: {:#}", e);
+    }
+    Ok(())
+}
+
+/// Scores of `project` recorded by `--baseline-auto`, oldest first, for the
+/// dashboard's time series; empty (with a warning) when unreadable.
+fn report_history(project: &Path) -> Vec<CrabScore> {
+    let Some(history) = history::default_path() else {
+        return Vec::new();
+    };
+    let loaded = std::fs::canonicalize(project)
+        .map_err(anyhow::Error::from)
+        .and_then(|project| history::for_project(&history, &project));
+    loaded.unwrap_or_else(|e| {
+        warn!("Could not read the score history: {:#}", e);
+        Vec::new()
+    })
+}
+
+/// Show the one dimension computed with `--only`, without the overall score.
+fn display_dimension(
+    out: &mut dyn Write,
+    result: &ScoredProject,
+    dimension: Dimension,
+    safety: &SafetyScoringConfig,
+) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    let title = format!("CrabScore: {} only", dimension.name());
+    writeln!(out, "\n{}", title.bold().bright_white())?;
+    writeln!(out, "{}", output::rule(50, color).bright_white())?;
+    writeln!(
+        out,
+        "{}",
+        "Note: other dimensions were not computed, so there is no overall score".dimmed()
+    )?;
+
+    let explanation = &result.explanation;
+    let dim = match dimension {
+        Dimension::Performance => &explanation.performance,
+        Dimension::Energy => &explanation.energy,
+        Dimension::Cost => &explanation.cost,
+        Dimension::Safety => {
+            let bonuses = safety.bonuses(&result.safety);
+            for bonus in &bonuses {
+                writeln!(
+                    out,
+                    "  [{}] {:24} {:24} value {:>8.3}  {:+.1}",
+                    if bonus.earned { "x" } else { " " },
+                    bonus.name,
+                    bonus.rule,
+                    bonus.value,
+                    bonus.points
+                )?;
+            }
+            if let Some(n) = result.safety.build_warnings {
+                writeln!(out, "  compiler warnings in build: {n}")?;
+            }
+            writeln!(
+                out,
+                "  panic sites (unwrap/expect/panic!): {}",
+                result.safety.panic_sites
+            )?;
+            let total: f64 = bonuses.iter().map(|b| b.points).sum();
+            return writeln!(out, "\n{}: +{:.1}", "Safety bonus".bold(), total);
+        }
+    };
+    for input in &dim.inputs {
+        writeln!(out, "  input      {:24} {:>12.3}", input.name, input.value)?;
+    }
+    for sub in &dim.sub_scores {
+        writeln!(out, "  sub-score  {:24} {:>12.2}", sub.name, sub.value)?;
+    }
+    writeln!(out, "\n{}: {:.1}/100", "Score".bold(), dim.score)
+}
+
+/// List the `n` most complex of `functions` (already ranked).
+fn display_complex_functions(
+    out: &mut dyn Write,
+    functions: &[FunctionComplexity],
+    n: usize,
+) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    writeln!(out, "\n{}", "Most Complex Functions".bold().bright_white())?;
+    if functions.is_empty() {
+        return writeln!(out, "{}", "No functions found".dimmed());
+    }
+    let top = &functions[..n.min(functions.len())];
+    writeln!(out, "{}", output::complex_functions_table(top, color))
+}
 
-    // Cost metrics - estimate based on complexity
-    let estimated_cost = estimate_cost_from_complexity(complexity);
+/// Explain the gap to the next certification level.
+fn display_next_tier(
+    out: &mut dyn Write,
+    score: &CrabScore,
+    bands: &CertificationBands,
+) -> std::io::Result<()> {
+    writeln!(out, "\n{}", "Next Certification Tier".bold().bright_white())?;
+    let Some(next) = score.next_tier(bands) else {
+        return writeln!(
+            out,
+            "The overall score already earns the highest level it can award"
+        );
+    };
+    writeln!(
+        out,
+        "{} needs an overall score of {:.1} ({:.1} more)",
+        next.certification, next.required_overall, next.gap
+    )?;
+    let advice = format!(
+        "Improve {}: it can add up to {:.1} points",
+        next.dimension, next.headroom
+    );
+    if next.reachable {
+        writeln!(
+            out,
+            "{advice}; raising it by {:.1} closes the gap",
+            next.required_increase
+        )
+    } else {
+        writeln!(
+            out,
+            "{advice}, not enough on its own - other dimensions must improve too"
+        )
+    }
+}
 
-    Ok((estimated_perf, estimated_energy, safety, estimated_cost))
+/// Print the overall score and certification on a single line.
+fn display_quiet(
+    out: &mut dyn Write,
+    score: &CrabScore,
+    certification: &str,
+) -> std::io::Result<()> {
+    writeln!(out, "{:.1} {}", score.overall, certification)
 }
 
 /// Display results with complexity information
 fn display_results(
+    out: &mut dyn Write,
     score: &crabscore_core::CrabScore,
+    grade: char,
+    certification: &str,
     complexity: &ProjectComplexity,
     static_only: bool,
     engine: &ComplexityAwareScoringEngine,
-) {
-    println!("\n{}", "CrabScore Report".bold().bright_white());
-    println!("{}", "━".repeat(50).bright_white());
+) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    writeln!(out, "\n{}", "CrabScore Report".bold().bright_white())?;
+    writeln!(out, "{}", output::rule(50, color).bright_white())?;
 
     if static_only {
-        println!("{}", "Mode: Static Analysis Only".yellow());
-        println!(
+        writeln!(out, "{}", "Mode: Static Analysis Only".yellow())?;
+        writeln!(
+            out,
             "{}",
             "Note: Performance metrics are estimated based on project complexity".dimmed()
-        );
-        println!();
+        )?;
+        writeln!(out)?;
     }
 
-    let cert_str = format!("{:?}", score.certification);
-    println!(
+    writeln!(
+        out,
         "{}: {:.0}/100 [{}]",
         "Overall Score".bold(),
         score.overall,
-        cert_str.bright_yellow()
-    );
+        certification.bright_yellow()
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        "Grade".bold(),
+        grade.to_string().bright_yellow()
+    )?;
+    writeln!(
+        out,
+        "{}: {:?}",
+        "Confidence".bold(),
+        score.metadata.confidence
+    )?;
+
+    let targets = &score.metadata.measurements.targets;
+    if let [target] = targets.as_slice() {
+        if let Some(size) = target.binary_size_bytes {
+            writeln!(
+                out,
+                "{}: {}",
+                "Binary Size".bold(),
+                output::format_size(size)
+            )?;
+        }
+    }
+    if targets.iter().any(|t| !t.latency.reliable_percentiles) {
+        writeln!(
+            out,
+            "{}",
+            "Note: too few benchmark samples - p95/p99 latencies are unreliable".dimmed()
+        )?;
+    }
+    if !score.metadata.warnings.is_empty() {
+        writeln!(out, "\n{}", "Warnings:".bold().yellow())?;
+        for warning in &score.metadata.warnings {
+            writeln!(out, "  - {warning}")?;
+        }
+    }
+    if let Some(calibration) = &score.metadata.calibration {
+        writeln!(out, "\n{}", "Estimate Check:".bold())?;
+        for (dimension, check) in [
+            ("Performance", calibration.performance),
+            ("Energy", calibration.energy),
+            ("Cost", calibration.cost),
+        ] {
+            writeln!(
+                out,
+                "  {dimension}: estimated {:.1}, measured {:.1} ({:+.1})",
+                check.estimated, check.measured, check.error
+            )?;
+        }
+    }
 
-    println!("\n{}", "Breakdown:".bold());
-    print_score_bar("Performance", score.performance);
-    print_score_bar("Energy", score.energy);
-    print_score_bar("Cost", score.cost);
+    writeln!(out, "\n{}", "Breakdown:".bold())?;
+    writeln!(out, "{}", output::breakdown_table(score, color))?;
+
+    if targets.len() > 1 {
+        writeln!(out, "\n{}", "Targets:".bold())?;
+        writeln!(out, "{}", output::targets_table(targets, color))?;
+    }
 
     if score.bonuses > 0.0 {
-        println!("\n{}: +{:.1}", "Bonuses".bold(), score.bonuses);
+        writeln!(out, "\n{}: +{:.1}", "Bonuses".bold(), score.bonuses)?;
 
         // Show which bonuses were earned
         for (bonus_name, bonus_value) in engine.get_bonus_breakdown() {
-            println!("  {} {} (+{:.1})", "✓".green(), bonus_name, bonus_value);
+            writeln!(
+                out,
+                "  {} {} (+{:.1})",
+                output::check_mark(color).green(),
+                bonus_name,
+                bonus_value
+            )?;
+        }
+    }
+
+    writeln!(out, "\n{}", "Project Complexity:".bold())?;
+    writeln!(out, "  Files: {}", complexity.file_count)?;
+    writeln!(out, "  Lines: {}", complexity.total_lines)?;
+    writeln!(out, "  Functions: {}", complexity.function_count)?;
+    if complexity.generated_files > 0 {
+        writeln!(
+            out,
+            "  Generated files skipped: {}",
+            complexity.generated_files
+        )?;
+    }
+    if complexity.async_functions > 0 {
+        writeln!(
+            out,
+            "  Async functions: {} ({:.0}%, {} awaits)",
+            complexity.async_functions,
+            complexity.async_ratio() * 100.0,
+            complexity.await_points
+        )?;
+    }
+    write!(out, "  Dependencies: {}", complexity.dependency_count)?;
+    if let Some((transitive, depth)) = complexity
+        .transitive_dep_count
+        .zip(complexity.dep_tree_depth)
+    {
+        write!(out, " ({transitive} transitive, {depth} levels deep)")?;
+    }
+    writeln!(out)
+}
+
+/// Display the step-by-step derivation of a score
+fn display_explanation(out: &mut dyn Write, explanation: &ScoreExplanation) -> std::io::Result<()> {
+    let color = output::color_enabled();
+    writeln!(out, "\n{}", "CrabScore Explanation".bold().bright_white())?;
+    writeln!(out, "{}", output::rule(50, color).bright_white())?;
+    writeln!(out, "{}: {:?}", "Profile".bold(), explanation.profile)?;
+
+    for (name, dim) in [
+        ("Performance", &explanation.performance),
+        ("Energy", &explanation.energy),
+        ("Cost", &explanation.cost),
+    ] {
+        writeln!(out, "\n{}", format!("{name}:").bold())?;
+        for input in &dim.inputs {
+            writeln!(out, "  input      {:24} {:>12.3}", input.name, input.value)?;
+        }
+        for sub in &dim.sub_scores {
+            writeln!(out, "  sub-score  {:24} {:>12.2}", sub.name, sub.value)?;
         }
+        writeln!(
+            out,
+            "  score {:.2} (mean of sub-scores) x weight {:.2} = {:.2}",
+            dim.score, dim.weight, dim.contribution
+        )?;
+    }
+
+    writeln!(out, "\n{}", "Bonuses:".bold())?;
+    for bonus in &explanation.bonuses {
+        writeln!(
+            out,
+            "  [{}] {:24} {:24} value {:>8.3}  {:+.1}",
+            if bonus.earned { "x" } else { " " },
+            bonus.name,
+            bonus.rule,
+            bonus.value,
+            bonus.points
+        )?;
+    }
+    for note in &explanation.notes {
+        writeln!(out, "  note: {note}")?;
+    }
+
+    writeln!(
+        out,
+        "\n{}: {:.2} + {:.2} + {:.2} + bonuses {:.2} = {:.2}",
+        "Overall".bold(),
+        explanation.performance.contribution,
+        explanation.energy.contribution,
+        explanation.cost.contribution,
+        explanation.bonus_total,
+        explanation.overall
+    )
+}
+
+/// Display per-member scores followed by the weighted workspace roll-up,
+/// naming each member's certification from the matching `member_bands` and
+/// the aggregate's from `bands`
+fn display_workspace_results(
+    out: &mut dyn Write,
+    report: &WorkspaceScore,
+    member_bands: &[CertificationBands],
+    bands: &CertificationBands,
+    weight_by: crate::cli::WeightBy,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "\n{}",
+        "CrabScore Workspace Report".bold().bright_white()
+    )?;
+    writeln!(
+        out,
+        "{}",
+        output::rule(50, output::color_enabled()).bright_white()
+    )?;
+
+    let name_width = report
+        .members
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+    writeln!(
+        out,
+        "  {:name_width$}  {:>8}  {:>7}  {}",
+        "Member".bold(),
+        "Weight".bold(),
+        "Score".bold(),
+        "Certification".bold()
+    )?;
+    for (member, bands) in report.members.iter().zip(member_bands) {
+        writeln!(
+            out,
+            "  {:name_width$}  {:>8.0}  {:>3.0}/100  {}",
+            member.name,
+            member.weight,
+            member.score.overall,
+            member.score.certification_with(bands)
+        )?;
     }
 
-    println!("\n{}", "Project Complexity:".bold());
-    println!("  Files: {}", complexity.file_count);
-    println!("  Lines: {}", complexity.total_lines);
-    println!("  Functions: {}", complexity.function_count);
-    println!("  Dependencies: {}", complexity.dependency_count);
+    let aggregate = &report.aggregate;
+    writeln!(
+        out,
+        "\n{} ({:?}-weighted): {:.0}/100 [{}]",
+        "Aggregate Score".bold(),
+        weight_by,
+        aggregate.overall,
+        aggregate.certification_with(bands).bright_yellow()
+    )?;
+    writeln!(
+        out,
+        "{}",
+        output::breakdown_table(aggregate, output::color_enabled())
+    )
 }
 
 // -----------------------------------------------------------------------------
 fn init_logging(verbosity: u8) {
+    let filter = log_filter(verbosity, std::env::var("RUST_LOG").ok().as_deref());
+    // Logs go to stderr so they never mix with `--format json` on stdout.
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Log filter from a user-provided `rust_log` (the `RUST_LOG` value), falling
+/// back to the `-v` verbosity level when it is unset, empty or unparsable
+fn log_filter(verbosity: u8, rust_log: Option<&str>) -> tracing_subscriber::EnvFilter {
+    use tracing_subscriber::EnvFilter;
+
     let level = match verbosity {
         0 => "info",
         1 => "debug",
         _ => "trace",
     };
-    std::env::set_var("RUST_LOG", level);
-    let _ = tracing_subscriber::fmt::try_init();
+    rust_log
+        .filter(|directives| !directives.trim().is_empty())
+        .and_then(|directives| EnvFilter::try_new(directives).ok())
+        .unwrap_or_else(|| EnvFilter::new(level))
 }
 
-fn print_score_bar(name: &str, score: f64) {
-    let width = 20;
-    let filled = ((score / 100.0) * width as f64) as usize;
-    let bar = "█".repeat(filled) + &"░".repeat(width - filled);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use crabscore_core::testing::default_score;
 
-    let color = if score >= 80.0 {
-        bar.bright_green()
-    } else if score >= 60.0 {
-        bar.yellow()
-    } else {
-        bar.red()
-    };
+    #[test]
+    fn explanation_adds_up_to_the_score() {
+        let complexity = ProjectComplexity {
+            total_lines: 40,
+            function_count: 4,
+            doc_lines: 12,
+            items: 4,
+            documented_items: 4,
+            ..Default::default()
+        };
+        let engine =
+            ComplexityAwareScoringEngine::new(IndustryProfile::Financial, complexity.clone());
+        let metrics = (
+            estimate_performance_from_complexity(&complexity),
+            estimate_energy_from_complexity(&complexity, &CarbonConfig::default()),
+            estimate_cost_from_complexity(&complexity),
+            SafetyMetrics::default(),
+        );
+        let score = engine.calculate_score(&metrics.0, &metrics.1, &metrics.2, &metrics.3);
+        let explanation = engine.explain(&metrics.0, &metrics.1, &metrics.2, &metrics.3);
 
-    println!("  {:12} {:3.0}/100 {}", format!("{}:", name), score, color);
+        assert!((explanation.overall - score.overall).abs() < 1e-9);
+        assert!((explanation.bonus_total - score.bonuses).abs() < 1e-9);
+        assert_eq!(explanation.performance.weight, 0.5);
+        assert!(explanation
+            .bonuses
+            .iter()
+            .any(|b| b.earned && b.rule == "total_lines < 100"));
+
+        let json = serde_json::to_value(&explanation).unwrap();
+        assert!(json["performance"]["sub_scores"].is_array());
+
+        let mut out = Vec::new();
+        display_explanation(&mut out, &explanation).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("doc_coverage > 0.8"));
+    }
+
+    #[test]
+    fn no_color_flag_produces_plain_ascii() {
+        // `--no-color` sets a process-wide override; undo it however the test ends
+        struct RestoreColor;
+        impl Drop for RestoreColor {
+            fn drop(&mut self) {
+                colored::control::unset_override();
+            }
+        }
+        let cli = crate::cli::Cli::try_parse_from(["crabscore", "--no-color", "version"]).unwrap();
+        let _restore = RestoreColor;
+        output::init_color(cli.no_color);
+
+        let complexity = ProjectComplexity {
+            file_count: 1,
+            total_lines: 40,
+            function_count: 4,
+            doc_lines: 12,
+            ..Default::default()
+        };
+        let engine =
+            ComplexityAwareScoringEngine::new(IndustryProfile::default(), complexity.clone());
+        let score = engine.calculate_score(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &SafetyMetrics::default(),
+        );
+        assert!(score.bonuses > 0.0);
+
+        let mut out = Vec::new();
+        display_results(
+            &mut out,
+            &score,
+            'B',
+            "Verified",
+            &complexity,
+            true,
+            &engine,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("CrabScore Report"));
+        assert!(text.contains("Grade: B"));
+        assert!(text.is_ascii(), "unexpected non-ASCII output:\n{text}");
+    }
+
+    #[test]
+    fn quiet_output_is_a_single_line() {
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = 86.04;
+        score.certification = crabscore_core::Certification::Certified;
+
+        let mut out = Vec::new();
+        display_quiet(&mut out, &score, "Certified").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "86.0 Certified\n");
+
+        let cli = crate::cli::Cli::try_parse_from(["crabscore", "score", "-q", "--explain"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn configured_bench_settings_merge_with_cli_options() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/bench_manifest");
+        let config = CrabScoreConfig::load(&root).unwrap();
+        let base = BenchmarkOptions::default();
+
+        let server = bench_options(&base, &config.bench["server"]).unwrap();
+        assert_eq!(server.args, ["--port", "0", "--workers", "2"]);
+        assert_eq!(server.iterations, 20);
+        assert_eq!(server.stdin_data, None);
+
+        let parser = bench_options(&base, &config.bench["parser"]).unwrap();
+        assert_eq!(parser.args, base.args);
+        assert_eq!(parser.iterations, base.iterations);
+        assert_eq!(
+            parser.stdin_data.as_deref(),
+            Some(&b"{\"records\": [1, 2, 3]}\n"[..])
+        );
+
+        // --stdin-file wins over the configured file
+        let cli = BenchmarkOptions {
+            stdin_data: Some(b"cli".to_vec()),
+            ..Default::default()
+        };
+        let parser = bench_options(&cli, &config.bench["parser"]).unwrap();
+        assert_eq!(parser.stdin_data.as_deref(), Some(&b"cli"[..]));
+
+        let missing = BinaryBench {
+            stdin: Some(root.join("inputs/missing.json")),
+            ..Default::default()
+        };
+        assert!(bench_options(&base, &missing).is_err());
+    }
+
+    #[test]
+    fn benchmark_failures_are_worded_by_cause() {
+        let exe = Path::new("target/release/app");
+        let failure = |e: CrabScoreError| benchmark_failure(exe, &e.into());
+        assert!(failure(CrabScoreError::BinaryNotFound(exe.into())).contains("does not exist"));
+        assert!(failure(CrabScoreError::AllIterationsFailed {
+            target: "app".into(),
+            attempts: 5,
+        })
+        .contains("all 5 runs exited with an error"));
+        assert!(failure(CrabScoreError::Timeout {
+            operation: "server did not start listening on 127.0.0.1:8080".into(),
+            after: std::time::Duration::from_secs(30),
+        })
+        .contains("timed out: server did not start listening on 127.0.0.1:8080 within 30s"));
+        assert_eq!(
+            benchmark_failure(exe, &anyhow::anyhow!("boom")),
+            "Benchmark of target/release/app failed: boom"
+        );
+    }
+
+    #[test]
+    fn reports_are_written_to_the_output_dir() {
+        let score = default_score(IndustryProfile::default());
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("reports/nested");
+        let files = ReportFiles {
+            format: ReportFormat::Json,
+            compress: None,
+            raw: false,
+            exports: vec![ExportFormat::Sbom, ExportFormat::Sarif],
+        };
+
+        let written = files
+            .write(&dir, &score, &CrabScoreConfig::default())
+            .unwrap();
+        assert_eq!(written.data, dir.join("crabscore_report.json"));
+        assert_eq!(written.html, dir.join("crabscore_report.html"));
+        assert_eq!(
+            written.exports,
+            [dir.join("report_sbom.json"), dir.join("report.sarif")]
+        );
+        let mut names: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "crabscore_report.html",
+                "crabscore_report.json",
+                "report.sarif",
+                "report_sbom.json"
+            ]
+        );
+        let data = std::fs::read_to_string(&written.data).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&data).is_ok());
+    }
+
+    #[test]
+    fn yaml_reports_use_the_configured_grade_and_tier() {
+        use crabscore_core::{CertificationBand, GradeBands};
+
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = 72.345;
+        let config = CrabScoreConfig {
+            grades: GradeBands {
+                a: 70.0,
+                b: 60.0,
+                c: 50.0,
+                d: 40.0,
+            },
+            certifications: CertificationBands(vec![CertificationBand {
+                name: "Bronze".into(),
+                min_overall: 70.0,
+            }]),
+            ..Default::default()
+        };
+
+        let report: generator::JsonReport =
+            serde_yaml::from_str(&report_yaml(&score, false, &config).unwrap()).unwrap();
+        assert_eq!(report.grade, 'A');
+        assert_eq!(report.certification_tier.as_deref(), Some("Bronze"));
+        assert_eq!(report.score.overall, 72.35);
+        let raw: generator::JsonReport =
+            serde_yaml::from_str(&report_yaml(&score, true, &config).unwrap()).unwrap();
+        assert_eq!(raw.score.overall, 72.345);
+    }
+
+    #[test]
+    fn next_tier_names_the_dimension_to_improve() {
+        let mut score = default_score(IndustryProfile::default());
+        (score.performance, score.energy, score.cost) = (80.0, 70.0, 85.0);
+        score.overall = 78.0;
+
+        let mut out = Vec::new();
+        display_next_tier(&mut out, &score, &CertificationBands::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.contains("Certified needs an overall score of 85.0 (7.0 more)"),
+            "{out}"
+        );
+        assert!(out.contains("Improve energy"), "{out}");
+        assert!(out.contains("raising it by 23.3 closes the gap"), "{out}");
+
+        let cli = crate::cli::Cli::try_parse_from([
+            "crabscore",
+            "score",
+            "--next-tier",
+            "--format",
+            "json",
+        ]);
+        assert!(cli.is_err());
+    }
+
+    #[tokio::test]
+    async fn network_tracing_costs_egress_or_degrades() {
+        // 1 kB per run over a million runs is 1 GB
+        assert!((egress_cost_usd(1_000, 0.09, DEFAULT_RUNS_PER_MONTH) - 0.09).abs() < 1e-12);
+        assert!((egress_cost_usd(1_000, 0.09, 10_000) - 0.0009).abs() < 1e-12);
+        for args in [
+            &["crabscore", "score", "--egress-price-per-gb", "0.12"][..],
+            &["crabscore", "score", "--runs-per-month", "10"][..],
+            &[
+                "crabscore",
+                "score",
+                "--trace-network",
+                "--http-endpoint",
+                "http://x",
+            ],
+        ] {
+            assert!(crate::cli::Cli::try_parse_from(args).is_err(), "{args:?}");
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let opts = ScoreOptions {
+            trace_network: true,
+            use_cache: false,
+            ..Default::default()
+        };
+        let metrics = collect_full_metrics(&[PathBuf::from("true")], tmp.path(), false, &opts)
+            .await
+            .unwrap();
+        // `true` sends nothing; without strace the cost model's figure is kept
+        let degraded = metrics
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("Network tracing unavailable"));
+        assert!(degraded || metrics.cost.infrastructure.network_egress_usd == 0.0);
+    }
+
+    #[tokio::test]
+    async fn reports_on_a_path_other_than_the_current_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sources = tmp.path().join("sources");
+        std::fs::create_dir(&sources).unwrap();
+        std::fs::write(
+            sources.join("lib.rs"),
+            "/// Adds\npub fn add(a: u8, b: u8) -> u8 { a + b }\n",
+        )
+        .unwrap();
+
+        // Not a Cargo project, so nothing is built and the metrics are estimated
+        let score = report_score(&sources, Vec::new()).await.unwrap();
+        assert_eq!(score.metadata.confidence, Confidence::Estimated);
+        assert!(score
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w == STATIC_ONLY_WARNING));
+        assert!(score.bonuses > 0.0);
+
+        // The configuration is read from the reported path too
+        std::fs::write(
+            sources.join(crate::config::CONFIG_FILE),
+            "[safety]\nno_unsafe_points = 0\nclippy_clean_points = 0\nlow_complexity_points = 0\n\n\
+             [bonuses]\ncomplexity_cap = 0\n",
+        )
+        .unwrap();
+        let score = report_score(&sources, Vec::new()).await.unwrap();
+        assert_eq!(score.bonuses, 0.0);
+    }
+
+    #[tokio::test]
+    async fn virtual_workspace_root_is_flagged_as_an_aggregate() {
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/virtual_workspace");
+        let opts = ScoreOptions {
+            only: Some(Dimension::Safety),
+            use_cache: false,
+            ..Default::default()
+        };
+        let result = score_path_detailed(&fixture, &opts).await.unwrap();
+        assert_eq!(result.complexity.file_count, 2);
+        assert!(result
+            .score
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w.contains("virtual workspace") && w.contains("crabscore workspace")));
+
+        let member = score_path_detailed(&fixture.join("crates/core"), &opts)
+            .await
+            .unwrap();
+        assert!(!member
+            .score
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w.contains("virtual workspace")));
+    }
+
+    #[tokio::test]
+    async fn only_computes_a_single_dimension_without_building() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join("src/main.rs"), "fn main() { unsafe {} }\n").unwrap();
+
+        let opts = ScoreOptions {
+            only: Some(Dimension::Energy),
+            use_cache: false,
+            profile: IndustryProfile::IotEmbedded,
+            ..Default::default()
+        };
+        let result = score_path_detailed(tmp.path(), &opts).await.unwrap();
+        assert!(!tmp.path().join("target").exists(), "project was built");
+        let metadata = &result.score.metadata;
+        assert_eq!(metadata.confidence, Confidence::Partial);
+        assert_eq!(metadata.profile, IndustryProfile::IotEmbedded);
+        assert!(metadata
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("Only the energy dimension")));
+        assert!(!metadata.warnings.iter().any(|w| w == STATIC_ONLY_WARNING));
+        let estimated = estimate_energy_from_complexity(&result.complexity, &opts.carbon);
+        let watts = |result: &ScoredProject| result.explanation.energy.inputs[0].value;
+        assert_eq!(watts(&result), estimated.direct_consumption.average_watts);
+        // Safety analysis was skipped, so the unsafe block went unseen
+        assert_eq!(result.safety.unsafe_blocks, 0);
+
+        let opts = ScoreOptions {
+            only: Some(Dimension::Safety),
+            ..opts
+        };
+        let result = score_path_detailed(tmp.path(), &opts).await.unwrap();
+        assert_eq!(result.safety.unsafe_blocks, 1);
+        assert_eq!(watts(&result), 0.0);
+    }
+
+    #[tokio::test]
+    async fn only_cost_reads_the_cost_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(
+            tmp.path().join(DEFAULT_COST_FILE),
+            r#"{"infrastructure": {"cloud_compute_usd": 1234.5}}"#,
+        )
+        .unwrap();
+
+        let opts = ScoreOptions {
+            only: Some(Dimension::Cost),
+            use_cache: false,
+            ..Default::default()
+        };
+        let result = score_path_detailed(tmp.path(), &opts).await.unwrap();
+        assert!(!tmp.path().join("target").exists(), "project was built");
+        assert_eq!(result.explanation.cost.inputs[0].value, 1234.5);
+        assert!(!result
+            .score
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w.contains("Cost file")));
+    }
+
+    #[tokio::test]
+    async fn measured_runs_take_churn_from_git_unless_the_cost_file_has_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        for args in [&["init", "-q"][..], &["add", "."]] {
+            assert!(std::process::Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(args)
+                .status()
+                .unwrap()
+                .success());
+        }
+        assert!(std::process::Command::new("git")
+            .args(["-c", "commit.gpgsign=false", "-C"])
+            .arg(repo)
+            .args(["commit", "-q", "-m", "init"])
+            .env("GIT_AUTHOR_NAME", "t")
+            .env("GIT_AUTHOR_EMAIL", "t@example.com")
+            .env("GIT_COMMITTER_NAME", "t")
+            .env("GIT_COMMITTER_EMAIL", "t@example.com")
+            .status()
+            .unwrap()
+            .success());
+
+        let churn = |cost_file: &'static str| async move {
+            std::fs::write(repo.join(DEFAULT_COST_FILE), cost_file).unwrap();
+            let metrics = collect_measured_metrics(
+                PerformanceMetrics::default(),
+                EnergyMetrics::default(),
+                Vec::new(),
+                Confidence::Measured,
+                repo,
+                true,
+                &ScoreOptions::default(),
+            )
+            .await
+            .unwrap();
+            metrics.cost.development.code_churn
+        };
+        assert_eq!(churn("{}").await, 2.0);
+        assert_eq!(churn(r#"{"development": {"code_churn": 7}}"#).await, 7.0);
+    }
+
+    #[tokio::test]
+    async fn since_scores_only_changed_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com", "-C"])
+                .arg(&repo)
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        std::fs::write(repo.join("stable.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(repo.join("edited.rs"), "fn c() {}\n").unwrap();
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        std::fs::write(repo.join("edited.rs"), "fn c() {}\nfn d() {}\n").unwrap();
+
+        let opts = ScoreOptions {
+            since: Some("HEAD".to_string()),
+            use_cache: false,
+            ..Default::default()
+        };
+        let scoped = score_path_detailed(&repo, &opts).await.unwrap();
+        assert_eq!(scoped.complexity.file_count, 1);
+        assert_eq!(scoped.complexity.function_count, 2);
+        assert_eq!(scoped.score.metadata.diff_base.as_deref(), Some("HEAD"));
+        assert!(scoped.score.metadata.warnings[0].starts_with("Partial score"));
+
+        let opts = ScoreOptions {
+            since: Some("no-such-ref".to_string()),
+            ..opts
+        };
+        let full = score_path_detailed(&repo, &opts).await.unwrap();
+        assert_eq!(full.complexity.file_count, 2);
+        assert_eq!(full.score.metadata.diff_base, None);
+        assert!(full.score.metadata.warnings[0].starts_with("Cannot diff against no-such-ref"));
+    }
+
+    #[tokio::test]
+    async fn score_path_scores_a_file_without_the_cli() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("hello.rs");
+        std::fs::write(&file, "/// Entry point\nfn main() {}\n").unwrap();
+
+        let opts = ScoreOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        let score = crate::score_path(&file, &opts).await.unwrap();
+        assert!(score.overall > 0.0);
+        assert_eq!(score.metadata.confidence, Confidence::Estimated);
+        assert!(score
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w == STATIC_ONLY_WARNING));
+    }
+
+    #[tokio::test]
+    async fn bounded_jobs_emit_in_order_without_exceeding_the_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let job = |delay_ms: u64| {
+            let (running, peak) = (Arc::clone(&running), Arc::clone(&peak));
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                delay_ms
+            }
+        };
+        let mut emitted = Vec::new();
+        // Later items finish first
+        for_each_bounded(vec![40, 30, 20, 10, 0], 2, job, |delay| {
+            emitted.push(delay);
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(emitted, [40, 30, 20, 10, 0]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn batch_results_do_not_depend_on_jobs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let list: String = (0..6)
+            .map(|i| {
+                let file = tmp.path().join(format!("crate{i}.rs"));
+                let body = "    let x = 1;\n    if x > 0 { println!(\"{x}\"); }\n".repeat(i * 20);
+                std::fs::write(&file, format!("fn main() {{\n{body}}}\n")).unwrap();
+                format!("{}\n", file.display())
+            })
+            .collect();
+
+        let run = |jobs| {
+            let list = list.clone();
+            async move {
+                let mut out = Vec::new();
+                score_batch(&list, jobs, false, &mut out).await.unwrap();
+                String::from_utf8(out)
+                    .unwrap()
+                    .lines()
+                    .map(|l| {
+                        let line: serde_json::Value = serde_json::from_str(l).unwrap();
+                        (
+                            line["path"].clone(),
+                            line["score"]["overall"].clone(),
+                            line["grade"].clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+        let serial = run(1).await;
+        assert_eq!(serial.len(), 6);
+        assert_eq!(serial, run(4).await);
+    }
+
+    #[tokio::test]
+    async fn batch_streams_one_line_per_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("hello.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        let missing = tmp.path().join("missing");
+        let list = format!("{}\n\n# skipped\n{}\n", file.display(), missing.display());
+
+        let mut out = Vec::new();
+        score_batch(&list, 2, false, &mut out).await.unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["path"], file.display().to_string());
+        assert!(lines[0]["score"]["overall"].as_f64().unwrap() > 0.0);
+        assert!(lines[0]["grade"].is_string());
+        assert_eq!(lines[1]["path"], missing.display().to_string());
+        assert!(lines[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("does not exist"));
+    }
+
+    #[test]
+    fn glob_paths_expand_to_matching_directories() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let crates = fixtures.join("virtual_workspace/crates");
+        let glob = |pattern: &Path| expand_path_glob(&pattern.display().to_string());
+
+        assert_eq!(
+            glob(&crates.join("*")).unwrap(),
+            Some(vec![crates.join("app"), crates.join("core")])
+        );
+        // Files are not scored, and a literal path is left alone
+        assert_eq!(
+            glob(&fixtures.join("*_service.rs"))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "No directory matches {:?}",
+                fixtures.join("*_service.rs").display().to_string()
+            )
+        );
+        assert_eq!(glob(&crates).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn static_metrics_are_estimated() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("main.rs"), "fn main() {}\n").unwrap();
+        let complexity = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+
+        let metrics =
+            collect_static_metrics(tmp.path(), false, &complexity, &ScoreOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(metrics.confidence, Confidence::Estimated);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn benchmarked_metrics_are_measured() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(DEFAULT_COST_FILE), "{}").unwrap();
+        let metrics = collect_full_metrics(
+            &[std::path::PathBuf::from("/bin/true")],
+            tmp.path(),
+            true,
+            &ScoreOptions::default(),
+        )
+        .await
+        .unwrap();
+        let expected = if PlatformMonitor::detect().await.has_telemetry() {
+            Confidence::Measured
+        } else {
+            Confidence::Partial
+        };
+        assert_eq!(metrics.confidence, expected);
+    }
+
+    #[test]
+    fn pre_collected_metrics_score_deterministically() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut perf = PerformanceMetrics::default();
+        perf.latency.p50_ms = 12.0;
+        perf.throughput.requests_per_second = 800.0;
+        let input = tmp.path().join("metrics.json");
+        std::fs::write(
+            &input,
+            serde_json::json!({
+                "performance": perf,
+                "energy": EnergyMetrics::default(),
+                "cost": CostMetrics::default(),
+                "safety": SafetyMetrics::default(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let first = score_metrics_file(&input, IndustryProfile::Gaming).unwrap();
+        let second = score_metrics_file(&input, IndustryProfile::Gaming).unwrap();
+        let direct = ScoringEngine::new(IndustryProfile::Gaming).calculate_score(
+            &perf,
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &SafetyMetrics::default(),
+        );
+        assert_eq!(first.overall, second.overall);
+        assert_eq!(first.overall, direct.overall);
+        assert_eq!(first.performance, direct.performance);
+        assert_eq!(first.metadata.profile, IndustryProfile::Gaming);
+
+        std::fs::write(&input, r#"{"performance": {}}"#).unwrap();
+        let err = score_metrics_file(&input, IndustryProfile::Gaming).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid metrics file"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn command_output_goes_to_the_given_writer() {
+        let run = |args: &[&str]| {
+            let cli = crate::cli::Cli::try_parse_from(args).unwrap();
+            async move {
+                let mut out = Vec::new();
+                execute_to(cli.command, cli.verbose, &mut out)
+                    .await
+                    .unwrap();
+                String::from_utf8(out).unwrap()
+            }
+        };
+        assert_eq!(
+            run(&["crabscore", "version"]).await,
+            format!("CrabScore CLI {}\n", env!("CARGO_PKG_VERSION"))
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let input = tmp.path().join("metrics.json");
+        std::fs::write(
+            &input,
+            serde_json::json!({
+                "performance": PerformanceMetrics::default(),
+                "energy": EnergyMetrics::default(),
+                "cost": CostMetrics::default(),
+                "safety": SafetyMetrics::default(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let input = input.to_str().unwrap();
+        let human = run(&["crabscore", "score-metrics", input, "--profile", "gaming"]).await;
+        assert!(human.contains("CrabScore Report"), "{human}");
+        assert!(human.contains("Profile: Gaming"), "{human}");
+        let json = run(&["crabscore", "score-metrics", input, "--format", "json"]).await;
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["score"]["metadata"]["profile"], "WebServices");
+    }
+
+    #[tokio::test]
+    async fn missing_cost_file_is_reported_as_warning() {
+        let tmp = tempfile::tempdir().unwrap();
+        let metrics = collect_measured_metrics(
+            PerformanceMetrics::default(),
+            EnergyMetrics::default(),
+            Vec::new(),
+            Confidence::Measured,
+            tmp.path(),
+            true,
+            &ScoreOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            metrics.warnings.iter().any(|w| w.contains("cost")),
+            "{:?}",
+            metrics.warnings
+        );
+        assert_eq!(metrics.confidence, Confidence::Partial);
+    }
+
+    #[tokio::test]
+    async fn strict_mode_fails_on_each_degradation() {
+        let strict = ScoreOptions {
+            strict: true,
+            use_cache: false,
+            ..Default::default()
+        };
+        let tmp = tempfile::tempdir().unwrap();
+
+        // Missing cost file
+        let mut warnings = Vec::new();
+        let err = collect_cost(tmp.path(), &strict, &mut warnings)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Cost file"), "{err:#}");
+        assert!(warnings.is_empty());
+
+        // Benchmark failure
+        let err = collect_full_metrics(
+            &[tmp.path().join("missing-binary")],
+            tmp.path(),
+            false,
+            &strict,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().starts_with("Benchmark failed"), "{err:#}");
+
+        // Safety parse error
+        std::fs::write(tmp.path().join("broken.rs"), "fn main( {").unwrap();
+        let err = safety_analysis(tmp.path(), &strict).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Safety analysis failed"),
+            "{err:#}"
+        );
+
+        // Failed build
+        let project = tmp.path().join("app");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.join("src/main.rs"),
+            "fn main() { let x: u8 = \"no\"; }\n",
+        )
+        .unwrap();
+        let err = score_path_detailed(&project, &strict).await.err().unwrap();
+        assert!(err.to_string().starts_with("Build failed"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn lenient_mode_degrades_instead() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lenient = ScoreOptions {
+            use_cache: false,
+            ..Default::default()
+        };
+        std::fs::write(tmp.path().join("broken.rs"), "fn main( {").unwrap();
+        let (safety, _) = safety_analysis(tmp.path(), &lenient).unwrap();
+        assert_eq!(safety.unsafe_blocks, SafetyMetrics::default().unsafe_blocks);
+
+        let mut warnings = Vec::new();
+        collect_cost(tmp.path(), &lenient, &mut warnings)
+            .await
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+
+        let metrics = collect_full_metrics(
+            &[tmp.path().join("missing-binary")],
+            tmp.path(),
+            false,
+            &lenient,
+        )
+        .await
+        .unwrap();
+        assert!(metrics
+            .warnings
+            .iter()
+            .any(|w| w.contains("missing-binary")));
+    }
+
+    #[test]
+    fn dimension_thresholds_fail_independently_of_overall() {
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = 85.0;
+        score.performance = 90.0;
+        score.energy = 40.0;
+        score.cost = 55.0;
+
+        let thresholds = Thresholds {
+            overall: Some(80.0),
+            ..Default::default()
+        };
+        assert!(enforce_thresholds(&score, &thresholds).is_ok());
+
+        let thresholds = Thresholds {
+            energy: Some(50.0),
+            cost: Some(60.0),
+            ..thresholds
+        };
+        let message = enforce_thresholds(&score, &thresholds)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("energy 40.0 < 50.0"), "{message}");
+        assert!(message.contains("cost 55.0 < 60.0"), "{message}");
+        assert!(!message.contains("overall"), "{message}");
+    }
+
+    #[test]
+    fn user_rust_log_takes_precedence_over_verbosity() {
+        assert_eq!(
+            log_filter(2, Some("crabscore=warn")).to_string(),
+            "crabscore=warn"
+        );
+        assert_eq!(log_filter(2, None).to_string(), "trace");
+        assert_eq!(log_filter(0, Some("")).to_string(), "info");
+        assert_eq!(
+            log_filter(1, Some("crabscore=nonsense")).to_string(),
+            "debug"
+        );
+    }
 }
@@ -0,0 +1,236 @@
+//! Side-by-side comparison of two saved score reports
+
+use anyhow::{bail, Context, Result};
+use crabscore_core::CrabScore;
+use serde::Serialize;
+use std::path::Path;
+
+/// Changes of at most this percentage of the baseline value count as noise
+pub const DEFAULT_TOLERANCE_PCT: f64 = 2.0;
+
+/// Whether a dimension moved beyond the comparison tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    /// The score rose by more than the tolerance
+    Improved,
+    /// The score moved by no more than the tolerance
+    Unchanged,
+    /// The score fell by more than the tolerance
+    Regressed,
+}
+
+/// Change of one dimension between two reports
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DimensionDelta {
+    /// Dimension name (`overall`, `performance`, `energy`, `cost` or `bonuses`)
+    pub dimension: &'static str,
+    /// Score in the baseline report
+    pub baseline: f64,
+    /// Score in the current report
+    pub current: f64,
+    /// `current - baseline`
+    pub delta: f64,
+    /// `delta` judged against the tolerance
+    pub change: Change,
+}
+
+/// Result of comparing a current report against a baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    /// Per-dimension changes, overall first
+    pub dimensions: Vec<DimensionDelta>,
+    /// Largest change, in percent of the baseline value, reported as unchanged
+    pub tolerance_pct: f64,
+    /// Whether both reports carry the same hardware fingerprint (`None` when
+    /// either lacks one)
+    pub same_hardware: Option<bool>,
+    /// Reasons the comparison may be misleading
+    pub warnings: Vec<String>,
+}
+
+/// Check that a tolerance is a non-negative percentage
+pub fn validate_tolerance(tolerance_pct: f64) -> Result<()> {
+    if !(tolerance_pct >= 0.0 && tolerance_pct.is_finite()) {
+        bail!("tolerance must be a percentage of at least 0, got {tolerance_pct}");
+    }
+    Ok(())
+}
+
+/// Compare `current` against `baseline`, flagging reports from different machines.
+///
+/// A dimension whose score moved by at most `tolerance_pct` percent of its
+/// baseline value is [`Change::Unchanged`].
+pub fn compare(baseline: &CrabScore, current: &CrabScore, tolerance_pct: f64) -> Comparison {
+    let dimensions = [
+        ("overall", baseline.overall, current.overall),
+        ("performance", baseline.performance, current.performance),
+        ("energy", baseline.energy, current.energy),
+        ("cost", baseline.cost, current.cost),
+        ("bonuses", baseline.bonuses, current.bonuses),
+    ]
+    .into_iter()
+    .map(|(dimension, baseline, current)| {
+        let delta = current - baseline;
+        let change = if delta.abs() <= baseline.abs() * tolerance_pct / 100.0 {
+            Change::Unchanged
+        } else if delta > 0.0 {
+            Change::Improved
+        } else {
+            Change::Regressed
+        };
+        DimensionDelta {
+            dimension,
+            baseline,
+            current,
+            delta,
+            change,
+        }
+    })
+    .collect();
+
+    let fingerprint = |score: &CrabScore| {
+        score
+            .metadata
+            .measurements
+            .environment
+            .hardware_fingerprint
+            .clone()
+    };
+    let (before, after) = (fingerprint(baseline), fingerprint(current));
+    let mut warnings = Vec::new();
+    let same_hardware = if before.is_empty() || after.is_empty() {
+        warnings.push(
+            "A report has no hardware fingerprint - cannot verify both were measured on the \
+             same machine"
+                .to_string(),
+        );
+        None
+    } else if before != after {
+        let calibrated = |score: &CrabScore| {
+            score
+                .metadata
+                .measurements
+                .environment
+                .speed_factor
+                .is_some()
+        };
+        warnings.push(if calibrated(baseline) && calibrated(current) {
+            format!(
+                "Reports were measured on different hardware (fingerprint {before} vs {after}) - \
+                 latencies were calibrated, so performance is only roughly comparable and energy \
+                 is not"
+            )
+        } else {
+            format!(
+                "Reports were measured on different hardware (fingerprint {before} vs {after}) - \
+                 latency, energy and the scores derived from them are NOT comparable"
+            )
+        });
+        Some(false)
+    } else {
+        Some(true)
+    };
+
+    Comparison {
+        dimensions,
+        tolerance_pct,
+        same_hardware,
+        warnings,
+    }
+}
+
+/// Read a report written by `crabscore score --format json` (or a bare
+/// score), upgrading reports written by older releases.
+pub fn load_report(path: &Path) -> Result<CrabScore> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut value: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    if let Some(score) = value.get_mut("score") {
+        value = score.take();
+    }
+    CrabScore::from_value(value)
+        .with_context(|| format!("{} is not a CrabScore report", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    fn score_on(fingerprint: &str, overall: f64) -> CrabScore {
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = overall;
+        score.metadata.measurements.environment.hardware_fingerprint = fingerprint.to_string();
+        score
+    }
+
+    #[test]
+    fn different_hardware_is_flagged() {
+        let same = compare(&score_on("aaaa", 70.0), &score_on("aaaa", 75.5), 0.0);
+        assert_eq!(same.same_hardware, Some(true));
+        assert!(same.warnings.is_empty());
+        assert_eq!(same.dimensions[0].dimension, "overall");
+        assert_eq!(same.dimensions[0].delta, 5.5);
+
+        let different = compare(&score_on("aaaa", 70.0), &score_on("bbbb", 75.5), 0.0);
+        assert_eq!(different.same_hardware, Some(false));
+        assert!(different.warnings[0].contains("NOT comparable"));
+
+        let calibrated = |fingerprint| {
+            let mut score = score_on(fingerprint, 70.0);
+            score.metadata.measurements.environment.speed_factor = Some(1.2);
+            score
+        };
+        let normalized = compare(&calibrated("aaaa"), &calibrated("bbbb"), 0.0);
+        assert_eq!(normalized.same_hardware, Some(false));
+        assert!(normalized.warnings[0].contains("roughly comparable"));
+
+        let unknown = compare(&score_on("", 70.0), &score_on("bbbb", 75.5), 0.0);
+        assert_eq!(unknown.same_hardware, None);
+        assert_eq!(unknown.warnings.len(), 1);
+    }
+
+    #[test]
+    fn changes_within_the_tolerance_are_unchanged() {
+        let change = |current: f64| {
+            compare(&score_on("aaaa", 80.0), &score_on("aaaa", current), 2.0).dimensions[0].change
+        };
+        // 1% drop
+        assert_eq!(change(79.2), Change::Unchanged);
+        assert_eq!(change(80.0), Change::Unchanged);
+        assert_eq!(change(81.6), Change::Unchanged);
+        // 5% drop or rise
+        assert_eq!(change(76.0), Change::Regressed);
+        assert_eq!(change(84.0), Change::Improved);
+
+        let strict = compare(&score_on("aaaa", 80.0), &score_on("aaaa", 79.2), 0.0);
+        assert_eq!(strict.dimensions[0].change, Change::Regressed);
+        assert_eq!(strict.dimensions[1].change, Change::Unchanged);
+
+        assert!(validate_tolerance(DEFAULT_TOLERANCE_PCT).is_ok());
+        assert!(validate_tolerance(-1.0).is_err());
+        assert!(validate_tolerance(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn loads_wrapped_and_bare_reports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let score = score_on("aaaa", 80.0);
+        let wrapped = tmp.path().join("report.json");
+        std::fs::write(
+            &wrapped,
+            crabscore_report::generator::generate_json(&score).to_pretty_string(),
+        )
+        .unwrap();
+        let bare = tmp.path().join("score.json");
+        std::fs::write(&bare, serde_json::to_string(&score).unwrap()).unwrap();
+
+        for path in [&wrapped, &bare] {
+            assert_eq!(load_report(path).unwrap().overall, 80.0);
+        }
+        std::fs::write(&bare, "{}").unwrap();
+        assert!(load_report(&bare).is_err());
+    }
+}
@@ -1,16 +1,31 @@
 //! Project complexity analysis for graceful scoring degradation
 
+use crate::progress::Progress;
 use anyhow::Result;
-use std::path::Path;
+use crabscore_core::cache::{content_hash, extend_content_hash, FileCache, EMPTY_CONTENT_HASH};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{AttrStyle, Attribute, ExprAwait, Item, Meta, Signature, Token, Visibility};
 use walkdir::WalkDir;
 
 /// Project complexity metrics for enhanced scoring
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct ProjectComplexity {
     /// Number of Rust source files
     pub file_count: usize,
-    /// Total lines of code
+    /// Total lines of code, counted as selected by [`LineCount`]
     pub total_lines: usize,
+    /// Lines including blanks and comments
+    pub physical_lines: usize,
+    /// Source lines: neither blank nor comment-only
+    pub sloc: usize,
     /// Number of function definitions
     pub function_count: usize,
     /// Number of module definitions
@@ -19,26 +34,150 @@ pub struct ProjectComplexity {
     pub test_count: usize,
     /// Number of documentation lines
     pub doc_lines: usize,
+    /// Documentable items (functions, types, traits, modules, constants, ...)
+    pub items: usize,
+    /// Items carrying doc comments or `#[doc = ...]` attributes
+    pub documented_items: usize,
+    /// Items declared `pub`
+    pub public_items: usize,
+    /// Documented items declared `pub`
+    pub documented_public_items: usize,
+    /// Number of `async fn` definitions
+    pub async_functions: usize,
+    /// Number of `.await` expressions
+    pub await_points: usize,
     /// Number of dependencies in Cargo.toml
     pub dependency_count: usize,
+    /// Unique crates the project depends on, directly or not (build and
+    /// normal dependencies); `None` when `cargo metadata` is unavailable, see
+    /// [`dependency_tree`]
+    pub transitive_dep_count: Option<usize>,
+    /// Longest chain of dependencies below the project's own crates
+    pub dep_tree_depth: Option<usize>,
+    /// The project has cargo-fuzz targets or property tests, see [`has_fuzzing`]
+    pub has_fuzzing: bool,
+    /// The crate root declares `#![no_std]`, see [`is_no_std`]
+    pub no_std: bool,
+    /// Generated files (see [`is_generated`]) left out of every other count
+    pub generated_files: usize,
+    /// Order-independent combination of the [`FileComplexity::content_hash`]
+    /// of every added file, see [`ProjectComplexity::content_hash`]
+    pub file_hashes: u64,
+}
+
+/// Complexity contribution of a single source file.
+///
+/// Fields have no serde defaults on purpose: a cache written before a field
+/// was added no longer parses and is rebuilt, instead of reporting zero for
+/// the new field on every unchanged file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileComplexity {
+    /// Lines in the file
+    pub lines: usize,
+    /// Lines that are neither blank nor comment-only
+    pub sloc: usize,
+    /// Number of function definitions
+    pub functions: usize,
+    /// Number of module definitions
+    pub modules: usize,
+    /// Number of test annotations
+    pub tests: usize,
+    /// Number of documentation lines
+    pub doc_lines: usize,
+    /// Documentable items
+    pub items: usize,
+    /// Documented items
+    pub documented_items: usize,
+    /// Items declared `pub`
+    pub public_items: usize,
+    /// Documented items declared `pub`
+    pub documented_public_items: usize,
+    /// Number of `async fn` definitions
+    pub async_functions: usize,
+    /// Number of `.await` expressions
+    pub await_points: usize,
+    /// [`content_hash`] of the file's bytes
+    pub content_hash: u64,
+}
+
+/// Which lines count toward [`ProjectComplexity::total_lines`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineCount {
+    /// Every line, including blank and comment lines
+    #[default]
+    Physical,
+    /// Source lines only: blank lines and lines holding nothing but a comment
+    /// are skipped (for non-Rust files only blank lines are)
+    Sloc,
+}
+
+/// Options controlling complexity analysis
+#[derive(Debug, Clone, Default)]
+pub struct ComplexityOptions {
+    /// Reuse cached per-file results for unchanged files
+    pub use_cache: bool,
+    /// Directory holding the cache; the default cache directory when `None`
+    pub cache_dir: Option<PathBuf>,
+    /// Spinner updated with the number of analyzed files
+    pub progress: Progress,
+    /// Extensions of non-Rust files (e.g. `c`, `sql`) whose lines are added to
+    /// `total_lines`; only `.rs` files are parsed for functions, tests and docs
+    pub include_extensions: Vec<String>,
+    /// Analyze only these files (canonical paths) instead of every file found
+    pub only_files: Option<Vec<PathBuf>>,
+    /// Which lines make up `total_lines`
+    pub count_mode: LineCount,
+    /// Descend at most this many directory levels below the root (files
+    /// directly in the root are at depth 1); unlimited when `None`
+    pub max_depth: Option<NonZeroUsize>,
+    /// Already resolved dependency graph of the workspace the project is part
+    /// of; the project's own graph is resolved when `None` or when the
+    /// project is not in it
+    pub dependency_graph: Option<std::sync::Arc<DependencyGraph>>,
+}
+
+impl ComplexityOptions {
+    fn includes(&self, path: &Path) -> bool {
+        self.only_files
+            .as_ref()
+            .is_none_or(|files| path.canonicalize().is_ok_and(|path| files.contains(&path)))
+    }
+
+    fn counts_lines_of(&self, ext: &str) -> bool {
+        self.include_extensions
+            .iter()
+            .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+    }
 }
 
 impl ProjectComplexity {
-    /// Calculate documentation coverage ratio
+    /// Ratio of documented items.
+    ///
+    /// Measured over public items, or over all items when the project has no
+    /// public API (binaries and single files).
     pub fn doc_coverage(&self) -> f64 {
-        if self.total_lines == 0 {
-            0.0
+        let (documented, total) = if self.public_items > 0 {
+            (self.documented_public_items, self.public_items)
         } else {
-            self.doc_lines as f64 / self.total_lines as f64
-        }
+            (self.documented_items, self.items)
+        };
+        bounded_ratio("doc_coverage", documented, total)
     }
 
-    /// Calculate test coverage ratio
+    /// Tests per function, clamped to 1.0
     pub fn test_coverage(&self) -> f64 {
+        bounded_ratio("test_coverage", self.test_count, self.function_count)
+    }
+
+    /// Share of functions that are `async` (0.0 to 1.0).
+    ///
+    /// Async code spends its time awaiting I/O rather than computing, so the
+    /// ratio shifts the estimates towards an I/O-bound profile.
+    pub fn async_ratio(&self) -> f64 {
         if self.function_count == 0 {
             0.0
         } else {
-            self.test_count as f64 / self.function_count as f64
+            (self.async_functions as f64 / self.function_count as f64).min(1.0)
         }
     }
 
@@ -46,81 +185,1392 @@ impl ProjectComplexity {
     pub fn complexity_factor(&self) -> f64 {
         (self.total_lines as f64 / 1000.0).min(10.0)
     }
+
+    /// Add the contribution of one source file
+    pub fn add_file(&mut self, file: &FileComplexity) {
+        self.file_count += 1;
+        self.total_lines += file.lines;
+        self.physical_lines += file.lines;
+        self.sloc += file.sloc;
+        self.function_count += file.functions;
+        self.module_count += file.modules;
+        self.test_count += file.tests;
+        self.doc_lines += file.doc_lines;
+        self.items += file.items;
+        self.documented_items += file.documented_items;
+        self.public_items += file.public_items;
+        self.documented_public_items += file.documented_public_items;
+        self.async_functions += file.async_functions;
+        self.await_points += file.await_points;
+        self.file_hashes = self.file_hashes.wrapping_add(file.content_hash);
+    }
+
+    /// Stable hash of the contents of every added file, whatever order they
+    /// were added in.
+    ///
+    /// Any edit to an analyzed file changes it, even one that leaves every
+    /// count unchanged, so it can key caches of results derived from the
+    /// sources.
+    pub fn content_hash(&self) -> u64 {
+        self.file_hashes
+    }
+
+    /// Stable hash of every metric.
+    ///
+    /// It hashes the counts, not the sources: edits that leave every count
+    /// unchanged keep the hash (see [`Self::content_hash`] for one that
+    /// changes).
+    ///
+    /// Unlike the derived [`Hash`], the value does not depend on the platform
+    /// or Rust version, so it can be stored in caches and test snapshots. It
+    /// does not depend on the order files were added in either.
+    pub fn metrics_hash(&self) -> u64 {
+        let Self {
+            file_count,
+            total_lines,
+            physical_lines,
+            sloc,
+            function_count,
+            module_count,
+            test_count,
+            doc_lines,
+            items,
+            documented_items,
+            public_items,
+            documented_public_items,
+            async_functions,
+            await_points,
+            dependency_count,
+            transitive_dep_count,
+            dep_tree_depth,
+            has_fuzzing,
+            no_std,
+            generated_files,
+            file_hashes: _,
+        } = *self;
+        let optional = |value: Option<usize>| value.map_or(u64::MAX, |v| v as u64);
+        stable_hash(&[
+            file_count as u64,
+            total_lines as u64,
+            physical_lines as u64,
+            sloc as u64,
+            function_count as u64,
+            module_count as u64,
+            test_count as u64,
+            doc_lines as u64,
+            items as u64,
+            documented_items as u64,
+            public_items as u64,
+            documented_public_items as u64,
+            async_functions as u64,
+            await_points as u64,
+            dependency_count as u64,
+            optional(transitive_dep_count),
+            optional(dep_tree_depth),
+            u64::from(has_fuzzing),
+            u64::from(no_std),
+            generated_files as u64,
+        ])
+    }
 }
 
-/// Analyze project complexity for enhanced scoring
-pub async fn analyze_project_complexity(path: &Path) -> Result<ProjectComplexity> {
-    let mut complexity = ProjectComplexity::default();
+/// `part / total` clamped to `[0, 1]`, or 0.0 when `total` is zero.
+///
+/// A ratio above 1.0 means the counts disagree (e.g. more tests than
+/// functions); it is logged so the miscount is visible instead of inflating
+/// the bonuses.
+fn bounded_ratio(name: &str, part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let ratio = part as f64 / total as f64;
+    if ratio > 1.0 {
+        tracing::debug!("{name} ratio {part}/{total} exceeds 1.0, clamping");
+    }
+    ratio.clamp(0.0, 1.0)
+}
 
-    // Count dependencies from Cargo.toml if it exists
-    let cargo_toml = path.join("Cargo.toml");
-    if cargo_toml.exists() {
-        if let Ok(content) = std::fs::read_to_string(&cargo_toml) {
-            if let Ok(toml) = content.parse::<toml::Value>() {
-                if let Some(deps) = toml.get("dependencies").and_then(|d| d.as_table()) {
-                    complexity.dependency_count = deps.len();
+/// FNV-1a hash of `values` as little-endian bytes
+fn stable_hash(values: &[u64]) -> u64 {
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    content_hash(&bytes)
+}
+
+impl FileComplexity {
+    /// Stable hash of every metric of the file, see
+    /// [`ProjectComplexity::metrics_hash`]
+    pub fn metrics_hash(&self) -> u64 {
+        let Self {
+            lines,
+            sloc,
+            functions,
+            modules,
+            tests,
+            doc_lines,
+            items,
+            documented_items,
+            public_items,
+            documented_public_items,
+            async_functions,
+            await_points,
+            content_hash: _,
+        } = *self;
+        stable_hash(&[
+            lines as u64,
+            sloc as u64,
+            functions as u64,
+            modules as u64,
+            tests as u64,
+            doc_lines as u64,
+            items as u64,
+            documented_items as u64,
+            public_items as u64,
+            documented_public_items as u64,
+            async_functions as u64,
+            await_points as u64,
+        ])
+    }
+
+    /// Measure a single source file with simple line heuristics
+    pub fn from_source(content: &str) -> Self {
+        let mut scanner = LineScanner::default();
+        content.lines().for_each(|line| scanner.push(line));
+        let mut file = scanner.file;
+        file.content_hash = content_hash(content.as_bytes());
+        // Files that do not parse contribute no items
+        if let Ok(syntax) = syn::parse_file(content) {
+            file.count_items(&syntax.items);
+            let mut visitor = AsyncVisitor::default();
+            visitor.visit_file(&syntax);
+            file.async_functions = visitor.async_functions;
+            file.await_points = visitor.await_points;
+        }
+        file
+    }
+
+    /// Count documentable items, descending into inline modules and impl blocks
+    fn count_items(&mut self, items: &[Item]) {
+        for item in items {
+            let (attrs, vis) = match item {
+                Item::Fn(i) => (&i.attrs, &i.vis),
+                Item::Struct(i) => (&i.attrs, &i.vis),
+                Item::Enum(i) => (&i.attrs, &i.vis),
+                Item::Union(i) => (&i.attrs, &i.vis),
+                Item::Trait(i) => (&i.attrs, &i.vis),
+                Item::Type(i) => (&i.attrs, &i.vis),
+                Item::Const(i) => (&i.attrs, &i.vis),
+                Item::Static(i) => (&i.attrs, &i.vis),
+                Item::Mod(i) => {
+                    if let Some((_, content)) = &i.content {
+                        self.count_items(content);
+                    }
+                    (&i.attrs, &i.vis)
                 }
-            }
+                Item::Impl(i) => {
+                    for impl_item in &i.items {
+                        if let syn::ImplItem::Fn(f) = impl_item {
+                            self.count_item(&f.attrs, &f.vis);
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            self.count_item(attrs, vis);
         }
     }
 
-    // Walk through all Rust files
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("rs"))
-    {
-        complexity.file_count += 1;
+    /// Count one item; `#[doc(hidden)]` items are not part of the documented API
+    fn count_item(&mut self, attrs: &[Attribute], vis: &Visibility) {
+        let doc_attrs = || attrs.iter().filter(|a| a.path().is_ident("doc"));
+        if doc_attrs().any(
+            |a| matches!(&a.meta, Meta::List(list) if list.tokens.to_string().contains("hidden")),
+        ) {
+            return;
+        }
+        let documented = doc_attrs().any(|a| matches!(a.meta, Meta::NameValue(_)));
+        let public = matches!(vis, Visibility::Public(_));
+        self.items += 1;
+        self.documented_items += usize::from(documented);
+        self.public_items += usize::from(public);
+        self.documented_public_items += usize::from(documented && public);
+    }
+}
+
+/// Line heuristics of [`FileComplexity`], fed one line at a time so a file
+/// can be measured from a reader without holding it in memory
+#[derive(Default)]
+struct LineScanner {
+    /// Counts so far; the syntax-based fields stay zero
+    file: FileComplexity,
+    /// Lines that are not blank, for non-Rust files
+    non_blank: usize,
+    /// Nesting of the block comment the previous line ended in
+    comment_depth: usize,
+    /// The leading comments checked by [`is_generated`]
+    header: GeneratedHeader,
+}
 
-        if let Ok(content) = std::fs::read_to_string(entry.path()) {
-            complexity.total_lines += content.lines().count();
+impl LineScanner {
+    fn push(&mut self, line: &str) {
+        self.file.lines += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return;
+        }
+        self.non_blank += 1;
+        self.header.push(trimmed);
+        let code = self.has_code(trimmed);
+        let file = &mut self.file;
+        file.sloc += usize::from(code);
 
-            // Parse code structure with simple heuristics
-            for line in content.lines() {
-                let trimmed = line.trim();
+        // Documentation comments
+        if trimmed.starts_with("///") || trimmed.starts_with("//!") {
+            file.doc_lines += 1;
+        }
 
-                // Documentation comments
-                if trimmed.starts_with("///") || trimmed.starts_with("//!") {
-                    complexity.doc_lines += 1;
-                }
+        // Function definitions
+        if trimmed.starts_with("fn ") || trimmed.contains("fn ") {
+            file.functions += 1;
+        }
 
-                // Function definitions
-                if trimmed.starts_with("fn ") || trimmed.contains("fn ") {
-                    complexity.function_count += 1;
-                }
+        // Module definitions
+        if trimmed.starts_with("mod ") {
+            file.modules += 1;
+        }
 
-                // Module definitions
-                if trimmed.starts_with("mod ") {
-                    complexity.module_count += 1;
-                }
+        // Test annotations
+        if trimmed.contains("#[test]") || trimmed.contains("#[cfg(test)]") {
+            file.tests += 1;
+        }
+    }
 
-                // Test annotations
-                if trimmed.contains("#[test]") || trimmed.contains("#[cfg(test)]") {
-                    complexity.test_count += 1;
+    /// Whether the trimmed `line` holds anything besides comments.
+    ///
+    /// Line and (nested) block comments are recognized; comment markers
+    /// inside string literals are not told apart from real ones.
+    fn has_code(&mut self, line: &str) -> bool {
+        let depth = &mut self.comment_depth;
+        let mut rest = line;
+        let mut code = false;
+        while !rest.is_empty() {
+            if *depth > 0 {
+                match (rest.find("/*"), rest.find("*/")) {
+                    (Some(open), close) if close.is_none_or(|close| open < close) => {
+                        *depth += 1;
+                        rest = &rest[open + 2..];
+                    }
+                    (_, Some(close)) => {
+                        *depth -= 1;
+                        rest = &rest[close + 2..];
+                    }
+                    _ => rest = "",
+                }
+            } else if rest.starts_with("//") {
+                rest = "";
+            } else if let Some(after) = rest.strip_prefix("/*") {
+                *depth = 1;
+                rest = after;
+            } else {
+                code = true;
+                match rest.find("/*") {
+                    Some(open) if !rest[..open].contains("//") => {
+                        *depth = 1;
+                        rest = &rest[open + 2..];
+                    }
+                    _ => rest = "",
                 }
             }
+            rest = rest.trim_start();
         }
+        code
     }
+}
 
-    // Handle single file case
-    if complexity.file_count == 0
-        && path.is_file()
-        && path.extension().and_then(|s| s.to_str()) == Some("rs")
+/// Longest line [`scan_lines`] keeps in memory; the rest of a longer line
+/// (minified or generated data) is skipped without being buffered
+const MAX_LINE_BYTES: u64 = 64 * 1024;
+
+/// Rust files larger than this are not parsed: their line heuristics are
+/// streamed from a buffered reader instead, so a huge (usually generated)
+/// file cannot spike memory. They contribute no item or async counts.
+pub const MAX_PARSED_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Feed every line of `reader` to a [`LineScanner`], holding at most
+/// [`MAX_LINE_BYTES`] of it at a time
+fn scan_lines(reader: impl BufRead) -> std::io::Result<LineScanner> {
+    let mut reader = HashingReader {
+        inner: reader,
+        hash: EMPTY_CONTENT_HASH,
+    };
+    let mut scanner = LineScanner::default();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = reader
+            .by_ref()
+            .take(MAX_LINE_BYTES)
+            .read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            scanner.file.content_hash = reader.hash;
+            return Ok(scanner);
+        }
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+        } else if read as u64 == MAX_LINE_BYTES {
+            reader.skip_until(b'\n')?;
+        }
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+        scanner.push(&String::from_utf8_lossy(&buf));
+    }
+}
+
+/// Reader folding every byte consumed from `inner` into a [`content_hash`],
+/// including the ones skipped without being buffered
+struct HashingReader<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: BufRead> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hash = extend_content_hash(self.hash, &buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for HashingReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let (true, Ok(buf)) = (amt > 0, self.inner.fill_buf()) {
+            self.hash = extend_content_hash(self.hash, &buf[..amt.min(buf.len())]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// Counts `async fn` signatures (free functions, methods and trait items)
+/// and `.await` expressions
+#[derive(Default)]
+struct AsyncVisitor {
+    async_functions: usize,
+    await_points: usize,
+}
+
+impl<'ast> Visit<'ast> for AsyncVisitor {
+    fn visit_signature(&mut self, sig: &'ast Signature) {
+        self.async_functions += usize::from(sig.asyncness.is_some());
+        visit::visit_signature(self, sig);
+    }
+
+    fn visit_expr_await(&mut self, expr: &'ast ExprAwait) {
+        self.await_points += 1;
+        visit::visit_expr_await(self, expr);
+    }
+}
+
+/// Crates whose presence among the dependencies marks a property-testing suite
+const PROPERTY_TEST_CRATES: [&str; 2] = ["proptest", "quickcheck"];
+
+/// Whether the project at `path` is fuzzed or property-tested.
+///
+/// Either signal counts: a `fuzz/` directory as created by `cargo fuzz init`,
+/// or `proptest`/`quickcheck` in the `[dependencies]` or `[dev-dependencies]`
+/// of `manifest` (the project's parsed `Cargo.toml`).
+pub fn has_fuzzing(path: &Path, manifest: Option<&toml::Value>) -> bool {
+    let property_tests = manifest.is_some_and(|m| {
+        ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|section| m.get(section).and_then(|d| d.as_table()))
+            .any(|deps| PROPERTY_TEST_CRATES.iter().any(|c| deps.contains_key(*c)))
+    });
+    property_tests || path.join("fuzz").is_dir()
+}
+
+/// Size and depth of a project's dependency graph, see [`dependency_tree`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependencyTree {
+    /// Unique crates reachable from the project's own crates
+    pub transitive: usize,
+    /// Most dependency edges along any chain from one of the project's
+    /// crates to a reachable crate
+    pub depth: usize,
+}
+
+/// Resolved dependency graph of a Cargo workspace, shared by the analyses of
+/// its members (see [`ComplexityOptions::dependency_graph`])
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DependencyGraph {
+    /// Directory of the workspace root manifest
+    workspace_root: PathBuf,
+    /// Package id of each workspace member, by manifest directory
+    members: HashMap<PathBuf, String>,
+    /// Normal and build dependencies of each package id; dev-dependencies
+    /// are not part of what ships
+    edges: HashMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Dependency graph of the Cargo workspace containing `path`, resolved
+    /// by `cargo metadata --locked --offline`.
+    ///
+    /// `--locked` keeps cargo from writing a `Cargo.lock` into the scored
+    /// project. `None` when cargo is missing, the project has no up-to-date
+    /// `Cargo.lock` or the graph cannot be resolved without the network
+    /// (dependencies that were never downloaded).
+    pub async fn resolve(path: &Path) -> Option<Self> {
+        let output = tokio::process::Command::new("cargo")
+            .args([
+                "metadata",
+                "--format-version",
+                "1",
+                "--locked",
+                "--offline",
+                "--manifest-path",
+            ])
+            .arg(path.join("Cargo.toml"))
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .inspect_err(|e| tracing::debug!("Could not run cargo metadata: {}", e))
+            .ok()?;
+        if !output.status.success() {
+            tracing::debug!("cargo metadata failed ({})", output.status);
+            return None;
+        }
+        Self::from_metadata(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Graph of the `resolve` section of `cargo metadata` JSON output
+    fn from_metadata(json: &str) -> Option<Self> {
+        let metadata: serde_json::Value = serde_json::from_str(json).ok()?;
+        let member_ids: HashSet<&str> = metadata
+            .get("workspace_members")?
+            .as_array()?
+            .iter()
+            .filter_map(|m| m.as_str())
+            .collect();
+        let members = metadata
+            .get("packages")?
+            .as_array()?
+            .iter()
+            .filter_map(|package| {
+                let id = package.get("id")?.as_str()?;
+                let manifest = Path::new(package.get("manifest_path")?.as_str()?);
+                let dir = manifest.parent()?.to_path_buf();
+                member_ids.contains(id).then(|| (dir, id.to_string()))
+            })
+            .collect();
+        let shipped = |dep: &&serde_json::Value| {
+            dep.get("dep_kinds")
+                .and_then(|kinds| kinds.as_array())
+                .is_none_or(|kinds| {
+                    kinds
+                        .iter()
+                        .any(|k| k.get("kind").and_then(|k| k.as_str()) != Some("dev"))
+                })
+        };
+        let edges = metadata
+            .get("resolve")?
+            .get("nodes")?
+            .as_array()?
+            .iter()
+            .filter_map(|node| {
+                let deps = node
+                    .get("deps")?
+                    .as_array()?
+                    .iter()
+                    .filter(shipped)
+                    .filter_map(|dep| Some(dep.get("pkg")?.as_str()?.to_string()))
+                    .collect();
+                Some((node.get("id")?.as_str()?.to_string(), deps))
+            })
+            .collect();
+        Some(Self {
+            workspace_root: PathBuf::from(metadata.get("workspace_root")?.as_str()?),
+            members,
+            edges,
+        })
+    }
+
+    /// [`DependencyTree`] of the package whose manifest is in `dir`, or of
+    /// every member when `dir` is the root of a virtual workspace; `None`
+    /// when `dir` is not part of the workspace.
+    ///
+    /// The project's own crates are the roots of the walk and are not
+    /// counted as dependencies.
+    pub fn tree_of(&self, dir: &Path) -> Option<DependencyTree> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let roots: HashSet<&str> = match self.members.get(&dir) {
+            Some(id) => HashSet::from([id.as_str()]),
+            None if dir == self.workspace_root => {
+                self.members.values().map(String::as_str).collect()
+            }
+            None => return None,
+        };
+        let deps = |id: &str| {
+            self.edges
+                .get(id)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|dep| !roots.contains(dep))
+        };
+
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = roots.iter().copied().collect();
+        while let Some(id) = stack.pop() {
+            stack.extend(deps(id).filter(|dep| seen.insert(dep)));
+        }
+        // Longest chain below each crate; the graph without dev-dependencies
+        // is acyclic, but a crate on the current chain is not entered again
+        fn longest<'a, I: Iterator<Item = &'a str>>(
+            id: &'a str,
+            deps: &impl Fn(&'a str) -> I,
+            memo: &mut HashMap<&'a str, usize>,
+            chain: &mut HashSet<&'a str>,
+        ) -> usize {
+            if let Some(&depth) = memo.get(id) {
+                return depth;
+            }
+            if !chain.insert(id) {
+                return 0;
+            }
+            let depth = deps(id)
+                .map(|dep| 1 + longest(dep, deps, memo, chain))
+                .max()
+                .unwrap_or(0);
+            chain.remove(id);
+            memo.insert(id, depth);
+            depth
+        }
+        let mut memo = HashMap::new();
+        let depth = roots
+            .iter()
+            .map(|root| longest(root, &deps, &mut memo, &mut HashSet::new()))
+            .max()
+            .unwrap_or(0);
+        Some(DependencyTree {
+            transitive: seen.len(),
+            depth,
+        })
+    }
+}
+
+/// Dependency graph of the Cargo project at `path`, see
+/// [`DependencyGraph::resolve`]
+pub async fn dependency_tree(path: &Path) -> Option<DependencyTree> {
+    DependencyGraph::resolve(path).await?.tree_of(path)
+}
+
+/// Whether the crate at `path` is `#![no_std]`.
+///
+/// The crate root (`src/lib.rs` or `src/main.rs`, or `path` itself when it
+/// is a file) must carry the inner attribute, either directly or through
+/// `cfg_attr` as in `#![cfg_attr(not(test), no_std)]`. Other files are not
+/// looked at, so `no_std` fixtures or vendored crates below the project do
+/// not count.
+pub fn is_no_std(path: &Path) -> bool {
+    let roots = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        vec![path.join("src/lib.rs"), path.join("src/main.rs")]
+    };
+    roots
+        .iter()
+        .filter_map(|root| std::fs::read_to_string(root).ok())
+        .filter_map(|content| syn::parse_file(&content).ok())
+        .any(|file| file.attrs.iter().any(declares_no_std))
+}
+
+/// Whether the leading `//` or `/* */` comments of `content` carry an
+/// `@generated` marker or Go's `Code generated ... DO NOT EDIT.` line, as
+/// written by code generators (`build.rs` output, bindgen, protobuf
+/// compilers, ...).
+///
+/// Doc comments (`//!`, `///`, `/*!`, `/**`) end the header, so a crate
+/// documenting its generated modules is not mistaken for one.
+pub fn is_generated(content: &str) -> bool {
+    let mut header = GeneratedHeader::default();
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
     {
-        complexity.file_count = 1;
-        if let Ok(content) = std::fs::read_to_string(path) {
-            complexity.total_lines = content.lines().count();
-            for line in content.lines() {
-                let trimmed = line.trim();
-                if trimmed.starts_with("fn ") || trimmed.contains("fn ") {
-                    complexity.function_count += 1;
+        header.push(line);
+        if header.done {
+            break;
+        }
+    }
+    header.generated
+}
+
+/// The leading comments of a file, fed one trimmed non-blank line at a time
+/// and checked for a generated-code marker (see [`is_generated`])
+#[derive(Default)]
+struct GeneratedHeader {
+    /// Inside a `/* */` comment of the header
+    in_block: bool,
+    /// Past the header: a line that is not a plain comment was seen
+    done: bool,
+    /// The header carries a marker
+    generated: bool,
+}
+
+impl GeneratedHeader {
+    fn push(&mut self, line: &str) {
+        if self.done {
+            return;
+        }
+        let text = if self.in_block {
+            line
+        } else if let Some(text) = line
+            .strip_prefix("//")
+            .filter(|text| !text.starts_with(['/', '!']))
+        {
+            text
+        } else if let Some(text) = line
+            .strip_prefix("/*")
+            .filter(|text| !text.starts_with(['*', '!']))
+        {
+            self.in_block = true;
+            text
+        } else {
+            self.done = true;
+            return;
+        };
+        let text = match text.split_once("*/").filter(|_| self.in_block) {
+            Some((inside, _)) => {
+                self.in_block = false;
+                inside
+            }
+            None => text,
+        };
+        self.generated |= marks_generated(text.trim_start_matches('*').trim());
+    }
+}
+
+/// Whether the comment `text` holds an `@generated` marker or is a
+/// `Code generated ... DO NOT EDIT.` line
+fn marks_generated(text: &str) -> bool {
+    text.split_whitespace().any(|word| word == "@generated")
+        || text.starts_with("Code generated ")
+            && text.trim_end_matches('.').ends_with("DO NOT EDIT")
+}
+
+/// Whether `dir` is Cargo's build output (`target`, holding every `OUT_DIR`)
+fn is_target_dir(dir: &Path) -> bool {
+    dir.file_name().is_some_and(|name| name == "target")
+        && (dir.join("CACHEDIR.TAG").is_file()
+            || dir.parent().is_some_and(|p| p.join("Cargo.toml").is_file()))
+}
+
+/// `#![no_std]` or a `#![cfg_attr(<predicate>, ..., no_std)]`
+fn declares_no_std(attr: &Attribute) -> bool {
+    matches!(attr.style, AttrStyle::Inner(_)) && meta_declares_no_std(&attr.meta)
+}
+
+/// Whether `meta` is `no_std`, or a `cfg_attr` applying it; the predicate of
+/// a `cfg_attr` (e.g. `feature = "no_std"`) does not count
+fn meta_declares_no_std(meta: &Meta) -> bool {
+    if meta.path().is_ident("no_std") {
+        return true;
+    }
+    match meta {
+        Meta::List(list) if list.path.is_ident("cfg_attr") => list
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .is_ok_and(|args| args.iter().skip(1).any(meta_declares_no_std)),
+        _ => false,
+    }
+}
+
+/// What analyzing one walked file produced
+enum FileOutcome {
+    /// Lines of a non-Rust file
+    Lines { physical: usize, non_blank: usize },
+    /// The file could not be read
+    Unreadable(std::io::Error),
+    /// A generated Rust file, left out of the counts
+    Generated,
+    /// A Rust file, with the content hash to cache it under when it was not
+    /// taken from the cache
+    Rust {
+        file: FileComplexity,
+        uncached: Option<u64>,
+    },
+}
+
+/// Walk the files under `path` that `opts` selects and analyze them in
+/// parallel, in walk order.
+///
+/// Only Rust files (a single .rs path yields just that file) and files with
+/// the extra extensions whose lines are counted are visited; build output is
+/// generated, so `target` is not entered.
+fn analyze_files(
+    path: &Path,
+    opts: &ComplexityOptions,
+    cache: &FileCache<FileComplexity>,
+) -> Vec<(PathBuf, FileOutcome)> {
+    let entries: Vec<_> = WalkDir::new(path)
+        .follow_links(false)
+        .max_depth(opts.max_depth.map_or(usize::MAX, NonZeroUsize::get))
+        .into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && is_target_dir(e.path())))
+        .filter_map(|entry| {
+            entry
+                .inspect_err(|e| tracing::warn!("Skipping unreadable path: {}", e))
+                .ok()
+        })
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            e.path()
+                .extension()
+                .and_then(|s| s.to_str())
+                .is_some_and(|ext| ext == "rs" || opts.counts_lines_of(ext))
+        })
+        .filter(|e| opts.includes(e.path()))
+        .collect();
+    // Each worker holds at most one file (and at most MAX_PARSED_FILE_BYTES
+    // of it), so memory stays bounded however large the checkout is
+    let total = entries.len();
+    let done = AtomicUsize::new(0);
+    entries
+        .into_par_iter()
+        .map(|entry| {
+            let outcome = analyze_file(entry.path(), cache);
+            let i = done.fetch_add(1, Ordering::Relaxed);
+            opts.progress
+                .phase(format!("Analyzing files {}/{}", i + 1, total));
+            (entry.into_path(), outcome)
+        })
+        .collect()
+}
+
+/// Measure the file at `path`: non-Rust and oversized Rust files (see
+/// [`MAX_PARSED_FILE_BYTES`]) are streamed, other Rust files are read whole
+/// and parsed unless `cache` holds their result
+fn analyze_file(path: &Path, cache: &FileCache<FileComplexity>) -> FileOutcome {
+    let streamed = || -> std::io::Result<LineScanner> {
+        scan_lines(BufReader::new(std::fs::File::open(path)?))
+    };
+    if path.extension().and_then(|s| s.to_str()) != Some("rs") {
+        return match streamed() {
+            Ok(scanner) => FileOutcome::Lines {
+                physical: scanner.file.lines,
+                non_blank: scanner.non_blank,
+            },
+            Err(e) => FileOutcome::Unreadable(e),
+        };
+    }
+    let oversized = std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_PARSED_FILE_BYTES);
+    if oversized {
+        tracing::debug!("Streaming oversized file {}", path.display());
+        return match streamed() {
+            Ok(scanner) if scanner.header.generated => FileOutcome::Generated,
+            Ok(scanner) => FileOutcome::Rust {
+                file: scanner.file,
+                uncached: None,
+            },
+            Err(e) => FileOutcome::Unreadable(e),
+        };
+    }
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return FileOutcome::Unreadable(e),
+    };
+    if is_generated(&content) {
+        return FileOutcome::Generated;
+    }
+    let hash = content_hash(content.as_bytes());
+    match cache.get(path, hash) {
+        Some(file) => FileOutcome::Rust {
+            file,
+            uncached: None,
+        },
+        None => FileOutcome::Rust {
+            file: FileComplexity::from_source(&content),
+            uncached: Some(hash),
+        },
+    }
+}
+
+/// Analyze project complexity for enhanced scoring
+pub async fn analyze_project_complexity(
+    path: &Path,
+    opts: &ComplexityOptions,
+) -> Result<ProjectComplexity> {
+    let mut complexity = ProjectComplexity::default();
+    let cache = match (opts.use_cache, &opts.cache_dir) {
+        (false, _) => FileCache::disabled(),
+        (true, Some(dir)) => FileCache::<FileComplexity>::open_in(dir, "complexity"),
+        (true, None) => FileCache::open("complexity"),
+    };
+
+    // Count dependencies from Cargo.toml if it exists
+    let cargo_toml = path.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&cargo_toml)
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok());
+    if let Some(deps) = manifest
+        .as_ref()
+        .and_then(|m| m.get("dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        complexity.dependency_count = deps.len();
+    }
+    if manifest.is_some() {
+        let shared = opts
+            .dependency_graph
+            .as_ref()
+            .and_then(|graph| graph.tree_of(path));
+        let tree = match shared {
+            Some(tree) => Some(tree),
+            None => dependency_tree(path).await,
+        };
+        complexity.transitive_dep_count = tree.map(|t| t.transitive);
+        complexity.dep_tree_depth = tree.map(|t| t.depth);
+    }
+    complexity.has_fuzzing = has_fuzzing(path, manifest.as_ref());
+    complexity.no_std = is_no_std(path);
+
+    // The walk and the parsing block, so they run off the async runtime
+    let (outcomes, mut cache) = {
+        let (path, opts) = (path.to_path_buf(), opts.clone());
+        tokio::task::spawn_blocking(move || (analyze_files(&path, &opts, &cache), cache)).await?
+    };
+    for (path, outcome) in outcomes {
+        match outcome {
+            FileOutcome::Lines {
+                physical,
+                non_blank,
+            } => {
+                complexity.total_lines += physical;
+                complexity.physical_lines += physical;
+                complexity.sloc += non_blank;
+            }
+            FileOutcome::Unreadable(e) => {
+                // Only Rust files count toward `file_count`
+                if path.extension().and_then(|s| s.to_str()) == Some("rs") {
+                    tracing::warn!("Skipping unreadable file {}: {}", path.display(), e);
+                    complexity.file_count += 1;
                 }
-                if trimmed.starts_with("///") || trimmed.starts_with("//!") {
-                    complexity.doc_lines += 1;
+            }
+            FileOutcome::Generated => {
+                tracing::debug!("Skipping generated file {}", path.display());
+                complexity.generated_files += 1;
+            }
+            FileOutcome::Rust { file, uncached } => {
+                if let Some(hash) = uncached {
+                    cache.insert(&path, hash, file.clone());
                 }
+                complexity.add_file(&file);
             }
         }
     }
 
+    if let Err(e) = cache.save() {
+        tracing::warn!("Failed to write complexity cache: {}", e);
+    }
+    complexity.total_lines = match opts.count_mode {
+        LineCount::Physical => complexity.physical_lines,
+        LineCount::Sloc => complexity.sloc,
+    };
+
     Ok(complexity)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cached_run_matches_fresh_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("lib.rs"),
+            "//! Crate docs\n/// Adds\npub fn add() {}\n#[test]\nfn t() {}\n",
+        )
+        .unwrap();
+
+        let cached = ComplexityOptions {
+            use_cache: true,
+            cache_dir: Some(tmp.path().join("cache")),
+            ..Default::default()
+        };
+        let first = analyze_project_complexity(&src, &cached).await.unwrap();
+        assert!(tmp
+            .path()
+            .join("cache")
+            .read_dir()
+            .unwrap()
+            .next()
+            .is_some());
+        let second = analyze_project_complexity(&src, &cached).await.unwrap();
+        let fresh = analyze_project_complexity(&src, &ComplexityOptions::default())
+            .await
+            .unwrap();
+
+        for c in [&second, &fresh] {
+            assert_eq!(c.total_lines, first.total_lines);
+            assert_eq!(c.function_count, first.function_count);
+            assert_eq!(c.doc_lines, first.doc_lines);
+            assert_eq!(c.test_count, first.test_count);
+            assert_eq!(*c, first);
+            assert_eq!(c.metrics_hash(), first.metrics_hash());
+        }
+    }
+
+    #[test]
+    fn caches_without_the_async_counts_are_rebuilt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        std::fs::write(&file, "async fn a() {}\n").unwrap();
+        let key = file.canonicalize().unwrap().to_string_lossy().into_owned();
+        let mut old = serde_json::to_value(FileComplexity::default()).unwrap();
+        old.as_object_mut().unwrap().remove("async_functions");
+        old.as_object_mut().unwrap().remove("await_points");
+        std::fs::write(
+            tmp.path()
+                .join(format!("complexity-v{}.json", env!("CARGO_PKG_VERSION"))),
+            serde_json::json!({ key: { "hash": 1, "value": old } }).to_string(),
+        )
+        .unwrap();
+
+        let cache = FileCache::<FileComplexity>::open_in(tmp.path(), "complexity");
+        assert_eq!(cache.get(&file, 1), None);
+    }
+
+    #[tokio::test]
+    async fn oversized_files_are_streamed_with_the_same_line_counts() {
+        let chunk = "//! Docs\r\n/// Adds\npub fn add() {}\n\n/* block\n   comment */\n\
+                     mod m {}\n#[test]\nfn t() {}\n";
+        let mut content = chunk.repeat(MAX_PARSED_FILE_BYTES as usize / chunk.len() + 1);
+        // A line longer than the streaming buffer is still one line
+        content.push_str(&format!(
+            "const DATA: &str = \"{}\";\n",
+            "x".repeat(100_000)
+        ));
+        content.push_str("fn last() {}");
+        assert!(content.len() as u64 > MAX_PARSED_FILE_BYTES);
+
+        let parsed = FileComplexity::from_source(&content);
+        let streamed = scan_lines(content.as_bytes()).unwrap().file;
+        assert_eq!(
+            streamed,
+            FileComplexity {
+                items: 0,
+                documented_items: 0,
+                public_items: 0,
+                documented_public_items: 0,
+                async_functions: 0,
+                await_points: 0,
+                ..parsed.clone()
+            }
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("huge.rs"), &content).unwrap();
+        let c = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(c.file_count, 1);
+        assert_eq!(c.physical_lines, parsed.lines);
+        assert_eq!(c.sloc, parsed.sloc);
+        assert_eq!(c.function_count, parsed.functions);
+        assert_eq!(c.test_count, parsed.tests);
+        assert_eq!(c.doc_lines, parsed.doc_lines);
+        assert_eq!(c.items, 0);
+
+        // The generated marker is still found without parsing
+        std::fs::write(
+            tmp.path().join("huge.rs"),
+            format!("// @generated\n{content}"),
+        )
+        .unwrap();
+        let c = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!((c.file_count, c.generated_files), (0, 1));
+    }
+
+    #[test]
+    fn metrics_hash_is_stable_and_order_independent() {
+        // Pinned: FNV-1a of twelve zero u64s, whatever the platform
+        assert_eq!(
+            FileComplexity::default().metrics_hash(),
+            0x0243_cfa8_4518_5aa5
+        );
+
+        let a = FileComplexity::from_source("/// Docs\npub fn a() {}\n");
+        let b = FileComplexity::from_source("async fn b() { c().await }\n#[test]\nfn t() {}\n");
+        assert_ne!(a.metrics_hash(), b.metrics_hash());
+        assert_eq!(a.content_hash, content_hash(b"/// Docs\npub fn a() {}\n"));
+
+        let mut forward = ProjectComplexity::default();
+        forward.add_file(&a);
+        forward.add_file(&b);
+        let mut backward = ProjectComplexity::default();
+        backward.add_file(&b);
+        backward.add_file(&a);
+        assert_eq!(forward, backward);
+        assert_eq!(forward.metrics_hash(), backward.metrics_hash());
+        assert_eq!(forward.content_hash(), backward.content_hash());
+
+        // An edit that keeps every count changes the content hash only
+        let edited = FileComplexity::from_source("/// Docs\npub fn z() {}\n");
+        assert_eq!(edited.metrics_hash(), a.metrics_hash());
+        let mut changed = ProjectComplexity::default();
+        changed.add_file(&edited);
+        changed.add_file(&b);
+        assert_eq!(changed.metrics_hash(), forward.metrics_hash());
+        assert_ne!(changed.content_hash(), forward.content_hash());
+        assert_ne!(changed, forward);
+
+        let unique: std::collections::HashSet<_> = [forward.clone(), backward, changed].into();
+        assert_eq!(unique.len(), 2);
+        let with_fuzzing = ProjectComplexity {
+            has_fuzzing: true,
+            ..forward.clone()
+        };
+        assert_ne!(with_fuzzing.metrics_hash(), forward.metrics_hash());
+        let no_std = ProjectComplexity {
+            no_std: true,
+            ..forward.clone()
+        };
+        assert_ne!(no_std.metrics_hash(), forward.metrics_hash());
+    }
+
+    #[test]
+    fn doc_coverage_counts_documented_items() {
+        let file = FileComplexity::from_source(include_str!("../tests/fixtures/doc_attributes.rs"));
+        // The `#[doc(hidden)]` module is skipped
+        assert_eq!(file.items, 5);
+        assert_eq!(file.public_items, 4);
+        // `#[doc = ...]` and `///`, but not the string literal
+        assert_eq!(file.documented_public_items, 2);
+
+        let mut project = ProjectComplexity::default();
+        project.add_file(&file);
+        assert!((project.doc_coverage() - 0.5).abs() < 1e-9);
+
+        let mut binary = ProjectComplexity::default();
+        binary.add_file(&FileComplexity::from_source(
+            "/// Entry point\nfn main() {}\nfn helper() {}\n",
+        ));
+        assert!((binary.doc_coverage() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn coverage_ratios_are_clamped() {
+        let project = ProjectComplexity {
+            function_count: 4,
+            test_count: 9,
+            items: 3,
+            documented_items: 7,
+            ..Default::default()
+        };
+        assert_eq!(project.test_coverage(), 1.0);
+        assert_eq!(project.doc_coverage(), 1.0);
+
+        let project = ProjectComplexity {
+            function_count: 4,
+            test_count: 1,
+            public_items: 4,
+            documented_public_items: 3,
+            documented_items: 40,
+            ..Default::default()
+        };
+        assert_eq!(project.test_coverage(), 0.25);
+        assert_eq!(project.doc_coverage(), 0.75);
+
+        let empty = ProjectComplexity {
+            test_count: 2,
+            documented_items: 2,
+            ..Default::default()
+        };
+        assert_eq!(empty.test_coverage(), 0.0);
+        assert_eq!(empty.doc_coverage(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn included_extensions_only_add_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(tmp.path().join("ffi.c"), "int f() {\n  return 1;\n}\n").unwrap();
+        std::fs::write(tmp.path().join("schema.sql"), "CREATE TABLE t (id int);\n").unwrap();
+
+        let rust_only = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(rust_only.total_lines, 1);
+
+        let opts = ComplexityOptions {
+            include_extensions: vec!["rs".into(), ".c".into()],
+            ..Default::default()
+        };
+        let mixed = analyze_project_complexity(tmp.path(), &opts).await.unwrap();
+        assert_eq!(mixed.total_lines, 4);
+        assert_eq!(mixed.function_count, rust_only.function_count);
+        assert_eq!(mixed.file_count, 1);
+    }
+
+    #[tokio::test]
+    async fn files_below_max_depth_are_skipped() {
+        // src/main.rs is at depth 2, vendor/deep-dep/src/a/b/c/lib.rs at depth 7
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/nested");
+        for (max_depth, files, functions) in [(None, 2, 3), (Some(6), 1, 1), (Some(7), 2, 3)] {
+            let opts = ComplexityOptions {
+                max_depth: max_depth.and_then(NonZeroUsize::new),
+                ..Default::default()
+            };
+            let complexity = analyze_project_complexity(&root, &opts).await.unwrap();
+            assert_eq!(
+                (complexity.file_count, complexity.function_count),
+                (files, functions),
+                "{max_depth:?}"
+            );
+        }
+
+        use clap::Parser;
+        let zero = crate::cli::Cli::try_parse_from(["crabscore", "score", "--max-depth", "0"]);
+        assert!(zero.is_err());
+    }
+
+    #[tokio::test]
+    async fn generated_files_are_excluded() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/generated");
+        let complexity = analyze_project_complexity(&root, &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(complexity.generated_files, 1);
+        assert_eq!(complexity.file_count, 1);
+        let lib =
+            FileComplexity::from_source(&std::fs::read_to_string(root.join("src/lib.rs")).unwrap());
+        assert_eq!(complexity.total_lines, lib.lines);
+        assert_eq!(complexity.function_count, lib.functions);
+
+        for (source, generated) in [
+            (
+                "// Code generated by protoc. DO NOT EDIT.\nfn f() {}\n",
+                true,
+            ),
+            ("\n/*\n * @generated by bindgen\n */\nfn f() {}\n", true),
+            ("// Licensed under MIT\n/* @generated */\nfn f() {}\n", true),
+            ("fn f() {}\n// @generated\n", false),
+            ("//! Hand-written\nfn f() {}\n", false),
+            // Doc comments are not a header, and prose is not a marker
+            ("//! @generated\nfn f() {}\n", false),
+            (
+                "/// Code generated by protoc. DO NOT EDIT.\nfn f() {}\n",
+                false,
+            ),
+            ("// Code generated by hand, then edited\nfn f() {}\n", false),
+            (
+                "// Wraps the @generated-bindings module\nfn f() {}\n",
+                false,
+            ),
+        ] {
+            assert_eq!(is_generated(source), generated, "{source:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn cargo_target_dirs_are_not_walked() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let out_dir = tmp.path().join("target/debug/build/app-1234/out");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        std::fs::write(out_dir.join("bindings.rs"), "pub fn generated() {}\n").unwrap();
+        std::fs::create_dir(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let complexity = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(complexity.file_count, 1);
+        assert_eq!(complexity.function_count, 1);
+    }
+
+    #[tokio::test]
+    async fn detects_each_fuzzing_signal() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fuzzing");
+        for (fixture, fuzzed) in [
+            ("cargo_fuzz", true),
+            ("proptest", true),
+            ("quickcheck", true),
+            ("none", false),
+        ] {
+            let complexity =
+                analyze_project_complexity(&fixtures.join(fixture), &ComplexityOptions::default())
+                    .await
+                    .unwrap();
+            assert_eq!(complexity.has_fuzzing, fuzzed, "{fixture}");
+        }
+    }
+
+    #[tokio::test]
+    async fn counts_transitive_dependencies_and_tree_depth() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dep_chain");
+        let tmp = tempfile::tempdir().unwrap();
+        // The lock file generated below must not land in the fixture
+        for entry in WalkDir::new(&fixture) {
+            let entry = entry.unwrap();
+            let target = tmp
+                .path()
+                .join(entry.path().strip_prefix(&fixture).unwrap());
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&target).unwrap();
+            } else {
+                std::fs::copy(entry.path(), &target).unwrap();
+            }
+        }
+
+        // Without a Cargo.lock nothing is resolved, and none is written
+        assert_eq!(dependency_tree(tmp.path()).await, None);
+        assert!(!tmp.path().join("Cargo.lock").exists());
+        let locked = std::process::Command::new("cargo")
+            .args(["generate-lockfile", "--offline"])
+            .current_dir(tmp.path())
+            .status();
+        if !locked.is_ok_and(|status| status.success()) {
+            // No cargo on this machine
+            return;
+        }
+
+        let tree = dependency_tree(tmp.path()).await.unwrap();
+        // a, b and c; the dev-dependency does not ship
+        assert_eq!(
+            tree,
+            DependencyTree {
+                transitive: 3,
+                depth: 3
+            }
+        );
+        let c = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(c.dependency_count, 1);
+        assert_eq!(
+            (c.transitive_dep_count, c.dep_tree_depth),
+            (Some(3), Some(3))
+        );
+
+        assert_eq!(dependency_tree(&tmp.path().join("src")).await, None);
+    }
+
+    #[test]
+    fn dependency_trees_of_members_and_virtual_workspaces() {
+        let metadata = r#"{
+            "workspace_root": "/ws",
+            "workspace_members": ["app", "lib"],
+            "packages": [
+                {"id": "app", "manifest_path": "/ws/app/Cargo.toml"},
+                {"id": "lib", "manifest_path": "/ws/lib/Cargo.toml"},
+                {"id": "serde", "manifest_path": "/registry/serde/Cargo.toml"}
+            ],
+            "resolve": {
+                "root": null,
+                "nodes": [
+                    {"id": "app", "deps": [
+                        {"pkg": "lib", "dep_kinds": [{"kind": null}]},
+                        {"pkg": "serde", "dep_kinds": [{"kind": null}]}
+                    ]},
+                    {"id": "lib", "deps": [
+                        {"pkg": "serde", "dep_kinds": [{"kind": null}]},
+                        {"pkg": "cc", "dep_kinds": [{"kind": "build"}]},
+                        {"pkg": "proptest", "dep_kinds": [{"kind": "dev"}]}
+                    ]},
+                    {"id": "serde", "deps": [{"pkg": "serde_derive", "dep_kinds": [{"kind": null}]}]},
+                    {"id": "serde_derive", "deps": []},
+                    {"id": "cc", "deps": []},
+                    {"id": "proptest", "deps": [{"pkg": "rand", "dep_kinds": [{"kind": null}]}]},
+                    {"id": "rand", "deps": []}
+                ]
+            }
+        }"#;
+        let graph = DependencyGraph::from_metadata(metadata).unwrap();
+        // Members are roots, not dependencies, of the whole workspace
+        assert_eq!(
+            graph.tree_of(Path::new("/ws")),
+            Some(DependencyTree {
+                transitive: 3,
+                depth: 2
+            })
+        );
+        // app -> lib -> serde -> serde_derive is longer than app -> serde
+        assert_eq!(
+            graph.tree_of(Path::new("/ws/app")),
+            Some(DependencyTree {
+                transitive: 4,
+                depth: 3
+            })
+        );
+        assert_eq!(graph.tree_of(Path::new("/elsewhere")), None);
+        assert_eq!(DependencyGraph::from_metadata(r#"{"resolve": null}"#), None);
+    }
+
+    #[tokio::test]
+    async fn detects_no_std_crate_roots() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let complexity =
+            analyze_project_complexity(&fixtures.join("no_std"), &ComplexityOptions::default())
+                .await
+                .unwrap();
+        assert!(complexity.no_std);
+        assert!(!is_no_std(&fixtures.join("nested")));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        for (source, expected) in [
+            ("#![cfg_attr(not(test), no_std)]\npub fn f() {}\n", true),
+            ("#![cfg_attr(all(), deny(warnings), no_std)]\n", true),
+            ("#![forbid(unsafe_code)]\npub fn f() {}\n", false),
+            // `no_std` only in the predicate or an unrelated attribute
+            (
+                "#![cfg_attr(feature = \"no_std\", deny(warnings))]\n",
+                false,
+            ),
+            ("#![cfg_attr(not(no_std), doc = \"no_std\")]\n", false),
+            // Only the crate-level inner attribute counts
+            ("// #![no_std]\n#[cfg(any())]\nmod m {}\n", false),
+        ] {
+            std::fs::write(&file, source).unwrap();
+            assert_eq!(is_no_std(&file), expected, "{source}");
+        }
+    }
+
+    #[tokio::test]
+    async fn sloc_skips_blank_and_comment_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("main.rs"),
+            "//! Crate docs\n\n\n/// Entry point\nfn main() {\n\n    // comment\n    \
+             let x = 1; // trailing\n\n    /* block\n       still comment\n    */\n    \
+             let y /* inline */ = 2;\n    /* a */ println!(\"{}\", x + y);\n}\n\n\n",
+        )
+        .unwrap();
+
+        let physical = analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap();
+        let opts = ComplexityOptions {
+            count_mode: LineCount::Sloc,
+            ..Default::default()
+        };
+        let sloc = analyze_project_complexity(tmp.path(), &opts).await.unwrap();
+        assert_eq!(physical.total_lines, 17);
+        assert_eq!(sloc.total_lines, 5);
+        for c in [&physical, &sloc] {
+            assert_eq!((c.physical_lines, c.sloc), (17, 5));
+        }
+    }
+}
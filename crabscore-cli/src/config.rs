@@ -0,0 +1,513 @@
+//! Project configuration from `crabscore.toml` or `[package.metadata.crabscore]`
+//!
+//! Settings are read from `crabscore.toml` in the project root when it
+//! exists, otherwise from the `[package.metadata.crabscore]` table of the
+//! project's `Cargo.toml`. Command-line flags take precedence over both.
+
+use anyhow::{bail, Context, Result};
+use crabscore_core::{
+    metrics::CarbonEfficiency,
+    scoring::{BinarySizeConfig, SafetyScoringConfig, Thresholds},
+    CertificationBands, GradeBands,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::scoring_engine::DEFAULT_MAX_COMPLEXITY_BONUS;
+
+/// Name of the standalone configuration file
+pub const CONFIG_FILE: &str = "crabscore.toml";
+
+/// Commented `crabscore.toml` written by `crabscore init`, spelling out the
+/// defaults of [`CrabScoreConfig`]
+pub const TEMPLATE: &str = r#"# CrabScore configuration
+#
+# Command-line flags take precedence over the values in this file. Every
+# value below is the default; delete what you do not want to change.
+#
+# Scores are weighted with the web-services profile (see
+# `crabscore profiles list`). Cost figures are read from crabscore-cost.json
+# next to this file, or from the file passed to `crabscore score --cost-file`.
+# Benchmarks run the discovered binary once to warm up and five times
+# measured; `--bin`, `--all-bins`, `--http-endpoint` and `--input-sizes` choose
+# what is benchmarked.
+
+# Minimum scores `crabscore score` enforces through its exit code (off by
+# default; `--fail-under`, `--min-performance`, `--min-energy` and `--min-cost`
+# override them)
+[thresholds]
+# overall = 70
+# performance = 60
+# energy = 60
+# cost = 60
+
+# Lowest overall score for each letter grade
+[grades]
+a = 90
+b = 80
+c = 70
+d = 60
+
+# Custom certification tiers replacing the built-in levels (Verified,
+# Certified, ...) in reports; the highest tier the overall score reaches is
+# shown
+# [[certifications]]
+# name = "Bronze"
+# min_overall = 60
+#
+# [[certifications]]
+# name = "Silver"
+# min_overall = 75
+#
+# [[certifications]]
+# name = "Gold"
+# min_overall = 90
+
+# Energy mix of the machines the project runs on (the CRABSCORE_CARBON_INTENSITY
+# and CRABSCORE_RENEWABLE_PCT environment variables override it)
+[carbon]
+carbon_intensity = 400    # gCO2/kWh
+renewable_percentage = 0.3
+
+# Safety bonus points. Setting a falloff awards partial credit that drops by
+# that many points per unsafe block or clippy warning.
+[safety]
+no_unsafe_points = 4
+# unsafe_falloff = 1.0
+clippy_clean_points = 3
+# clippy_falloff = 0.5
+low_complexity_points = 3
+max_avg_cyclomatic = 10
+# Subtract points per `.unwrap()`, `.expect()`, `panic!`, `todo!` or
+# `unimplemented!` outside test code beyond this many (off by default)
+# max_panic_sites = 20
+panic_site_penalty = 0.5
+
+# Energy points lost per doubling of a binary beyond its size budget. Only the
+# iot profile has a budget (1 MiB) unless budget_bytes sets one for all.
+[binary_size]
+enabled = true
+# budget_bytes = 4194304
+penalty_per_doubling = 10
+max_penalty = 50
+
+# Most points the size, documentation, test, dependency and fuzzing bonuses
+# can add together (`crabscore score --max-bonus` overrides it)
+[bonuses]
+complexity_cap = 10
+
+# Benchmark settings per binary name; `stdin` is relative to this file and
+# `--stdin-file` takes precedence over it
+# [bench.my-server]
+# args = ["--port", "0"]
+# stdin = "fixtures/request.json"
+# iterations = 20
+"#;
+
+/// Environment variable overriding the grid carbon intensity (gCO2/kWh)
+pub const CARBON_INTENSITY_ENV: &str = "CRABSCORE_CARBON_INTENSITY";
+
+/// Environment variable overriding the renewable energy share (0.0 to 1.0)
+pub const RENEWABLE_PCT_ENV: &str = "CRABSCORE_RENEWABLE_PCT";
+
+/// Project-level CrabScore settings
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CrabScoreConfig {
+    /// Minimum scores that `crabscore score` enforces through its exit code
+    pub thresholds: Thresholds,
+    /// Overall score cutoffs for the A–D letter grades
+    pub grades: GradeBands,
+    /// Custom certification tiers from `[[certifications]]`
+    pub certifications: CertificationBands,
+    /// Energy mix of the machines the project runs on
+    pub carbon: CarbonConfig,
+    /// Safety bonus points and falloff
+    pub safety: SafetyScoringConfig,
+    /// Energy penalty for oversized binaries
+    pub binary_size: BinarySizeConfig,
+    /// Limits on bonus points
+    pub bonuses: BonusConfig,
+    /// Benchmark settings per binary name
+    pub bench: BTreeMap<String, BinaryBench>,
+}
+
+/// How one binary is benchmarked, from `[bench.<name>]`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BinaryBench {
+    /// Arguments passed to the binary on every run (none when empty)
+    pub args: Vec<String>,
+    /// File piped to the binary's stdin on every run; relative paths are
+    /// resolved against the project directory when the configuration is loaded
+    pub stdin: Option<PathBuf>,
+    /// Measured iterations (at least 1)
+    pub iterations: Option<u32>,
+}
+
+/// Limits on the bonus points added to the overall score
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BonusConfig {
+    /// Most points the complexity bonuses (size, docs, tests, dependencies,
+    /// fuzzing) can add together
+    pub complexity_cap: f64,
+}
+
+impl Default for BonusConfig {
+    fn default() -> Self {
+        Self {
+            complexity_cap: DEFAULT_MAX_COMPLEXITY_BONUS,
+        }
+    }
+}
+
+impl BonusConfig {
+    /// Check that the cap is a non-negative number of points
+    pub fn validate(&self) -> Result<()> {
+        if !(self.complexity_cap >= 0.0 && self.complexity_cap.is_finite()) {
+            bail!(
+                "complexity bonus cap must be at least 0 points, got {}",
+                self.complexity_cap
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Carbon profile of the electricity grid, applied to estimated and measured energy
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CarbonConfig {
+    /// Carbon intensity of the grid in gCO2/kWh
+    pub carbon_intensity: f64,
+    /// Share of renewable energy (0.0 to 1.0)
+    pub renewable_percentage: f64,
+}
+
+impl Default for CarbonConfig {
+    fn default() -> Self {
+        Self {
+            carbon_intensity: 400.0,
+            renewable_percentage: 0.3,
+        }
+    }
+}
+
+impl CarbonConfig {
+    /// Replace the grid figures in `carbon` with these
+    pub fn apply(&self, carbon: &mut CarbonEfficiency) {
+        carbon.carbon_intensity = self.carbon_intensity;
+        carbon.renewable_percentage = self.renewable_percentage;
+    }
+
+    /// Override values from [`CARBON_INTENSITY_ENV`] and [`RENEWABLE_PCT_ENV`]
+    /// as returned by `var`
+    fn override_from(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for (name, field) in [
+            (CARBON_INTENSITY_ENV, &mut self.carbon_intensity),
+            (RENEWABLE_PCT_ENV, &mut self.renewable_percentage),
+        ] {
+            if let Some(value) = var(name) {
+                *field = value
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("{name} is not a number: {value:?}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the intensity is non-negative and the renewable share within 0–1
+    pub fn validate(&self) -> Result<()> {
+        if !(self.carbon_intensity >= 0.0 && self.carbon_intensity.is_finite()) {
+            bail!(
+                "carbon intensity must be at least 0 gCO2/kWh, got {}",
+                self.carbon_intensity
+            );
+        }
+        if !(0.0..=1.0).contains(&self.renewable_percentage) {
+            bail!(
+                "renewable percentage must be between 0 and 1, got {}",
+                self.renewable_percentage
+            );
+        }
+        Ok(())
+    }
+}
+
+impl CrabScoreConfig {
+    /// Load the configuration of the project at `project`.
+    ///
+    /// A missing file is not an error and yields the defaults; a file that
+    /// exists but does not parse is. The carbon settings can additionally be
+    /// overridden through [`CARBON_INTENSITY_ENV`] and [`RENEWABLE_PCT_ENV`].
+    pub fn load(project: &Path) -> Result<Self> {
+        Self::load_with_env(project, |name| std::env::var(name).ok())
+    }
+
+    fn load_with_env(project: &Path, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut config = Self::read(project)?;
+        config.carbon.override_from(var)?;
+        config.grades.validate()?;
+        config.certifications.validate()?;
+        config.carbon.validate()?;
+        config.bonuses.validate()?;
+        config.safety.validate()?;
+        config.binary_size.validate()?;
+        for (name, bench) in &mut config.bench {
+            if bench.iterations == Some(0) {
+                bail!("benchmark iterations of `{name}` must be at least 1");
+            }
+            if let Some(stdin) = &mut bench.stdin {
+                *stdin = config_dir(project).join(&*stdin);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Write [`TEMPLATE`] to `crabscore.toml` in `dir` and return its path.
+    ///
+    /// An existing file is only replaced when `force` is set.
+    pub fn write_template(dir: &Path, force: bool) -> Result<std::path::PathBuf> {
+        let path = dir.join(CONFIG_FILE);
+        if path.exists() && !force {
+            bail!(
+                "{} already exists (use --force to overwrite it)",
+                path.display()
+            );
+        }
+        std::fs::write(&path, TEMPLATE)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    fn read(project: &Path) -> Result<Self> {
+        let dir = config_dir(project);
+
+        let standalone = dir.join(CONFIG_FILE);
+        if standalone.is_file() {
+            let content = std::fs::read_to_string(&standalone)?;
+            return toml::from_str(&content)
+                .with_context(|| format!("invalid configuration in {}", standalone.display()));
+        }
+
+        let manifest = dir.join("Cargo.toml");
+        let Ok(content) = std::fs::read_to_string(&manifest) else {
+            return Ok(Self::default());
+        };
+        let cargo: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("invalid manifest {}", manifest.display()))?;
+        match cargo
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("crabscore"))
+        {
+            Some(table) => table.clone().try_into().with_context(|| {
+                format!(
+                    "invalid [package.metadata.crabscore] in {}",
+                    manifest.display()
+                )
+            }),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Directory holding the configuration of the project (or file) at `project`
+fn config_dir(project: &Path) -> &Path {
+    if project.is_file() {
+        project.parent().unwrap_or(Path::new("."))
+    } else {
+        project
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a project whose `crabscore.toml` holds `content`
+    fn load_config(content: &str) -> Result<CrabScoreConfig> {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(CONFIG_FILE), content).unwrap();
+        CrabScoreConfig::load(tmp.path())
+    }
+
+    #[test]
+    fn standalone_file_takes_precedence_over_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n\
+             [package.metadata.crabscore.thresholds]\nenergy = 40.0\n",
+        )
+        .unwrap();
+        let from_manifest = CrabScoreConfig::load(tmp.path()).unwrap();
+        assert_eq!(from_manifest.thresholds.energy, Some(40.0));
+        assert_eq!(from_manifest.thresholds.overall, None);
+
+        std::fs::write(
+            tmp.path().join(CONFIG_FILE),
+            "[thresholds]\noverall = 70\ncost = 55.5\n",
+        )
+        .unwrap();
+        let config = CrabScoreConfig::load(tmp.path()).unwrap();
+        assert_eq!(config.thresholds.overall, Some(70.0));
+        assert_eq!(config.thresholds.cost, Some(55.5));
+        assert_eq!(config.thresholds.energy, None);
+
+        std::fs::write(
+            tmp.path().join(CONFIG_FILE),
+            "[thresholds]\ncost = \"high\"\n",
+        )
+        .unwrap();
+        assert!(CrabScoreConfig::load(tmp.path()).is_err());
+    }
+
+    #[test]
+    fn grade_bands_load_and_validate() {
+        let grades = load_config("[grades]\na = 95\n").unwrap().grades;
+        assert_eq!(grades.a, 95.0);
+        assert_eq!(grades.b, 80.0);
+
+        assert!(load_config("[grades]\nb = 95\n").is_err());
+    }
+
+    #[test]
+    fn certification_bands_reject_duplicates() {
+        let certifications = load_config(
+            "[[certifications]]\nname = \"Bronze\"\nmin_overall = 60\n\n\
+             [[certifications]]\nname = \"Gold\"\nmin_overall = 90\n",
+        )
+        .unwrap()
+        .certifications;
+        assert_eq!(certifications.tier(95.0), Some("Gold"));
+        assert_eq!(certifications.tier(70.0), Some("Bronze"));
+
+        assert!(load_config(
+            "[[certifications]]\nname = \"Gold\"\nmin_overall = 90\n\n\
+             [[certifications]]\nname = \"Gold\"\nmin_overall = 95\n",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn safety_scoring_loads_and_validates() {
+        let safety = load_config("[safety]\nno_unsafe_points = 6\nunsafe_falloff = 1.5\n")
+            .unwrap()
+            .safety;
+        assert_eq!(safety.no_unsafe_points, 6.0);
+        assert_eq!(safety.unsafe_falloff, Some(1.5));
+        assert_eq!(safety.clippy_falloff, None);
+        assert_eq!(safety.clippy_clean_points, 3.0);
+
+        assert!(load_config("[safety]\nunsafe_falloff = -1\n").is_err());
+    }
+
+    #[test]
+    fn bonus_cap_loads_and_validates() {
+        let bonuses = load_config("[bonuses]\ncomplexity_cap = 5\n")
+            .unwrap()
+            .bonuses;
+        assert_eq!(bonuses.complexity_cap, 5.0);
+
+        assert!(load_config("[bonuses]\ncomplexity_cap = -1\n").is_err());
+    }
+
+    #[test]
+    fn binary_size_loads_and_validates() {
+        let binary_size = load_config("[binary_size]\nenabled = false\n")
+            .unwrap()
+            .binary_size;
+        assert!(!binary_size.enabled);
+        assert_eq!(binary_size.max_penalty, 50.0);
+
+        assert!(load_config("[binary_size]\nbudget_bytes = 0\n").is_err());
+    }
+
+    #[test]
+    fn bench_settings_come_from_either_source() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/bench_manifest");
+        let bench = CrabScoreConfig::load(&fixture).unwrap().bench;
+        assert_eq!(bench.keys().collect::<Vec<_>>(), ["parser", "server"]);
+        assert_eq!(bench["server"].args, ["--port", "0", "--workers", "2"]);
+        assert_eq!(bench["server"].iterations, Some(20));
+        assert_eq!(
+            bench["parser"].stdin.as_deref(),
+            Some(fixture.join("inputs/large.json").as_path())
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(CONFIG_FILE),
+            "[bench.app]\nargs = [\"-q\"]\nstdin = \"/dev/null\"\n",
+        )
+        .unwrap();
+        let app = &CrabScoreConfig::load(tmp.path()).unwrap().bench["app"];
+        assert_eq!(app.args, ["-q"]);
+        assert_eq!(app.stdin.as_deref(), Some(Path::new("/dev/null")));
+        assert_eq!(app.iterations, None);
+
+        for invalid in ["iterations = 0", "itertions = 3", "args = \"-q\""] {
+            std::fs::write(
+                tmp.path().join(CONFIG_FILE),
+                format!("[bench.app]\n{invalid}\n"),
+            )
+            .unwrap();
+            assert!(CrabScoreConfig::load(tmp.path()).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn template_matches_defaults_and_is_not_overwritten() {
+        let template: CrabScoreConfig = toml::from_str(TEMPLATE).unwrap();
+        assert_eq!(template, CrabScoreConfig::default());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = CrabScoreConfig::write_template(tmp.path(), false).unwrap();
+        assert_eq!(CrabScoreConfig::load(tmp.path()).unwrap(), template);
+
+        std::fs::write(&path, "[grades]\na = 95\n").unwrap();
+        assert!(CrabScoreConfig::write_template(tmp.path(), false).is_err());
+        assert_eq!(CrabScoreConfig::load(tmp.path()).unwrap().grades.a, 95.0);
+        CrabScoreConfig::write_template(tmp.path(), true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), TEMPLATE);
+    }
+
+    #[test]
+    fn environment_overrides_configured_carbon_profile() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(CONFIG_FILE),
+            "[carbon]\ncarbon_intensity = 250\nrenewable_percentage = 0.5\n",
+        )
+        .unwrap();
+        let no_env = |_: &str| None;
+        let config = CrabScoreConfig::load_with_env(tmp.path(), no_env).unwrap();
+        assert_eq!(config.carbon.carbon_intensity, 250.0);
+        assert_eq!(config.carbon.renewable_percentage, 0.5);
+
+        let iceland = |name: &str| match name {
+            CARBON_INTENSITY_ENV => Some("28".to_string()),
+            RENEWABLE_PCT_ENV => Some("1.0".to_string()),
+            _ => None,
+        };
+        let config = CrabScoreConfig::load_with_env(tmp.path(), iceland).unwrap();
+        assert_eq!(config.carbon.carbon_intensity, 28.0);
+        assert_eq!(config.carbon.renewable_percentage, 1.0);
+
+        for (name, value) in [
+            (CARBON_INTENSITY_ENV, "-1"),
+            (RENEWABLE_PCT_ENV, "30"),
+            (RENEWABLE_PCT_ENV, "lots"),
+        ] {
+            let bad = |n: &str| (n == name).then(|| value.to_string());
+            assert!(
+                CrabScoreConfig::load_with_env(tmp.path(), bad).is_err(),
+                "{name}={value}"
+            );
+        }
+    }
+}
@@ -1,56 +1,77 @@
 //! Performance, energy, and cost estimation functions for graceful degradation
 
 use crate::complexity::ProjectComplexity;
+use crate::config::CarbonConfig;
+use crate::scoring_engine::ComplexityAwareScoringEngine;
 use crabscore_core::metrics::*;
+use crabscore_core::{CrabScore, EstimateError, EstimationCalibration};
+use crabscore_energy::thermal::DEFAULT_THERMAL_EFFICIENCY;
 
 /// Estimate performance metrics based on code complexity
+///
+/// Async code is treated as I/O-bound: requests wait longer (with a longer
+/// tail) but many are in flight at once.
 pub fn estimate_performance_from_complexity(complexity: &ProjectComplexity) -> PerformanceMetrics {
     // Heuristic: smaller, simpler projects tend to have better latency
     let complexity_factor = complexity.complexity_factor();
-    let base_latency = 10.0 + complexity_factor * 5.0;
+    let io_bound = complexity.async_ratio();
+    let base_latency = (10.0 + complexity_factor * 5.0) * (1.0 + io_bound);
+    let concurrency = 1.0 + 9.0 * io_bound;
 
     PerformanceMetrics {
         latency: LatencyMetrics {
             p50_ms: base_latency,
-            p95_ms: base_latency * 1.5,
-            p99_ms: base_latency * 2.0,
+            p95_ms: base_latency * (1.5 + 0.5 * io_bound),
+            p99_ms: base_latency * (2.0 + io_bound),
+            p999_ms: base_latency * (3.0 + 2.0 * io_bound),
+            stddev_ms: 0.0,
+            reliable_percentiles: false,
             cold_start_ms: base_latency * 3.0,
             ttfb_ms: base_latency * 0.3,
         },
         throughput: ThroughputMetrics {
-            requests_per_second: 1000.0 / base_latency,
+            requests_per_second: 1000.0 / base_latency * concurrency,
             mb_per_second: 100.0 / complexity_factor.max(1.0),
-            concurrent_connections: 100,
+            concurrent_connections: (100.0 * concurrency) as u64,
             queue_depth: 10.0,
         },
         resource_usage: ResourceMetrics {
             cpu_efficiency: 0.8 - (complexity_factor * 0.05).min(0.5),
             memory_bandwidth_gb_s: 10.0,
-            io_operations_per_sec: 1000.0,
+            io_operations_per_sec: 1000.0 * concurrency,
             cache_hit_rate: 0.9 - (complexity_factor * 0.02).min(0.3),
+            binary_size_bytes: None,
         },
         scalability: ScalabilityMetrics::default(),
     }
 }
 
-/// Estimate energy metrics based on project size and complexity
-pub fn estimate_energy_from_complexity(complexity: &ProjectComplexity) -> EnergyMetrics {
+/// Estimate energy metrics based on project size and complexity on a grid
+/// with the given carbon profile
+///
+/// The CPU of an I/O-bound (async) program idles while awaiting, lowering
+/// its average draw.
+pub fn estimate_energy_from_complexity(
+    complexity: &ProjectComplexity,
+    grid: &CarbonConfig,
+) -> EnergyMetrics {
     let size_factor = complexity.complexity_factor();
+    let io_bound = complexity.async_ratio();
 
     EnergyMetrics {
         direct_consumption: PowerConsumption {
-            average_watts: 5.0 + size_factor * 2.0,
+            average_watts: (5.0 + size_factor * 2.0) * (1.0 - 0.3 * io_bound),
             peak_watts: 10.0 + size_factor * 5.0,
             idle_watts: 2.0 + size_factor * 0.5,
             joules_per_operation: 0.001 * (1.0 + size_factor * 0.1),
         },
         carbon_efficiency: CarbonEfficiency {
             co2_per_operation: 0.0001 * (1.0 + size_factor * 0.1),
-            carbon_intensity: 400.0,   // Default grid intensity
-            renewable_percentage: 0.3, // Assume 30% renewable by default
+            carbon_intensity: grid.carbon_intensity,
+            renewable_percentage: grid.renewable_percentage,
         },
         hardware_lifecycle: HardwareLifecycle {
-            thermal_efficiency: 0.8,
+            thermal_efficiency: DEFAULT_THERMAL_EFFICIENCY,
             component_stress: 0.2 + (size_factor * 0.05).min(0.5),
             expected_lifespan_years: 5.0,
         },
@@ -64,9 +85,15 @@ pub fn estimate_energy_from_complexity(complexity: &ProjectComplexity) -> Energy
 }
 
 /// Estimate cost metrics based on project complexity and maintenance burden
+///
+/// Every 50 transitive dependencies add an onboarding day (up to a week),
+/// and each level of the dependency tree adds to the time to repair, as
+/// failures deep in the tree take longer to track down.
 pub fn estimate_cost_from_complexity(complexity: &ProjectComplexity) -> CostMetrics {
     let size_factor = complexity.complexity_factor();
     let maintenance_factor = complexity.function_count as f64 / 10.0;
+    let dependency_days = (complexity.transitive_dep_count.unwrap_or(0) as f64 / 50.0).min(7.0);
+    let tree_depth = complexity.dep_tree_depth.unwrap_or(0) as f64;
 
     CostMetrics {
         infrastructure: InfrastructureCosts {
@@ -76,7 +103,7 @@ pub fn estimate_cost_from_complexity(complexity: &ProjectComplexity) -> CostMetr
             cost_per_million_ops: 0.1 + size_factor * 0.05,
         },
         operations: OperationalCosts {
-            mttr_minutes: 30.0 + maintenance_factor * 10.0,
+            mttr_minutes: 30.0 + maintenance_factor * 10.0 + tree_depth * 5.0,
             incidents_per_month: 0.5 + size_factor * 0.2,
             overhead_percentage: 0.1 + (size_factor * 0.02).min(0.3),
             monitoring_usd: 5.0 + size_factor * 5.0,
@@ -85,7 +112,7 @@ pub fn estimate_cost_from_complexity(complexity: &ProjectComplexity) -> CostMetr
             loc: complexity.total_lines as u64,
             cyclomatic_complexity: 1.0 + (complexity.function_count as f64 / 10.0),
             code_churn: 100.0 + size_factor * 50.0,
-            onboarding_days: 1.0 + (size_factor * 2.0).min(14.0),
+            onboarding_days: 1.0 + (size_factor * 2.0).min(14.0) + dependency_days,
         },
         business_impact: BusinessImpact {
             revenue_per_100ms_latency: 100.0,
@@ -95,3 +122,136 @@ pub fn estimate_cost_from_complexity(complexity: &ProjectComplexity) -> CostMetr
         },
     }
 }
+
+/// Score the static estimates for `complexity` with `engine` and compare
+/// them with the `measured` score, dimension by dimension.
+///
+/// The estimates are scored with the measured `safety`, so only the
+/// estimation itself differs.
+pub fn check_estimates(
+    engine: &ComplexityAwareScoringEngine,
+    complexity: &ProjectComplexity,
+    grid: &CarbonConfig,
+    safety: &SafetyMetrics,
+    measured: &CrabScore,
+) -> EstimationCalibration {
+    let estimated = engine.explain(
+        &estimate_performance_from_complexity(complexity),
+        &estimate_energy_from_complexity(complexity, grid),
+        &estimate_cost_from_complexity(complexity),
+        safety,
+    );
+    EstimationCalibration {
+        performance: EstimateError::new(estimated.performance.score, measured.performance),
+        energy: EstimateError::new(estimated.energy.score, measured.energy),
+        cost: EstimateError::new(estimated.cost.score, measured.cost),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::complexity::{analyze_project_complexity, ComplexityOptions};
+    use crabscore_core::IndustryProfile;
+
+    #[test]
+    fn dependency_trees_add_onboarding_and_repair_time() {
+        let lean = ProjectComplexity::default();
+        let bloated = ProjectComplexity {
+            transitive_dep_count: Some(200),
+            dep_tree_depth: Some(6),
+            ..Default::default()
+        };
+        let (lean, bloated) = (
+            estimate_cost_from_complexity(&lean),
+            estimate_cost_from_complexity(&bloated),
+        );
+        assert_eq!(
+            bloated.development.onboarding_days - lean.development.onboarding_days,
+            4.0
+        );
+        assert_eq!(
+            bloated.operations.mttr_minutes - lean.operations.mttr_minutes,
+            30.0
+        );
+    }
+
+    #[test]
+    fn estimate_errors_are_relative_to_the_measured_scores() {
+        let complexity = ProjectComplexity::default();
+        let engine =
+            ComplexityAwareScoringEngine::new(IndustryProfile::default(), complexity.clone());
+        let safety = SafetyMetrics::default();
+        let mut measured = engine.calculate_score(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &safety,
+        );
+        (measured.performance, measured.energy, measured.cost) = (90.0, 40.0, 55.0);
+
+        let calibration = check_estimates(
+            &engine,
+            &complexity,
+            &CarbonConfig::default(),
+            &safety,
+            &measured,
+        );
+        // An empty project is estimated at 10 ms p50 / 15 ms p95, 100 req/s and
+        // 80% CPU efficiency
+        let performance = (100.0 / 1.15 + 100.0 / 11.0 + 80.0) / 3.0;
+        assert!((calibration.performance.estimated - performance).abs() < 1e-9);
+        assert_eq!(calibration.performance.measured, 90.0);
+        assert!((calibration.performance.error - (performance - 90.0)).abs() < 1e-9);
+        for (check, measured) in [(calibration.energy, 40.0), (calibration.cost, 55.0)] {
+            assert_eq!(check.measured, measured);
+            assert_eq!(check.error, check.estimated - measured);
+        }
+    }
+
+    async fn complexity_of(source: &str) -> ProjectComplexity {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("main.rs"), source).unwrap();
+        analyze_project_complexity(tmp.path(), &ComplexityOptions::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn async_code_is_estimated_as_io_bound() {
+        let sync = complexity_of(include_str!("../tests/fixtures/sync_service.rs")).await;
+        let asynchronous = complexity_of(include_str!("../tests/fixtures/async_service.rs")).await;
+        assert_eq!(sync.async_ratio(), 0.0);
+        assert!(asynchronous.async_ratio() > 0.5, "{asynchronous:?}");
+        assert!(asynchronous.await_points >= 3);
+        // Same size, so only the async signal separates the estimates
+        assert_eq!(sync.complexity_factor(), asynchronous.complexity_factor());
+
+        let (sync_perf, async_perf) = (
+            estimate_performance_from_complexity(&sync),
+            estimate_performance_from_complexity(&asynchronous),
+        );
+        assert!(async_perf.latency.p50_ms > sync_perf.latency.p50_ms);
+        assert!(
+            async_perf.latency.p99_ms / async_perf.latency.p50_ms
+                > sync_perf.latency.p99_ms / sync_perf.latency.p50_ms
+        );
+        assert!(
+            async_perf.throughput.concurrent_connections
+                > sync_perf.throughput.concurrent_connections
+        );
+        assert!(
+            async_perf.throughput.requests_per_second > sync_perf.throughput.requests_per_second
+        );
+
+        let grid = CarbonConfig::default();
+        assert!(
+            estimate_energy_from_complexity(&asynchronous, &grid)
+                .direct_consumption
+                .average_watts
+                < estimate_energy_from_complexity(&sync, &grid)
+                    .direct_consumption
+                    .average_watts
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Git helpers for scoring only what changed since a given revision and for
+//! measuring code churn
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Files that differ between `base` and the working tree of the repository
+/// containing `path`, including untracked files.
+///
+/// Paths are canonical; deleted files are left out.
+pub async fn changed_files(path: &Path, base: &str) -> Result<Vec<PathBuf>> {
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(Path::new(""))
+    } else {
+        path
+    };
+    let toplevel = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"]).await?.trim());
+    let changed = git(dir, &["diff", "--name-only", base, "--"]).await?;
+    let untracked = git(
+        dir,
+        &["ls-files", "--others", "--exclude-standard", "--full-name"],
+    )
+    .await?;
+    Ok(changed
+        .lines()
+        .chain(untracked.lines())
+        .filter_map(|name| toplevel.join(name).canonicalize().ok())
+        .collect())
+}
+
+/// Lines added plus lines deleted in Rust files under `path` by the commits
+/// of the last `days` days, according to `git log --numstat`.
+///
+/// For a file, only that file's changes count. Fails outside a git repository.
+pub async fn code_churn(path: &Path, days: u32) -> Result<u64> {
+    let (dir, pathspec) = match (path.is_file(), path.file_name()) {
+        (true, Some(name)) => (
+            path.parent().unwrap_or(Path::new("")),
+            name.to_string_lossy().into_owned(),
+        ),
+        _ => (path, "*.rs".to_string()),
+    };
+    let since = format!("--since={days}.days.ago");
+    let log = git(
+        dir,
+        &["log", &since, "--numstat", "--format=", "--", &pathspec],
+    )
+    .await?;
+    Ok(log
+        .lines()
+        .filter_map(|line| {
+            // `added<TAB>deleted<TAB>path`, with `-` counts for binary files
+            let mut fields = line.split('\t');
+            let added: u64 = fields.next()?.parse().ok()?;
+            let deleted: u64 = fields.next()?.parse().ok()?;
+            Some(added + deleted)
+        })
+        .sum())
+}
+
+/// Stdout of `git -C dir args`, failing with git's message on error
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("git is not available")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Commit the staged changes of `repo`, independent of the user's git
+    /// configuration
+    fn commit(repo: &Path, message: &str, date: Option<&str>) {
+        let mut command = std::process::Command::new("git");
+        command
+            .args(["-c", "commit.gpgsign=false", "-C"])
+            .arg(repo)
+            .args(["commit", "-q", "-m", message])
+            .env("GIT_AUTHOR_NAME", "t")
+            .env("GIT_AUTHOR_EMAIL", "t@example.com")
+            .env("GIT_COMMITTER_NAME", "t")
+            .env("GIT_COMMITTER_EMAIL", "t@example.com");
+        if let Some(date) = date {
+            command
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date);
+        }
+        assert!(command.status().unwrap().success());
+    }
+
+    #[tokio::test]
+    async fn churn_sums_recent_rust_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git(repo, &["init", "-q"]).await.unwrap();
+        assert!(code_churn(repo, 30).await.is_err(), "no commits yet");
+
+        // Outside the window: 4 lines
+        std::fs::write(
+            repo.join("lib.rs"),
+            "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n",
+        )
+        .unwrap();
+        git(repo, &["add", "."]).await.unwrap();
+        commit(repo, "old", Some("2000-01-01T00:00:00Z"));
+
+        // Inside the window: 1 line rewritten (1 + 1), 2 lines added, README ignored
+        std::fs::write(
+            repo.join("lib.rs"),
+            "fn a() {}\nfn b() {}\nfn c() {}\nfn e() {}\n",
+        )
+        .unwrap();
+        std::fs::create_dir(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/main.rs"), "fn main() {}\n\n").unwrap();
+        std::fs::write(repo.join("README.md"), "one\ntwo\n").unwrap();
+        git(repo, &["add", "."]).await.unwrap();
+        commit(repo, "recent", None);
+
+        assert_eq!(code_churn(repo, 30).await.unwrap(), 4);
+        assert_eq!(code_churn(&repo.join("lib.rs"), 30).await.unwrap(), 2);
+        assert_eq!(code_churn(&repo.join("src"), 30).await.unwrap(), 2);
+    }
+}
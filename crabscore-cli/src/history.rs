@@ -0,0 +1,195 @@
+//! Score history stored as JSON Lines, one report per line
+//!
+//! `crabscore score --baseline-auto` appends `{"project": ..., "score": ...}`
+//! lines to the history file (see [`default_path`]) and compares each run
+//! with the last one recorded for the same project.
+
+use anyhow::{Context, Result};
+use crabscore_core::CrabScore;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Name of the history file in `~/.crabscore/`
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// `$CRABSCORE_HISTORY`, or [`HISTORY_FILE`] in `~/.crabscore/`, if a home
+/// directory can be determined.
+///
+/// The history lives next to the analysis cache rather than in it, so that
+/// clearing the cache does not lose past scores.
+pub fn default_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("CRABSCORE_HISTORY") {
+        return Some(PathBuf::from(path));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".crabscore").join(HISTORY_FILE))
+}
+
+/// One recorded run
+#[derive(Serialize)]
+struct Entry<'a> {
+    project: &'a Path,
+    score: &'a CrabScore,
+}
+
+/// Append `score` of `project` to the history file at `history`, creating it
+/// (and its directory) if needed.
+pub fn record(history: &Path, project: &Path, score: &CrabScore) -> Result<()> {
+    if let Some(dir) = history.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(&Entry { project, score })?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("failed to write {}", history.display()))
+}
+
+/// The scores recorded for `project` in the history file at `history`,
+/// oldest first; empty when there is no history file yet.
+///
+/// Lines that do not parse are skipped with a warning, like in [`load`];
+/// lines without a project belong to none.
+pub fn for_project(history: &Path, project: &Path) -> Result<Vec<CrabScore>> {
+    let content = match std::fs::read_to_string(history) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", history.display())),
+    };
+    let mut scores = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(value) => value,
+            Err(e) => {
+                warn_malformed(history, i, &e.into());
+                continue;
+            }
+        };
+        let recorded = value.get("project").and_then(|p| p.as_str());
+        if recorded.map(Path::new) != Some(project) {
+            continue;
+        }
+        match parse_value(value) {
+            Ok(score) => scores.push(score),
+            Err(e) => warn_malformed(history, i, &e),
+        }
+    }
+    Ok(scores)
+}
+
+/// The most recent score recorded for `project` (see [`for_project`]), or
+/// `None` when there is none.
+pub fn latest_for(history: &Path, project: &Path) -> Result<Option<CrabScore>> {
+    Ok(for_project(history, project)?.pop())
+}
+
+/// Load the past scores recorded in the JSON Lines file at `path`, oldest first.
+///
+/// Each line holds a bare score or a `{"score": ...}` report of any supported
+/// schema version. Lines that do not parse (e.g. left truncated by an
+/// interrupted write) are skipped with a warning; blank lines are ignored.
+pub fn load(path: &Path) -> Result<Vec<CrabScore>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut scores = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line)
+            .map_err(anyhow::Error::from)
+            .and_then(parse_value)
+        {
+            Ok(score) => scores.push(score),
+            Err(e) => warn_malformed(path, i, &e),
+        }
+    }
+    Ok(scores)
+}
+
+/// The score of a parsed line: a bare score or the `score` of a report
+fn parse_value(mut value: serde_json::Value) -> Result<CrabScore> {
+    if let Some(score) = value.get_mut("score") {
+        value = score.take();
+    }
+    Ok(CrabScore::from_value(value)?)
+}
+
+/// Warn that the line at index `i` of `path` is skipped
+fn warn_malformed(path: &Path, i: usize, e: &anyhow::Error) {
+    warn!(
+        "Skipping malformed line {} of {}: {:#}",
+        i + 1,
+        path.display(),
+        e
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    #[test]
+    fn corrupt_lines_are_skipped() {
+        let score = default_score(IndustryProfile::default());
+        let good = serde_json::to_string(&score).unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("history.jsonl");
+        // The second write was cut short
+        std::fs::write(&path, format!("{good}\n{}\n\n", &good[..good.len() / 2])).unwrap();
+
+        let history = load(&path).unwrap();
+        assert_eq!(history, [score]);
+
+        assert!(load(&tmp.path().join("missing.jsonl")).is_err());
+    }
+
+    #[test]
+    fn latest_score_of_the_same_project_is_the_baseline() {
+        let score = |overall| {
+            let mut score = default_score(IndustryProfile::default());
+            score.overall = overall;
+            score
+        };
+        let tmp = tempfile::tempdir().unwrap();
+        let history = tmp.path().join(".crabscore").join(HISTORY_FILE);
+        let (app, lib) = (Path::new("/work/app"), Path::new("/work/lib"));
+        assert_eq!(latest_for(&history, app).unwrap(), None);
+
+        record(&history, app, &score(70.0)).unwrap();
+        record(&history, lib, &score(50.0)).unwrap();
+        record(&history, app, &score(75.0)).unwrap();
+        let overall = |project| latest_for(&history, project).unwrap().map(|s| s.overall);
+        assert_eq!(overall(app), Some(75.0));
+        assert_eq!(overall(lib), Some(50.0));
+        assert_eq!(overall(Path::new("/work")), None);
+        let app_scores: Vec<f64> = for_project(&history, app)
+            .unwrap()
+            .iter()
+            .map(|s| s.overall)
+            .collect();
+        assert_eq!(app_scores, [70.0, 75.0]);
+        // Recorded runs are ordinary history lines
+        assert_eq!(load(&history).unwrap().len(), 3);
+
+        // A truncated line is skipped, not fatal
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&history)
+            .and_then(|mut file| file.write_all(b"{\"project\": \"/work/app\", \"sco\n"))
+            .unwrap();
+        record(&history, app, &score(80.0)).unwrap();
+        assert_eq!(overall(app), Some(80.0));
+    }
+}
@@ -9,6 +9,18 @@ pub mod cli;
 /// Command execution
 pub mod command;
 
+/// Project configuration (crabscore.toml)
+pub mod config;
+
+/// Comparison of two saved reports
+pub mod compare;
+
+/// Git helpers for diff-scoped scoring
+pub mod git;
+
+/// Score history stored as JSON Lines
+pub mod history;
+
 /// Project complexity analysis
 pub mod complexity;
 
@@ -20,3 +32,17 @@ pub mod estimation;
 
 /// Complexity-aware scoring engine
 pub mod scoring_engine;
+
+/// Terminal tables and color handling
+pub mod output;
+
+/// Industry profile inference from Cargo metadata
+pub mod profile_inference;
+
+/// Progress spinner for long-running phases
+pub mod progress;
+
+/// Workspace member discovery and score aggregation
+pub mod workspace;
+
+pub use command::{score_path, score_path_detailed, ScoreOptions, ScoredProject};
@@ -1,9 +1,67 @@
-use anyhow::Result;
 use clap::Parser;
-use crabscore_cli::{cli::Cli, command};
+use crabscore_cli::{cli::Cli, command, output};
+use crabscore_core::error::CrabScoreError;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
-    command::execute(cli.command, cli.verbose).await
+    output::init_color(cli.no_color);
+    match command::execute(cli.command, cli.verbose).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+/// Exit status of a failed run: 3 when the binary to benchmark is missing,
+/// 4 when all its runs failed, 5 on a timeout, 6 for invalid configuration
+/// or input and 1 otherwise (clap exits with 2 on invalid arguments)
+fn exit_code(e: &anyhow::Error) -> u8 {
+    match e
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<CrabScoreError>())
+    {
+        Some(CrabScoreError::BinaryNotFound(_)) => 3,
+        Some(CrabScoreError::AllIterationsFailed { .. }) => 4,
+        Some(CrabScoreError::Timeout { .. }) => 5,
+        Some(CrabScoreError::ConfigError(_) | CrabScoreError::ValidationError(_)) => 6,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn errors_map_to_exit_codes() {
+        let code = |e: CrabScoreError| exit_code(&anyhow::Error::new(e));
+        assert_eq!(code(CrabScoreError::BinaryNotFound("app".into())), 3);
+        assert_eq!(
+            code(CrabScoreError::AllIterationsFailed {
+                target: "app".into(),
+                attempts: 5,
+            }),
+            4
+        );
+        assert_eq!(
+            code(CrabScoreError::Timeout {
+                operation: "startup".into(),
+                after: Duration::from_secs(1),
+            }),
+            5
+        );
+        assert_eq!(code(CrabScoreError::config("bad grades")), 6);
+        assert_eq!(code(CrabScoreError::measurement("no samples")), 1);
+        assert_eq!(exit_code(&anyhow::anyhow!("untyped")), 1);
+
+        // Context added on the way up does not hide the cause
+        let wrapped = anyhow::Error::new(CrabScoreError::BinaryNotFound("app".into()))
+            .context("Benchmark failed (--strict)");
+        assert_eq!(exit_code(&wrapped), 3);
+    }
 }
@@ -0,0 +1,244 @@
+//! Terminal rendering helpers for human-readable output
+
+use crate::cli::ProfileSummary;
+use crate::compare::{Change, DimensionDelta};
+use comfy_table::{presets, Cell, CellAlignment, Color, ContentArrangement, Table};
+use crabscore_analysis::safety::FunctionComplexity;
+use crabscore_core::{CrabScore, TargetMeasurement};
+use std::io::IsTerminal;
+
+/// Width of the score bar in characters
+const BAR_WIDTH: usize = 20;
+
+/// Disable all `colored` output when `--no-color` is passed or `NO_COLOR`
+/// is set in the environment.
+pub fn init_color(no_color: bool) {
+    if no_color || std::env::var_os("NO_COLOR").is_some() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Whether colored output (and Unicode decorations) should be used: stdout
+/// is a terminal and neither `NO_COLOR` nor `--no-color` is in effect.
+pub fn color_enabled() -> bool {
+    std::env::var_os("NO_COLOR").is_none()
+        && colored::control::SHOULD_COLORIZE.should_colorize()
+        && std::io::stdout().is_terminal()
+}
+
+/// Horizontal rule of `width` characters
+pub fn rule(width: usize, color: bool) -> String {
+    if color { "━" } else { "=" }.repeat(width)
+}
+
+/// Marker for an earned bonus
+pub fn check_mark(color: bool) -> &'static str {
+    if color {
+        "✓"
+    } else {
+        "+"
+    }
+}
+
+/// Borderless table that wraps to the terminal width when `color` is set
+/// and renders as plain text otherwise.
+fn new_table(color: bool) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    if color {
+        table.enforce_styling();
+    } else {
+        table.force_no_tty();
+    }
+    table
+}
+
+fn score_bar(score: f64, color: bool) -> Cell {
+    let filled = ((score / 100.0) * BAR_WIDTH as f64).clamp(0.0, BAR_WIDTH as f64) as usize;
+    if !color {
+        return Cell::new("#".repeat(filled) + &"-".repeat(BAR_WIDTH - filled));
+    }
+    Cell::new("█".repeat(filled) + &"░".repeat(BAR_WIDTH - filled)).fg(if score >= 80.0 {
+        Color::Green
+    } else if score >= 60.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    })
+}
+
+/// Performance / energy / cost breakdown with a score bar per dimension
+pub fn breakdown_table(score: &CrabScore, color: bool) -> Table {
+    let mut table = new_table(color);
+    for (name, value) in [
+        ("Performance", score.performance),
+        ("Energy", score.energy),
+        ("Cost", score.cost),
+    ] {
+        table.add_row(vec![
+            Cell::new(format!("{name}:")),
+            Cell::new(format!("{value:.0}/100")).set_alignment(CellAlignment::Right),
+            score_bar(value, color),
+        ]);
+    }
+    table
+}
+
+/// Profile names with their dimension weights as percentages
+pub fn profiles_table(profiles: &[ProfileSummary], color: bool) -> Table {
+    let mut table = new_table(color);
+    table.set_header(vec!["Profile", "Performance", "Energy", "Cost", "Use for"]);
+    for profile in profiles {
+        let percent = |weight: f64| {
+            Cell::new(format!("{:.0}%", weight * 100.0)).set_alignment(CellAlignment::Right)
+        };
+        table.add_row(vec![
+            Cell::new(&profile.name),
+            percent(profile.performance),
+            percent(profile.energy),
+            percent(profile.cost),
+            Cell::new(&profile.description),
+        ]);
+    }
+    table
+}
+
+/// Rank, name, file and cyclomatic complexity of each function
+pub fn complex_functions_table(functions: &[FunctionComplexity], color: bool) -> Table {
+    let mut table = new_table(color);
+    table.set_header(vec!["#", "Function", "File", "Complexity"]);
+    for (rank, function) in functions.iter().enumerate() {
+        table.add_row(vec![
+            Cell::new(rank + 1).set_alignment(CellAlignment::Right),
+            Cell::new(&function.name),
+            Cell::new(function.file.display()),
+            Cell::new(function.complexity).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    table
+}
+
+/// Baseline, current and change for each compared dimension
+pub fn comparison_table(dimensions: &[DimensionDelta], color: bool) -> Table {
+    let mut table = new_table(color);
+    table.set_header(vec!["Dimension", "Baseline", "Current", "Change"]);
+    for d in dimensions {
+        let text = match d.change {
+            Change::Unchanged => "unchanged".to_string(),
+            _ => format!("{:+.1}", d.delta),
+        };
+        let mut change = Cell::new(text).set_alignment(CellAlignment::Right);
+        if color {
+            change = match d.change {
+                Change::Improved => change.fg(Color::Green),
+                Change::Regressed => change.fg(Color::Red),
+                Change::Unchanged => change,
+            };
+        }
+        table.add_row(vec![
+            Cell::new(d.dimension),
+            Cell::new(format!("{:.1}", d.baseline)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.1}", d.current)).set_alignment(CellAlignment::Right),
+            change,
+        ]);
+    }
+    table
+}
+
+/// Per-target latency percentiles and binary sizes
+pub fn targets_table(targets: &[TargetMeasurement], color: bool) -> Table {
+    let mut table = new_table(color);
+    table.set_header(vec!["Target", "p50 ms", "p95 ms", "p99 ms", "Size"]);
+    for target in targets {
+        let size = target
+            .binary_size_bytes
+            .map_or("-".to_string(), format_size);
+        table.add_row(vec![
+            Cell::new(&target.name),
+            Cell::new(format!("{:.2}", target.latency.p50_ms)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.2}", target.latency.p95_ms)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.2}", target.latency.p99_ms)).set_alignment(CellAlignment::Right),
+            Cell::new(size).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    table
+}
+
+/// Byte count in the largest binary unit that keeps it at least 1, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    #[test]
+    fn plain_tables_have_no_ansi_escapes() {
+        let score = default_score(IndustryProfile::default());
+
+        let plain = breakdown_table(&score, false).to_string();
+        assert!(plain.contains("Performance:"));
+        assert!(plain.is_ascii());
+        assert!(breakdown_table(&score, true).to_string().contains('\x1b'));
+    }
+
+    #[test]
+    fn comparison_table_hides_changes_within_tolerance() {
+        let delta = |dimension, current: f64, change| DimensionDelta {
+            dimension,
+            baseline: 80.0,
+            current,
+            delta: current - 80.0,
+            change,
+        };
+        let table = comparison_table(
+            &[
+                delta("overall", 79.2, Change::Unchanged),
+                delta("performance", 76.0, Change::Regressed),
+            ],
+            false,
+        )
+        .to_string();
+        let line = |name| table.lines().find(|l| l.contains(name)).unwrap();
+        assert!(line("overall").contains("unchanged"), "{table}");
+        assert!(line("performance").contains("-4.0"), "{table}");
+    }
+
+    #[test]
+    fn sizes_use_binary_units() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn profiles_table_lists_weights_as_percentages() {
+        let profiles = crate::cli::Profile::summaries();
+        assert_eq!(profiles.len(), 5);
+        for p in &profiles {
+            assert!(
+                (p.performance + p.energy + p.cost - 1.0).abs() < 1e-9,
+                "{p:?}"
+            );
+        }
+
+        let table = profiles_table(&profiles, false).to_string();
+        let iot = table.lines().find(|l| l.contains("iot-embedded")).unwrap();
+        assert!(iot.contains("20%") && iot.contains("60%"), "{iot}");
+        assert!(iot.contains("IoT and embedded systems"), "{iot}");
+    }
+}
@@ -0,0 +1,214 @@
+//! Industry profile inference from Cargo metadata.
+//!
+//! The `keywords` and `categories` of the `[package]` section are matched
+//! against a small table of terms per profile. Categories are split at `::`,
+//! so `game-development::engines` matches like `game-development`. The
+//! profile with the most matching terms wins; ties go to the more specific
+//! profile (gaming, then IoT/embedded, financial, enterprise, web services).
+//! A `#![no_std]` crate root counts as the `no-std` term.
+
+use crate::complexity::is_no_std;
+use crabscore_core::IndustryProfile;
+use std::path::Path;
+
+/// Terms that point at each profile, most specific profiles first
+const RULES: [(IndustryProfile, &[&str]); 5] = [
+    (
+        IndustryProfile::Gaming,
+        &[
+            "game",
+            "games",
+            "gamedev",
+            "game-dev",
+            "game-development",
+            "game-engine",
+            "game-engines",
+            "bevy",
+        ],
+    ),
+    (
+        IndustryProfile::IotEmbedded,
+        &[
+            "embedded",
+            "no-std",
+            "no_std",
+            "iot",
+            "firmware",
+            "microcontroller",
+            "bare-metal",
+            "hardware-support",
+        ],
+    ),
+    (
+        IndustryProfile::Financial,
+        &[
+            "finance",
+            "financial",
+            "fintech",
+            "trading",
+            "banking",
+            "payments",
+            "accounting",
+            "cryptocurrencies",
+        ],
+    ),
+    (
+        IndustryProfile::Enterprise,
+        &["enterprise", "erp", "crm", "business", "workflow"],
+    ),
+    (
+        IndustryProfile::WebServices,
+        &[
+            "web",
+            "web-programming",
+            "http",
+            "http-server",
+            "api",
+            "rest",
+            "server",
+        ],
+    ),
+];
+
+/// Profile suggested by Cargo `keywords` and `categories`, or `None` when no
+/// term matches.
+pub fn infer<'a>(terms: impl IntoIterator<Item = &'a str>) -> Option<IndustryProfile> {
+    let mut matches = [0usize; RULES.len()];
+    for term in terms {
+        for part in term.split("::") {
+            let part = part.trim().to_ascii_lowercase();
+            for (count, (_, needles)) in matches.iter_mut().zip(&RULES) {
+                if needles.contains(&part.as_str()) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    // `max_by_key` keeps the last maximum, so walk the rules in reverse
+    matches
+        .iter()
+        .zip(&RULES)
+        .rev()
+        .filter(|(count, _)| **count > 0)
+        .max_by_key(|(count, _)| **count)
+        .map(|(_, (profile, _))| *profile)
+}
+
+/// Profile inferred from the `Cargo.toml` in `path`, if any keyword or
+/// category of its `[package]` matches.
+///
+/// Missing or unreadable manifests and inherited (`keywords.workspace =
+/// true`) metadata match nothing.
+pub fn infer_from_cargo_metadata(path: &Path) -> Option<IndustryProfile> {
+    infer(cargo_terms(path).iter().map(String::as_str))
+}
+
+/// Like [`infer_from_cargo_metadata`], with a `#![no_std]` crate root
+/// (see [`is_no_std`]) counting as one more IoT/embedded term.
+pub fn infer_from_project(path: &Path) -> Option<IndustryProfile> {
+    let no_std = is_no_std(path).then_some("no-std");
+    infer(cargo_terms(path).iter().map(String::as_str).chain(no_std))
+}
+
+/// `keywords` and `categories` of the `[package]` in `path`'s Cargo.toml
+fn cargo_terms(path: &Path) -> Vec<String> {
+    let Some(manifest) = std::fs::read_to_string(path.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+    else {
+        return Vec::new();
+    };
+    let Some(package) = manifest.get("package") else {
+        return Vec::new();
+    };
+    ["keywords", "categories"]
+        .iter()
+        .filter_map(|key| package.get(*key).and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|term| term.as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_representative_keyword_sets() {
+        let cases: [(&[&str], Option<IndustryProfile>); 7] = [
+            (
+                &["game-development", "graphics"],
+                Some(IndustryProfile::Gaming),
+            ),
+            (
+                &["embedded", "no-std", "hardware-support"],
+                Some(IndustryProfile::IotEmbedded),
+            ),
+            (&["finance", "trading"], Some(IndustryProfile::Financial)),
+            (
+                &["web-programming::http-server", "api"],
+                Some(IndustryProfile::WebServices),
+            ),
+            (&["Enterprise", "CRM"], Some(IndustryProfile::Enterprise)),
+            // Two web terms outweigh one gaming term
+            (
+                &["http", "server", "game"],
+                Some(IndustryProfile::WebServices),
+            ),
+            (&["parser", "command-line-utilities"], None),
+        ];
+        for (terms, expected) in cases {
+            assert_eq!(infer(terms.iter().copied()), expected, "{terms:?}");
+        }
+    }
+
+    #[test]
+    fn ties_go_to_the_more_specific_profile() {
+        assert_eq!(
+            infer(["embedded", "http"]),
+            Some(IndustryProfile::IotEmbedded)
+        );
+        assert_eq!(infer(["bevy", "web"]), Some(IndustryProfile::Gaming));
+    }
+
+    #[test]
+    fn reads_package_keywords_and_categories() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(infer_from_cargo_metadata(tmp.path()), None);
+
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            r#"[package]
+name = "blinky"
+version = "0.1.0"
+keywords = ["led", "firmware"]
+categories = ["embedded", "no-std::no-alloc"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            infer_from_cargo_metadata(tmp.path()),
+            Some(IndustryProfile::IotEmbedded)
+        );
+
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"member\"\nkeywords.workspace = true\n",
+        )
+        .unwrap();
+        assert_eq!(infer_from_cargo_metadata(tmp.path()), None);
+    }
+
+    #[test]
+    fn no_std_crates_suggest_iot() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/no_std");
+        assert_eq!(infer_from_cargo_metadata(&fixture), None);
+        assert_eq!(
+            infer_from_project(&fixture),
+            Some(IndustryProfile::IotEmbedded)
+        );
+
+        let std_crate = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/nested");
+        assert_eq!(infer_from_project(&std_crate), None);
+    }
+}
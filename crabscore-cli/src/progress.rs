@@ -0,0 +1,98 @@
+//! Progress spinner for long-running phases (build, analysis, benchmarks)
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::borrow::Cow;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Spinner showing the current phase of a command on stderr.
+///
+/// A hidden spinner (see [`Progress::hidden`]) is a no-op, so callers never
+/// need to check whether progress output is enabled.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    bar: ProgressBar,
+    /// Spinners drawn together with this one (see [`Progress::job`]);
+    /// `None` when hidden
+    multi: Option<MultiProgress>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::hidden()
+    }
+}
+
+impl Progress {
+    /// Spinner that is only drawn when `enabled` and stderr is a terminal
+    pub fn new(enabled: bool) -> Self {
+        if !enabled || !std::io::stderr().is_terminal() {
+            return Self::hidden();
+        }
+        let multi = MultiProgress::new();
+        let bar = multi.add(spinner("{spinner:.cyan} [{elapsed}] {msg}"));
+        Self {
+            bar,
+            multi: Some(multi),
+        }
+    }
+
+    /// Spinner that never draws anything
+    pub fn hidden() -> Self {
+        Self {
+            bar: ProgressBar::hidden(),
+            multi: None,
+        }
+    }
+
+    /// Spinner of its own for one of several concurrent jobs, drawn below
+    /// this one with `label` before its phase; hidden when this one is.
+    ///
+    /// Call [`Progress::finish`] on it once the job is done.
+    pub fn job(&self, label: impl Into<Cow<'static, str>>) -> Self {
+        let Some(multi) = &self.multi else {
+            return Self::hidden();
+        };
+        let bar = multi.add(spinner("{spinner:.cyan} [{elapsed}] {prefix}: {msg}"));
+        bar.set_prefix(label);
+        Self {
+            bar,
+            multi: Some(multi.clone()),
+        }
+    }
+
+    /// Whether the spinner is drawn
+    pub fn is_active(&self) -> bool {
+        !self.bar.is_hidden()
+    }
+
+    /// Show the current phase
+    pub fn phase(&self, msg: impl Into<Cow<'static, str>>) {
+        self.bar.set_message(msg);
+    }
+
+    /// Print a line to stderr without garbling the spinner
+    pub fn println(&self, msg: impl AsRef<str>) {
+        self.bar.suspend(|| eprintln!("{}", msg.as_ref()));
+    }
+
+    /// Run `f` (e.g. printing to stdout) with the spinner temporarily cleared
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+
+    /// Remove the spinner from the terminal
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Ticking spinner drawn with `template`
+fn spinner(template: &str) -> ProgressBar {
+    let bar = ProgressBar::new_spinner();
+    if let Ok(style) = ProgressStyle::with_template(template) {
+        bar.set_style(style);
+    }
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
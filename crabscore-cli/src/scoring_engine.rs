@@ -3,14 +3,17 @@
 use crate::complexity::ProjectComplexity;
 use crabscore_core::{
     metrics::{CostMetrics, EnergyMetrics, PerformanceMetrics, SafetyMetrics},
-    scoring::ScoringEngine,
-    CrabScore, IndustryProfile,
+    scoring::{
+        BinarySizeConfig, BonusExplanation, SafetyScoringConfig, ScoreExplanation, ScoringEngine,
+    },
+    CrabScore, Environment, IndustryProfile,
 };
 
 /// Scoring engine that adapts to project complexity and awards bonuses
 pub struct ComplexityAwareScoringEngine {
     base_engine: ScoringEngine,
     complexity: ProjectComplexity,
+    max_bonus: f64,
 }
 
 impl ComplexityAwareScoringEngine {
@@ -19,9 +22,36 @@ impl ComplexityAwareScoringEngine {
         Self {
             base_engine: ScoringEngine::new(profile),
             complexity,
+            max_bonus: DEFAULT_MAX_COMPLEXITY_BONUS,
         }
     }
 
+    /// Cap the complexity bonuses at `points` instead of
+    /// [`DEFAULT_MAX_COMPLEXITY_BONUS`]
+    pub fn with_max_bonus(mut self, points: f64) -> Self {
+        self.max_bonus = points;
+        self
+    }
+
+    /// Replace the default safety bonus points and falloff
+    pub fn with_safety_config(mut self, safety: SafetyScoringConfig) -> Self {
+        self.base_engine = self.base_engine.with_safety_config(safety);
+        self
+    }
+
+    /// Replace the default binary size budget and penalty
+    pub fn with_binary_size_config(mut self, binary_size: BinarySizeConfig) -> Self {
+        self.base_engine = self.base_engine.with_binary_size_config(binary_size);
+        self
+    }
+
+    /// Anchor the latency score to a p95 target of `sla_ms` (see
+    /// [`ScoringEngine::with_latency_sla`])
+    pub fn with_latency_sla(mut self, sla_ms: f64) -> Self {
+        self.base_engine = self.base_engine.with_latency_sla(sla_ms);
+        self
+    }
+
     /// Calculate score with complexity bonuses applied
     pub fn calculate_score(
         &self,
@@ -40,92 +70,293 @@ impl ComplexityAwareScoringEngine {
         score.overall += bonus;
 
         // Add metadata about the analysis
-        score.metadata.measurements.environment.os = std::env::consts::OS.to_string();
-        score.metadata.measurements.environment.rust_version = "1.88.0".to_string();
+        score.metadata.measurements.environment = Environment::detect();
 
         score
     }
 
-    /// Calculate bonus points based on project complexity and best practices
-    fn calculate_complexity_bonus(&self) -> f64 {
-        let mut bonus: f64 = 0.0;
+    /// Explain how [`Self::calculate_score`] arrives at its result, including
+    /// every complexity bonus rule and the bonus cap
+    pub fn explain(
+        &self,
+        performance: &PerformanceMetrics,
+        energy: &EnergyMetrics,
+        cost: &CostMetrics,
+        safety: &SafetyMetrics,
+    ) -> ScoreExplanation {
+        let mut explanation = self.base_engine.explain(performance, energy, cost, safety);
 
-        // Small project bonus (encourages Rust philosophy of starting small)
-        if self.complexity.total_lines < 100 {
-            bonus += 2.0;
-        } else if self.complexity.total_lines < 500 {
-            bonus += 1.0;
+        let uncapped: f64 = self.complexity_bonuses().iter().map(|b| b.points).sum();
+        let bonuses = self.capped_bonuses();
+        let bonus = self.calculate_complexity_bonus();
+        if bonus < uncapped {
+            explanation.notes.push(format!(
+                "Complexity bonuses capped at {:.1} points (earned {uncapped:.1})",
+                self.max_bonus
+            ));
         }
+        explanation.bonuses.extend(bonuses);
+        explanation.bonus_total += bonus;
+        explanation.overall += bonus;
+        explanation
+    }
 
-        // Documentation bonus (encourages good practices)
-        let doc_ratio = self.complexity.doc_coverage();
-        if doc_ratio > 0.2 {
-            bonus += 2.0; // Excellent documentation
-        } else if doc_ratio > 0.1 {
-            bonus += 1.0; // Good documentation
-        }
+    /// Calculate bonus points based on project complexity and best practices
+    fn calculate_complexity_bonus(&self) -> f64 {
+        self.capped_bonuses().iter().map(|b| b.points).sum()
+    }
 
-        // Test coverage bonus (encourages testing)
-        let test_ratio = self.complexity.test_coverage();
-        if test_ratio > 0.8 {
-            bonus += 3.0; // Excellent test coverage
-        } else if test_ratio > 0.5 {
-            bonus += 2.0; // Good test coverage
-        } else if test_ratio > 0.2 {
-            bonus += 1.0; // Basic test coverage
+    /// Complexity bonuses with the points actually awarded under the cap.
+    ///
+    /// Bonuses are awarded in order until the cap is reached; the one that
+    /// crosses it is cut short and any later ones award nothing.
+    fn capped_bonuses(&self) -> Vec<BonusExplanation> {
+        let mut remaining = self.max_bonus.max(0.0);
+        let mut bonuses = self.complexity_bonuses();
+        for bonus in &mut bonuses {
+            bonus.points = bonus.points.min(remaining);
+            remaining -= bonus.points;
         }
+        bonuses
+    }
 
-        // Minimal dependencies bonus (zero-cost abstractions principle)
-        if self.complexity.dependency_count == 0 {
-            bonus += 3.0; // Self-contained project
-        } else if self.complexity.dependency_count < 5 {
-            bonus += 2.0; // Minimal dependencies
-        } else if self.complexity.dependency_count < 10 {
-            bonus += 1.0; // Reasonable dependencies
-        }
+    /// Evaluate each complexity bonus category.
+    ///
+    /// Every category yields its highest earned tier, or its lowest tier
+    /// marked as not earned.
+    fn complexity_bonuses(&self) -> Vec<BonusExplanation> {
+        let c = &self.complexity;
+        let lines = c.total_lines as f64;
+        let doc_ratio = c.doc_coverage();
+        let test_ratio = c.test_coverage();
+        let deps = c.dependency_count as f64;
 
-        // Cap bonus at 10 points to prevent inflation
-        bonus.min(10.0)
+        vec![
+            // Small project bonus (encourages Rust philosophy of starting small)
+            tiered(
+                lines,
+                &[
+                    (
+                        "Small Project Bonus",
+                        "total_lines < 100",
+                        lines < 100.0,
+                        2.0,
+                    ),
+                    (
+                        "Compact Project Bonus",
+                        "total_lines < 500",
+                        lines < 500.0,
+                        1.0,
+                    ),
+                ],
+            ),
+            // Documentation bonus (encourages good practices)
+            tiered(
+                doc_ratio,
+                &[
+                    (
+                        "Excellent Documentation",
+                        "doc_coverage > 0.8",
+                        doc_ratio > 0.8,
+                        2.0,
+                    ),
+                    (
+                        "Good Documentation",
+                        "doc_coverage > 0.5",
+                        doc_ratio > 0.5,
+                        1.0,
+                    ),
+                ],
+            ),
+            // Test coverage bonus (encourages testing)
+            tiered(
+                test_ratio,
+                &[
+                    (
+                        "Excellent Tests",
+                        "test_coverage > 0.8",
+                        test_ratio > 0.8,
+                        3.0,
+                    ),
+                    (
+                        "Good Test Coverage",
+                        "test_coverage > 0.5",
+                        test_ratio > 0.5,
+                        2.0,
+                    ),
+                    (
+                        "Basic Test Coverage",
+                        "test_coverage > 0.2",
+                        test_ratio > 0.2,
+                        1.0,
+                    ),
+                ],
+            ),
+            // Minimal dependencies bonus (zero-cost abstractions principle)
+            tiered(
+                deps,
+                &[
+                    (
+                        "Zero Dependencies",
+                        "dependency_count == 0",
+                        deps == 0.0,
+                        3.0,
+                    ),
+                    (
+                        "Minimal Dependencies",
+                        "dependency_count < 5",
+                        deps < 5.0,
+                        2.0,
+                    ),
+                    (
+                        "Reasonable Dependencies",
+                        "dependency_count < 10",
+                        deps < 10.0,
+                        1.0,
+                    ),
+                ],
+            ),
+            // Fuzzing bonus (cargo-fuzz targets or a proptest/quickcheck suite)
+            BonusExplanation::new(
+                "Fuzz/Property Testing",
+                "has_fuzzing",
+                f64::from(u8::from(c.has_fuzzing)),
+                c.has_fuzzing,
+                1.0,
+            ),
+            // Embedded practices bonus (a `#![no_std]` crate runs without an OS)
+            BonusExplanation::new(
+                "Embedded Practices",
+                "no_std",
+                f64::from(u8::from(c.no_std)),
+                c.no_std,
+                1.0,
+            ),
+        ]
     }
 
-    /// Get breakdown of earned bonuses for display
+    /// Get breakdown of earned bonuses for display, with the points awarded
+    /// under the cap (bonuses clipped to nothing are left out)
     pub fn get_bonus_breakdown(&self) -> Vec<(String, f64)> {
-        let mut bonuses = Vec::new();
+        self.capped_bonuses()
+            .into_iter()
+            .filter(|b| b.earned && b.points > 0.0)
+            .map(|b| (b.name, b.points))
+            .collect()
+    }
+}
 
-        // Small project bonus
-        if self.complexity.total_lines < 100 {
-            bonuses.push(("Small Project Bonus".to_string(), 2.0));
-        } else if self.complexity.total_lines < 500 {
-            bonuses.push(("Compact Project Bonus".to_string(), 1.0));
-        }
+/// Default maximum number of complexity bonus points
+pub const DEFAULT_MAX_COMPLEXITY_BONUS: f64 = 10.0;
 
-        // Documentation bonus
-        let doc_ratio = self.complexity.doc_coverage();
-        if doc_ratio > 0.2 {
-            bonuses.push(("Excellent Documentation".to_string(), 2.0));
-        } else if doc_ratio > 0.1 {
-            bonuses.push(("Good Documentation".to_string(), 1.0));
-        }
+/// First earned tier of `(name, rule, earned, points)`, or the last tier as
+/// not earned
+fn tiered(value: f64, tiers: &[(&str, &str, bool, f64)]) -> BonusExplanation {
+    let (name, rule, earned, points) = tiers
+        .iter()
+        .find(|t| t.2)
+        .or(tiers.last())
+        .copied()
+        .expect("at least one tier");
+    BonusExplanation::new(name, rule, value, earned, points)
+}
 
-        // Test coverage bonus
-        let test_ratio = self.complexity.test_coverage();
-        if test_ratio > 0.8 {
-            bonuses.push(("Excellent Tests".to_string(), 3.0));
-        } else if test_ratio > 0.5 {
-            bonuses.push(("Good Test Coverage".to_string(), 2.0));
-        } else if test_ratio > 0.2 {
-            bonuses.push(("Basic Test Coverage".to_string(), 1.0));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Dependencies bonus
-        if self.complexity.dependency_count == 0 {
-            bonuses.push(("Zero Dependencies".to_string(), 3.0));
-        } else if self.complexity.dependency_count < 5 {
-            bonuses.push(("Minimal Dependencies".to_string(), 2.0));
-        } else if self.complexity.dependency_count < 10 {
-            bonuses.push(("Reasonable Dependencies".to_string(), 1.0));
+    /// Earns every top tier: 2 + 2 + 3 + 3 = 10 points
+    fn exemplary_project() -> ProjectComplexity {
+        ProjectComplexity {
+            total_lines: 50,
+            function_count: 4,
+            test_count: 4,
+            items: 4,
+            documented_items: 4,
+            ..Default::default()
         }
+    }
 
-        bonuses
+    #[test]
+    fn fuzzing_earns_a_bonus_point() {
+        let bonus = |has_fuzzing| {
+            let complexity = ProjectComplexity {
+                total_lines: 1000,
+                dependency_count: 20,
+                has_fuzzing,
+                ..Default::default()
+            };
+            let engine = ComplexityAwareScoringEngine::new(IndustryProfile::default(), complexity);
+            (
+                engine.calculate_complexity_bonus(),
+                engine.get_bonus_breakdown(),
+            )
+        };
+        assert_eq!(bonus(false), (0.0, vec![]));
+        assert_eq!(
+            bonus(true),
+            (1.0, vec![("Fuzz/Property Testing".to_string(), 1.0)])
+        );
+    }
+
+    #[test]
+    fn no_std_earns_an_embedded_bonus_point() {
+        let complexity = ProjectComplexity {
+            total_lines: 1000,
+            dependency_count: 20,
+            no_std: true,
+            ..Default::default()
+        };
+        let engine = ComplexityAwareScoringEngine::new(IndustryProfile::IotEmbedded, complexity);
+        assert_eq!(
+            engine.get_bonus_breakdown(),
+            vec![("Embedded Practices".to_string(), 1.0)]
+        );
+    }
+
+    #[test]
+    fn bonus_cap_clips_total_and_breakdown() {
+        let score = |engine: &ComplexityAwareScoringEngine| {
+            engine.calculate_score(
+                &PerformanceMetrics::default(),
+                &EnergyMetrics::default(),
+                &CostMetrics::default(),
+                &SafetyMetrics::default(),
+            )
+        };
+        let uncapped =
+            ComplexityAwareScoringEngine::new(IndustryProfile::default(), exemplary_project());
+        let capped =
+            ComplexityAwareScoringEngine::new(IndustryProfile::default(), exemplary_project())
+                .with_max_bonus(5.0);
+        assert_eq!(uncapped.calculate_complexity_bonus(), 10.0);
+        assert_eq!(capped.calculate_complexity_bonus(), 5.0);
+        let (full, clipped) = (score(&uncapped), score(&capped));
+        assert!((full.bonuses - clipped.bonuses - 5.0).abs() < 1e-9);
+        assert!((full.overall - clipped.overall - 5.0).abs() < 1e-9);
+
+        // Docs and size fit under the cap, tests are cut short, dependencies clipped away
+        assert_eq!(
+            capped.get_bonus_breakdown(),
+            [
+                ("Small Project Bonus".to_string(), 2.0),
+                ("Excellent Documentation".to_string(), 2.0),
+                ("Excellent Tests".to_string(), 1.0),
+            ]
+        );
+        let total: f64 = uncapped.get_bonus_breakdown().iter().map(|b| b.1).sum();
+        assert_eq!(total, 10.0);
+
+        let explanation = capped.explain(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &SafetyMetrics::default(),
+        );
+        assert!(explanation
+            .notes
+            .iter()
+            .any(|n| n == "Complexity bonuses capped at 5.0 points (earned 10.0)"));
+        assert!((explanation.overall - clipped.overall).abs() < 1e-9);
     }
 }
@@ -0,0 +1,230 @@
+//! Workspace (monorepo) member discovery and score aggregation
+
+use anyhow::{Context, Result};
+use crabscore_core::{Certification, CrabScore};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::cli::WeightBy;
+use crate::complexity::ProjectComplexity;
+
+/// Score of a single workspace member
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberScore {
+    /// Package name of the member
+    pub name: String,
+    /// Path to the member crate
+    pub path: PathBuf,
+    /// Weight of this member in the aggregate
+    pub weight: f64,
+    /// The member's own score
+    pub score: CrabScore,
+}
+
+/// Per-member scores plus the weighted roll-up
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceScore {
+    /// Individual member results
+    pub members: Vec<MemberScore>,
+    /// Weighted aggregate across all members
+    pub aggregate: CrabScore,
+}
+
+/// Discover the member crates of the workspace rooted at `root`.
+///
+/// Entries of `[workspace] members` may be plain paths or glob patterns such
+/// as `crates/*`; paths listed in `exclude` are skipped. A root manifest that
+/// also has a `[package]` section counts as a member itself.
+pub fn discover_members(root: &Path) -> Result<Vec<PathBuf>> {
+    let manifest = root.join("Cargo.toml");
+    let content = std::fs::read_to_string(&manifest)
+        .with_context(|| format!("failed to read {}", manifest.display()))?;
+    let toml: toml::Value = content.parse()?;
+    let workspace = toml
+        .get("workspace")
+        .and_then(|w| w.as_table())
+        .with_context(|| format!("{} has no [workspace] section", manifest.display()))?;
+
+    let excluded: Vec<PathBuf> = string_array(workspace, "exclude")
+        .into_iter()
+        .map(|p| root.join(p))
+        .collect();
+
+    let mut members = Vec::new();
+    if toml.get("package").is_some() {
+        members.push(root.to_path_buf());
+    }
+    for pattern in string_array(workspace, "members") {
+        let pattern = root.join(pattern);
+        for entry in glob::glob(&pattern.to_string_lossy())?.flatten() {
+            if entry.join("Cargo.toml").is_file()
+                && !excluded.contains(&entry)
+                && !members.contains(&entry)
+            {
+                members.push(entry);
+            }
+        }
+    }
+    Ok(members)
+}
+
+/// Whether `path` holds a virtual workspace manifest: a `Cargo.toml` with a
+/// `[workspace]` section but no `[package]` of its own
+pub fn is_virtual_workspace(path: &Path) -> bool {
+    std::fs::read_to_string(path.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+        .is_some_and(|manifest| {
+            manifest.get("workspace").is_some() && manifest.get("package").is_none()
+        })
+}
+
+fn string_array<'a>(table: &'a toml::value::Table, key: &str) -> Vec<&'a str> {
+    table
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Read `package.name` from a crate manifest, falling back to the directory name
+pub fn package_name(path: &Path) -> String {
+    std::fs::read_to_string(path.join("Cargo.toml"))
+        .ok()
+        .and_then(|c| c.parse::<toml::Value>().ok())
+        .and_then(|t| {
+            t.get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        })
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Weight of a member in the aggregate for the given strategy
+pub fn member_weight(complexity: &ProjectComplexity, weight_by: WeightBy) -> f64 {
+    match weight_by {
+        WeightBy::Loc => complexity.total_lines as f64,
+        WeightBy::Functions => complexity.function_count as f64,
+        WeightBy::Equal => 1.0,
+    }
+}
+
+/// Combine member scores into a weighted roll-up named `name`.
+///
+/// Falls back to equal weights when every member weight is zero. Returns
+/// `None` for an empty member list.
+pub fn aggregate(name: &str, members: &[MemberScore]) -> Option<CrabScore> {
+    let first = members.first()?;
+    let total: f64 = members.iter().map(|m| m.weight).sum();
+    let weight = |m: &MemberScore| {
+        if total > 0.0 {
+            m.weight / total
+        } else {
+            1.0 / members.len() as f64
+        }
+    };
+    let weighted =
+        |f: fn(&CrabScore) -> f64| -> f64 { members.iter().map(|m| f(&m.score) * weight(m)).sum() };
+
+    let mut score = first.score.clone();
+    score.overall = weighted(|s| s.overall);
+    score.performance = weighted(|s| s.performance);
+    score.energy = weighted(|s| s.energy);
+    score.cost = weighted(|s| s.cost);
+    score.bonuses = weighted(|s| s.bonuses);
+    score.certification = Certification::from_overall(score.overall);
+    score.timestamp = members
+        .iter()
+        .map(|m| m.score.timestamp)
+        .max()
+        .unwrap_or(first.score.timestamp);
+    score.metadata.project_name = name.to_string();
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    fn write_crate(dir: &Path, name: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn discovers_globbed_members_and_honors_exclude() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tool\"]\nexclude = [\"crates/skip\"]\n",
+        )
+        .unwrap();
+        write_crate(&root.join("crates/a"), "a");
+        write_crate(&root.join("crates/b"), "b");
+        write_crate(&root.join("crates/skip"), "skip");
+        write_crate(&root.join("tool"), "tool");
+        std::fs::create_dir_all(root.join("crates/not-a-crate")).unwrap();
+
+        let names: Vec<String> = discover_members(root)
+            .unwrap()
+            .iter()
+            .map(|p| package_name(p))
+            .collect();
+        assert_eq!(names, ["a", "b", "tool"]);
+    }
+
+    #[test]
+    fn only_workspaces_without_a_package_are_virtual() {
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/virtual_workspace");
+        assert!(is_virtual_workspace(&fixture));
+        assert_eq!(discover_members(&fixture).unwrap().len(), 2);
+        assert!(!is_virtual_workspace(&fixture.join("crates/app")));
+        assert!(!is_virtual_workspace(
+            &fixture.join("crates/app/src/main.rs")
+        ));
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("Cargo.toml"),
+            "[package]\nname = \"root\"\n\n[workspace]\nmembers = [\"tool\"]\n",
+        )
+        .unwrap();
+        assert!(!is_virtual_workspace(tmp.path()));
+    }
+
+    #[test]
+    fn aggregate_is_weighted() {
+        let base = default_score(IndustryProfile::default());
+        let member = |overall: f64, weight: f64| MemberScore {
+            name: String::new(),
+            path: PathBuf::new(),
+            weight,
+            score: CrabScore {
+                overall,
+                ..base.clone()
+            },
+        };
+
+        let agg = aggregate("ws", &[member(90.0, 300.0), member(50.0, 100.0)]).unwrap();
+        assert!((agg.overall - 80.0).abs() < 1e-9);
+        assert_eq!(agg.certification, Certification::Verified);
+        assert_eq!(agg.metadata.project_name, "ws");
+
+        let equal = aggregate("ws", &[member(90.0, 0.0), member(50.0, 0.0)]).unwrap();
+        assert!((equal.overall - 70.0).abs() < 1e-9);
+        assert!(aggregate("ws", &[]).is_none());
+    }
+}
@@ -0,0 +1,21 @@
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(handle(stream));
+    }
+}
+
+async fn handle(mut stream: tokio::net::TcpStream) -> std::io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&mut stream).read_line(&mut line).await?;
+    stream.write_all(respond(&line).await.as_bytes()).await
+}
+
+fn respond(line: &str) -> impl std::future::Future<Output = String> + '_ {
+    std::future::ready(line.to_uppercase())
+}
@@ -0,0 +1,3 @@
+pub fn a() -> u32 {
+    b::b() + 1
+}
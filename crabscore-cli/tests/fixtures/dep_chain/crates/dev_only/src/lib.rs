@@ -0,0 +1,3 @@
+pub fn dev_only() -> u32 {
+    1
+}
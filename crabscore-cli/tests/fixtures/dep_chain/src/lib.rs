@@ -0,0 +1,3 @@
+pub fn run() -> u32 {
+    a::a()
+}
@@ -0,0 +1,20 @@
+//! Fixture for item-based documentation coverage.
+
+#[doc = "Documented through an attribute."]
+pub fn attribute_documented() {}
+
+/// Documented through a doc comment.
+pub struct CommentDocumented;
+
+pub enum Undocumented {
+    A,
+}
+
+#[doc(hidden)]
+pub mod hidden {}
+
+pub fn prints_fake_docs() {
+    println!("/// not a doc comment");
+}
+
+fn private_helper() {}
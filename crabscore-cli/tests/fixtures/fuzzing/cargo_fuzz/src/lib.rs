@@ -0,0 +1,4 @@
+/// Parse a decimal number
+pub fn parse(input: &[u8]) -> Option<u32> {
+    std::str::from_utf8(input).ok()?.parse().ok()
+}
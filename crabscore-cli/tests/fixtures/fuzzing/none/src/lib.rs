@@ -0,0 +1,4 @@
+/// Parse a decimal number
+pub fn parse(input: &str) -> Option<u32> {
+    input.parse().ok()
+}
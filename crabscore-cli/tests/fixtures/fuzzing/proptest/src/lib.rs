@@ -0,0 +1,16 @@
+/// Parse a decimal number
+pub fn parse(input: &str) -> Option<u32> {
+    input.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn parses_what_it_prints(n: u32) {
+            prop_assert_eq!(super::parse(&n.to_string()), Some(n));
+        }
+    }
+}
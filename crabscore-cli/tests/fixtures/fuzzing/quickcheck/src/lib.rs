@@ -0,0 +1,17 @@
+/// Parse a decimal number
+pub fn parse(input: &str) -> Option<u32> {
+    input.parse().ok()
+}
+
+/// Property: printing and parsing a number round-trips
+pub fn round_trips(n: u32) -> bool {
+    parse(&n.to_string()) == Some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn round_trips() {
+        quickcheck::quickcheck(super::round_trips as fn(u32) -> bool);
+    }
+}
@@ -0,0 +1,19 @@
+// @generated by regmap-gen from device.yaml. Do not edit.
+
+pub const REGISTERS: [u32; 4] = [0x00, 0x04, 0x08, 0x0c];
+
+pub fn read_ctrl() -> u32 {
+    REGISTERS[0]
+}
+
+pub fn read_status() -> u32 {
+    REGISTERS[1]
+}
+
+pub fn read_data() -> u32 {
+    REGISTERS[2]
+}
+
+pub fn read_irq() -> u32 {
+    REGISTERS[3]
+}
@@ -0,0 +1,8 @@
+//! Hand-written wrapper around the generated bindings
+
+mod bindings;
+
+/// Number of registers on the device
+pub fn register_count() -> usize {
+    bindings::REGISTERS.len()
+}
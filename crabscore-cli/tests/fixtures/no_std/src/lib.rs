@@ -0,0 +1,6 @@
+#![no_std]
+
+/// Toggle an LED bit in a GPIO register value
+pub fn toggle(register: u32, pin: u8) -> u32 {
+    register ^ (1 << pin)
+}
@@ -0,0 +1,21 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+
+fn main() -> std::io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    for stream in listener.incoming() {
+        handle(stream?)?;
+    }
+    Ok(())
+}
+
+fn handle(stream: std::net::TcpStream) -> std::io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    respond(&stream, &line)
+}
+
+/// Echo the line back in upper case
+fn respond(mut stream: &std::net::TcpStream, line: &str) -> std::io::Result<()> {
+    stream.write_all(line.to_uppercase().as_bytes())
+}
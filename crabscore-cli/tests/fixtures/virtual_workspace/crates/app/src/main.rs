@@ -0,0 +1,3 @@
+fn main() {
+    println!("{}", core::add(1, 2));
+}
@@ -0,0 +1,4 @@
+/// Adds two numbers
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
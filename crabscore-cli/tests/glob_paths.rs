@@ -0,0 +1,59 @@
+//! Scoring every directory matched by a glob path
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn glob_scores_each_matching_directory() {
+    let tmp = tempfile::tempdir().unwrap();
+    let crates =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/virtual_workspace/crates");
+    // An empty PATH keeps cargo from building the fixtures
+    let empty = tmp.path().join("bin");
+    std::fs::create_dir(&empty).unwrap();
+    let score = |path: &Path, flags: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_crabscore"))
+            .args(["score", "--no-cache"])
+            .arg(path)
+            .args(flags)
+            .env("PATH", &empty)
+            .env("CRABSCORE_CACHE_DIR", tmp.path().join("cache"))
+            .output()
+            .unwrap()
+    };
+
+    let output = score(&crates.join("*"), &[]);
+    assert!(output.status.success(), "{output:?}");
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let paths: Vec<_> = lines.iter().map(|l| l["path"].as_str().unwrap()).collect();
+    assert_eq!(
+        paths,
+        [
+            crates.join("app").display().to_string(),
+            crates.join("core").display().to_string()
+        ]
+    );
+    assert!(lines.iter().all(|l| l["score"]["overall"].is_number()));
+
+    let output = score(&crates.join("missing-*"), &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("No directory matches"), "{stderr}");
+
+    // Thresholds apply to every path: all are reported, then the run fails
+    let output = score(&crates.join("*"), &["--fail-under", "101", "-j", "1"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("2 path(s) failed"), "{stderr}");
+
+    // Per-path output formats are rejected rather than ignored
+    let output = score(&crates.join("*"), &["--format", "human"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--format human cannot be used"), "{stderr}");
+}
@@ -0,0 +1,35 @@
+//! Scoring a Cargo project on a machine without cargo
+
+use std::process::Command;
+
+#[test]
+fn missing_cargo_falls_back_to_static_analysis() {
+    let tmp = tempfile::tempdir().unwrap();
+    let project = tmp.path().join("app");
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(
+        project.join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    std::fs::write(project.join("src/main.rs"), "fn main() {}\n").unwrap();
+    // An empty PATH: no program can be found
+    let empty = tmp.path().join("bin");
+    std::fs::create_dir(&empty).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_crabscore"))
+        .args(["score", "--format", "json", "--no-cache"])
+        .arg(&project)
+        .env("PATH", &empty)
+        .env("CRABSCORE_CACHE_DIR", tmp.path().join("cache"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let warnings = report["score"]["metadata"]["warnings"].as_array().unwrap();
+    let warnings: Vec<&str> = warnings.iter().filter_map(|w| w.as_str()).collect();
+    assert!(warnings.contains(&"cargo not found on PATH; falling back to static analysis"));
+    assert!(!warnings.iter().any(|w| w.contains("Failed to run cargo")));
+    assert_eq!(report["score"]["metadata"]["confidence"], "Estimated");
+}
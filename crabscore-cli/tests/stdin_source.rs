@@ -0,0 +1,34 @@
+//! Scoring a single source file piped on stdin
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn scores_source_piped_on_stdin() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_crabscore"))
+        .args(["score", "-", "--format", "json", "--no-cache"])
+        .current_dir(tmp.path())
+        .env("CRABSCORE_CACHE_DIR", tmp.path().join("cache"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"/// Greets the world\nfn main() {\n    println!(\"hello\");\n}\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let overall = report["score"]["overall"].as_f64().unwrap();
+    assert!(overall > 0.0 && overall <= 100.0, "{overall}");
+    assert_eq!(report["score"]["metadata"]["confidence"], "Estimated");
+    let warnings = report["score"]["metadata"]["warnings"].as_array().unwrap();
+    assert!(warnings
+        .iter()
+        .any(|w| w.as_str().unwrap().contains("static analysis")));
+}
@@ -0,0 +1,178 @@
+//! On-disk cache of per-file analysis results keyed by content hash
+//!
+//! Each namespace (e.g. `complexity`, `safety`) is stored as one JSON file
+//! under `~/.crabscore/cache/` (or `$CRABSCORE_CACHE_DIR`). The file name
+//! embeds the CrabScore version so a version bump starts from a clean cache;
+//! files of other versions are deleted when the new one is saved.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// [`content_hash`] of no bytes (the FNV-1a offset basis)
+pub const EMPTY_CONTENT_HASH: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Stable 64-bit FNV-1a hash of a file's contents
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    extend_content_hash(EMPTY_CONTENT_HASH, bytes)
+}
+
+/// Continue the [`content_hash`] `hash` of earlier bytes with `bytes`, so a
+/// file can be hashed chunk by chunk
+pub fn extend_content_hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Default cache directory, if a home directory can be determined
+pub fn default_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("CRABSCORE_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".crabscore").join("cache"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry<T> {
+    hash: u64,
+    value: T,
+}
+
+/// Per-file analysis cache for values of type `T`
+#[derive(Debug)]
+pub struct FileCache<T> {
+    namespace: String,
+    file: Option<PathBuf>,
+    entries: HashMap<String, CacheEntry<T>>,
+    dirty: bool,
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> FileCache<T> {
+    /// Open the cache for `namespace` in the default cache directory.
+    ///
+    /// A missing or unreadable cache file yields an empty cache.
+    pub fn open(namespace: &str) -> Self {
+        match default_cache_dir() {
+            Some(dir) => Self::open_in(&dir, namespace),
+            None => Self::disabled(),
+        }
+    }
+
+    /// Open the cache for `namespace` inside `dir`
+    pub fn open_in(dir: &Path, namespace: &str) -> Self {
+        let file = dir.join(format!("{namespace}-v{}.json", env!("CARGO_PKG_VERSION")));
+        let entries = std::fs::read_to_string(&file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            namespace: namespace.to_string(),
+            file: Some(file),
+            entries,
+            dirty: false,
+        }
+    }
+
+    /// A cache that never hits and never writes
+    pub fn disabled() -> Self {
+        Self {
+            namespace: String::new(),
+            file: None,
+            entries: HashMap::new(),
+            dirty: false,
+        }
+    }
+
+    /// Cached value for `path`, if its content hash still matches
+    pub fn get(&self, path: &Path, hash: u64) -> Option<T> {
+        self.file.as_ref()?;
+        self.entries
+            .get(&Self::key(path))
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| entry.value.clone())
+    }
+
+    /// Store the value computed for `path` with content hash `hash`
+    pub fn insert(&mut self, path: &Path, hash: u64, value: T) {
+        if self.file.is_some() {
+            self.entries
+                .insert(Self::key(path), CacheEntry { hash, value });
+            self.dirty = true;
+        }
+    }
+
+    /// Persist the cache if anything changed, deleting the files other
+    /// CrabScore versions left for the same namespace
+    pub fn save(&self) -> crate::Result<()> {
+        if let (Some(file), true) = (&self.file, self.dirty) {
+            if let Some(dir) = file.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(file, serde_json::to_string(&self.entries)?)?;
+            self.remove_other_versions(file);
+        }
+        Ok(())
+    }
+
+    /// Delete `<namespace>-v*.json` files next to `file` other than `file`
+    fn remove_other_versions(&self, file: &Path) {
+        let Some(entries) = file.parent().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+            return;
+        };
+        let prefix = format!("{}-v", self.namespace);
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if path != file && name.starts_with(&prefix) && name.ends_with(".json") {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    fn key(path: &Path) -> String {
+        path.canonicalize()
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_only_for_unchanged_content() {
+        let dir = std::env::temp_dir().join(format!("crabscore-cache-{}", std::process::id()));
+        let src = dir.join("lib.rs");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&src, "fn a() {}").unwrap();
+
+        let mut cache = FileCache::<u32>::open_in(&dir, "test");
+        let hash = content_hash(b"fn a() {}");
+        assert_eq!(cache.get(&src, hash), None);
+        cache.insert(&src, hash, 7);
+        cache.save().unwrap();
+
+        let reopened = FileCache::<u32>::open_in(&dir, "test");
+        assert_eq!(reopened.get(&src, hash), Some(7));
+        assert_eq!(reopened.get(&src, content_hash(b"fn b() {}")), None);
+
+        // Saving drops the files of other versions, and only of this namespace
+        let stale = dir.join("test-v0.0.1.json");
+        let other = dir.join("other-v0.0.1.json");
+        std::fs::write(&stale, "{}").unwrap();
+        std::fs::write(&other, "{}").unwrap();
+        let mut cache = FileCache::<u32>::open_in(&dir, "test");
+        cache.insert(&src, hash, 8);
+        cache.save().unwrap();
+        assert!(!stale.exists());
+        assert!(other.exists());
+        assert!(cache.file.as_ref().unwrap().exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 //! Error types for the CrabScore library
 
 use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Main error type for the CrabScore library
@@ -48,6 +50,28 @@ pub enum CrabScoreError {
     /// Invalid input data
     #[error("Invalid input: {0}")]
     ValidationError(String),
+
+    /// The executable to benchmark does not exist or is not on `PATH`
+    #[error("Benchmark binary not found: {}", .0.display())]
+    BinaryNotFound(PathBuf),
+
+    /// Every measured run (or request) of a benchmark failed
+    #[error("All {attempts} benchmark runs of {target} failed")]
+    AllIterationsFailed {
+        /// The binary or URL that was benchmarked
+        target: String,
+        /// How many runs (including retries) or requests were attempted
+        attempts: u32,
+    },
+
+    /// A benchmark step did not finish in time
+    #[error("Timed out after {after:?}: {operation}")]
+    Timeout {
+        /// What was being waited for
+        operation: String,
+        /// How long was waited
+        after: Duration,
+    },
 }
 
 impl CrabScoreError {
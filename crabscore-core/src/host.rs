@@ -0,0 +1,107 @@
+//! Best-effort detection of the machine CrabScore runs on.
+//!
+//! Every probe returns an empty or zero value when the platform does not
+//! expose the information, so detection never fails.
+
+use std::process::Command;
+
+/// CPU model name
+pub(crate) fn cpu_model() -> String {
+    #[cfg(target_os = "linux")]
+    {
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        parse_cpuinfo(&cpuinfo)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        command_output("sysctl", &["-n", "machdep.cpu.brand_string"])
+    }
+    #[cfg(windows)]
+    {
+        std::env::var("PROCESSOR_IDENTIFIER").unwrap_or_default()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        String::new()
+    }
+}
+
+/// Total physical memory in GB
+pub(crate) fn memory_gb() -> f32 {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        parse_meminfo_kb(&meminfo) as f32 / (1024.0 * 1024.0)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        command_output("sysctl", &["-n", "hw.memsize"])
+            .parse::<u64>()
+            .map_or(0.0, |bytes| bytes as f32 / (1024.0 * 1024.0 * 1024.0))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        0.0
+    }
+}
+
+/// Version of the `rustc` on the `PATH`, e.g. `1.88.0`
+pub(crate) fn rustc_version() -> String {
+    command_output("rustc", &["--version"])
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Trimmed stdout of a successful command, or an empty string
+fn command_output(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// The `model name` (x86) or `Hardware`/`Model` (ARM) line of `/proc/cpuinfo`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpuinfo(cpuinfo: &str) -> String {
+    ["model name", "Hardware", "Model"]
+        .iter()
+        .find_map(|key| {
+            cpuinfo.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                (name.trim() == *key).then(|| value.trim().to_string())
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// `MemTotal` of `/proc/meminfo` in kB
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo_kb(meminfo: &str) -> u64 {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_files() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\n\
+                       model name\t: Intel(R) Xeon(R) CPU @ 2.20GHz\nprocessor\t: 1\n";
+        assert_eq!(parse_cpuinfo(cpuinfo), "Intel(R) Xeon(R) CPU @ 2.20GHz");
+        assert_eq!(parse_cpuinfo("processor : 0\n"), "");
+        assert_eq!(
+            parse_meminfo_kb("MemTotal:       16303412 kB\nMemFree: 1 kB\n"),
+            16303412
+        );
+    }
+}
@@ -11,10 +11,15 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 pub mod analysis;
+pub mod cache;
 pub mod error;
+mod host;
 pub mod metrics;
 pub mod profiles;
+pub mod schema;
 pub mod scoring;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
 
 pub use profiles::ProfileWeights;
 
@@ -24,6 +29,7 @@ pub type Result<T> = std::result::Result<T, error::CrabScoreError>;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::default_score;
 
     #[test]
     fn test_industry_profile_weights() {
@@ -31,11 +37,151 @@ mod tests {
         let weights = profile.weights();
         assert!((weights.performance + weights.energy + weights.cost - 1.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_certification_ordering() {
+        assert!(Certification::Certified > Certification::Verified);
+        assert!(Certification::Verified > Certification::None);
+        assert!(Certification::Elite > Certification::Sustainable);
+        assert!(Certification::Sustainable > Certification::Certified);
+        assert!(Certification::Pioneer > Certification::Elite);
+        assert_eq!(Certification::None.rank(), 0);
+        assert_eq!(
+            Certification::Pioneer.max(Certification::Verified),
+            Certification::Pioneer
+        );
+    }
+
+    #[test]
+    fn hardware_fingerprint_ignores_memory_noise_only() {
+        let base = hardware_fingerprint("Xeon", 8, 15.6);
+        assert_eq!(base.len(), 16);
+        assert_eq!(base, hardware_fingerprint("Xeon", 8, 15.9));
+        assert_ne!(base, hardware_fingerprint("Xeon", 16, 15.6));
+        assert_ne!(base, hardware_fingerprint("EPYC", 8, 15.6));
+        assert_ne!(base, hardware_fingerprint("Xeon", 8, 32.0));
+
+        let env = Environment::detect();
+        assert_eq!(
+            env.hardware_fingerprint,
+            Environment::detect().hardware_fingerprint
+        );
+    }
+
+    #[test]
+    fn verified_score_reports_the_gap_to_certified() {
+        let mut score = default_score(IndustryProfile::WebServices);
+        score.performance = 80.0;
+        score.energy = 70.0;
+        score.cost = 85.0;
+        score.overall = 78.0;
+        score.certification = Certification::from_overall(score.overall);
+        assert_eq!(score.certification, Certification::Verified);
+
+        let next = score.next_tier(&CertificationBands::default()).unwrap();
+        assert_eq!(next.certification, "Certified");
+        assert_eq!(next.required_overall, 85.0);
+        assert!((next.gap - 7.0).abs() < 1e-9);
+        // Headroom: performance 0.4 × 20 = 8, energy 0.3 × 30 = 9, cost 0.3 × 15 = 4.5
+        assert_eq!(next.dimension, "energy");
+        assert!((next.headroom - 9.0).abs() < 1e-9);
+        assert!((next.required_increase - 7.0 / 0.3).abs() < 1e-9);
+        assert!(next.reachable);
+
+        // Too far from the bar for one dimension to close the gap
+        score.energy = 90.0;
+        score.overall = 60.0;
+        let next = score.next_tier(&CertificationBands::default()).unwrap();
+        assert_eq!(next.certification, "Verified");
+        assert_eq!(next.dimension, "performance");
+        assert!(!next.reachable);
+
+        score.overall = 85.0;
+        assert_eq!(
+            Certification::from_overall(score.overall),
+            Certification::Certified
+        );
+        assert_eq!(score.next_tier(&CertificationBands::default()), None);
+    }
+
+    #[test]
+    fn custom_certification_bands_replace_the_built_in_levels() {
+        let band = |name: &str, min_overall| CertificationBand {
+            name: name.into(),
+            min_overall,
+        };
+        let bands = CertificationBands(vec![
+            band("Bronze", 60.0),
+            band("Gold", 90.0),
+            band("Silver", 75.0),
+        ]);
+        assert!(bands.validate().is_ok());
+        let mut score = default_score(IndustryProfile::WebServices);
+        for (overall, tier) in [
+            (65.0, "Bronze"),
+            (75.0, "Silver"),
+            (89.9, "Silver"),
+            (95.0, "Gold"),
+            (40.0, "None"),
+        ] {
+            score.overall = overall;
+            score.certification = Certification::from_overall(overall);
+            assert_eq!(score.certification_with(&bands), tier, "{overall}");
+        }
+        score.overall = 88.0;
+        score.certification = Certification::from_overall(88.0);
+        assert_eq!(
+            score.certification_with(&CertificationBands::default()),
+            "Certified"
+        );
+        // The next tier comes from the same bands
+        assert_eq!(score.next_tier(&bands).unwrap().certification, "Gold");
+        score.overall = 95.0;
+        assert_eq!(score.next_tier(&bands), None);
+
+        let duplicate = CertificationBands(vec![band("Gold", 90.0), band("Gold", 80.0)]);
+        assert!(duplicate.validate().is_err());
+        assert!(CertificationBands(vec![band("", 50.0)]).validate().is_err());
+        assert!(CertificationBands(vec![band("Tin", f64::NAN)])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn grades_include_their_lower_boundary() {
+        let bands = GradeBands::default();
+        assert_eq!(bands.grade(100.0), 'A');
+        assert_eq!(bands.grade(90.0), 'A');
+        assert_eq!(bands.grade(89.999), 'B');
+        assert_eq!(bands.grade(80.0), 'B');
+        assert_eq!(bands.grade(70.0), 'C');
+        assert_eq!(bands.grade(60.0), 'D');
+        assert_eq!(bands.grade(59.999), 'F');
+        assert_eq!(bands.grade(0.0), 'F');
+
+        let strict = GradeBands {
+            a: 95.0,
+            ..Default::default()
+        };
+        assert_eq!(strict.grade(90.0), 'B');
+        assert!(strict.validate().is_ok());
+        assert!(GradeBands { b: 96.0, ..strict }.validate().is_err());
+        assert!(GradeBands {
+            d: f64::NAN,
+            ..strict
+        }
+        .validate()
+        .is_err());
+    }
 }
 
 /// Represents a complete CrabScore assessment
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CrabScore {
+    /// Version of the serialized format (see [`schema::SCHEMA_VERSION`])
+    #[serde(default = "schema::unversioned")]
+    pub schema_version: u32,
     /// Overall score (0-100)
     pub overall: f64,
     /// Performance component score (0-100)
@@ -54,8 +200,184 @@ pub struct CrabScore {
     pub metadata: ScoreMetadata,
 }
 
+impl CrabScore {
+    /// Letter grade (A–F) of the overall score with the default [`GradeBands`]
+    pub fn grade(&self) -> char {
+        self.grade_with(&GradeBands::default())
+    }
+
+    /// Letter grade (A–F) of the overall score with custom cutoffs
+    pub fn grade_with(&self, bands: &GradeBands) -> char {
+        bands.grade(self.overall)
+    }
+
+    /// Name of the certification earned: the built-in [`Certification`]
+    /// level, or the custom tier of the overall score when `bands` defines
+    /// any
+    pub fn certification_with(&self, bands: &CertificationBands) -> String {
+        if bands.0.is_empty() {
+            format!("{:?}", self.certification)
+        } else {
+            bands.tier(self.overall).unwrap_or("None").to_string()
+        }
+    }
+
+    /// What it takes to reach the next certification level, or `None` when
+    /// the overall score already earns the highest level it can.
+    ///
+    /// The levels are the custom tiers of `bands`, or the built-in
+    /// [`Certification`] levels when it defines none, as in
+    /// [`Self::certification_with`]. The suggested dimension is the one with
+    /// the most weight-adjusted headroom (profile weight × points below 100),
+    /// i.e. the one whose improvement can add the most to the overall score.
+    pub fn next_tier(&self, bands: &CertificationBands) -> Option<scoring::NextTier> {
+        let (certification, required_overall) = if bands.0.is_empty() {
+            Certification::SCORED
+                .into_iter()
+                .filter_map(|level| level.min_overall().map(|min| (level, min)))
+                .find(|(_, min)| self.overall < *min)
+                .map(|(level, min)| (format!("{level:?}"), min))?
+        } else {
+            bands
+                .0
+                .iter()
+                .filter(|band| self.overall < band.min_overall)
+                .min_by(|a, b| a.min_overall.total_cmp(&b.min_overall))
+                .map(|band| (band.name.clone(), band.min_overall))?
+        };
+        let gap = required_overall - self.overall;
+        let weights = self.metadata.profile.weights();
+        let (dimension, score, weight) = [
+            ("performance", self.performance, weights.performance),
+            ("energy", self.energy, weights.energy),
+            ("cost", self.cost, weights.cost),
+        ]
+        .into_iter()
+        .filter(|(_, _, weight)| *weight > 0.0)
+        .max_by(|a, b| (a.2 * (100.0 - a.1)).total_cmp(&(b.2 * (100.0 - b.1))))?;
+        let required_increase = gap / weight;
+        Some(scoring::NextTier {
+            certification,
+            required_overall,
+            gap,
+            dimension,
+            headroom: weight * (100.0 - score),
+            required_increase,
+            reachable: score + required_increase <= 100.0,
+        })
+    }
+}
+
+/// Minimum overall score for each letter grade; anything below `d` is an F
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GradeBands {
+    /// Minimum score for an A
+    pub a: f64,
+    /// Minimum score for a B
+    pub b: f64,
+    /// Minimum score for a C
+    pub c: f64,
+    /// Minimum score for a D
+    pub d: f64,
+}
+
+impl Default for GradeBands {
+    fn default() -> Self {
+        Self {
+            a: 90.0,
+            b: 80.0,
+            c: 70.0,
+            d: 60.0,
+        }
+    }
+}
+
+impl GradeBands {
+    /// Letter grade earned by an overall score
+    pub fn grade(&self, overall: f64) -> char {
+        if overall >= self.a {
+            'A'
+        } else if overall >= self.b {
+            'B'
+        } else if overall >= self.c {
+            'C'
+        } else if overall >= self.d {
+            'D'
+        } else {
+            'F'
+        }
+    }
+
+    /// Check that the cutoffs are finite and descend from A to D
+    pub fn validate(&self) -> Result<()> {
+        let cutoffs = [self.a, self.b, self.c, self.d];
+        if cutoffs.iter().any(|c| !c.is_finite()) || cutoffs.windows(2).any(|w| w[0] < w[1]) {
+            return Err(error::CrabScoreError::config(format!(
+                "grade cutoffs must descend from A to D, got {cutoffs:?}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A custom certification tier, e.g. `Gold` from an overall score of 90
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CertificationBand {
+    /// Tier name shown instead of the built-in certification level
+    pub name: String,
+    /// Minimum overall score for the tier
+    pub min_overall: f64,
+}
+
+/// Custom certification tiers replacing the built-in [`Certification`]
+/// levels in reports; with no bands the built-in levels are shown
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CertificationBands(pub Vec<CertificationBand>);
+
+impl CertificationBands {
+    /// Name of the highest tier whose minimum `overall` reaches, or `None`
+    /// below every tier
+    pub fn tier(&self, overall: f64) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|band| overall >= band.min_overall)
+            .max_by(|a, b| a.min_overall.total_cmp(&b.min_overall))
+            .map(|band| band.name.as_str())
+    }
+
+    /// Check that every tier has a name and a finite minimum, and that names
+    /// and minimums are unique
+    pub fn validate(&self) -> Result<()> {
+        for (i, band) in self.0.iter().enumerate() {
+            let problem = if band.name.trim().is_empty() {
+                "has no name"
+            } else if !band.min_overall.is_finite() {
+                "has no finite min_overall"
+            } else if self.0[..i].iter().any(|b| b.name == band.name) {
+                "is defined twice"
+            } else if self.0[..i]
+                .iter()
+                .any(|b| b.min_overall == band.min_overall)
+            {
+                "shares its min_overall with another tier"
+            } else {
+                continue;
+            };
+            return Err(error::CrabScoreError::config(format!(
+                "certification tier {:?} {problem}",
+                band.name
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Metadata about the score calculation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScoreMetadata {
     /// Name of the project
     pub project_name: String,
@@ -65,10 +387,77 @@ pub struct ScoreMetadata {
     pub profile: IndustryProfile,
     /// Summary of measurements taken
     pub measurements: MeasurementSummary,
+    /// How the underlying metrics were obtained
+    #[serde(default)]
+    pub confidence: Confidence,
+    /// Why the score may be imprecise (failed builds, default costs, ...)
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Git revision the analysis was restricted to changes since; `None`
+    /// for a score of the whole project
+    #[serde(default)]
+    pub diff_base: Option<String>,
+    /// p95 latency target (ms) the performance score was anchored to, if any
+    #[serde(default)]
+    pub latency_sla_ms: Option<f64>,
+    /// How far the static estimates were from the measured scores, when
+    /// checked (`crabscore score --check-estimates`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<EstimationCalibration>,
+}
+
+/// Scores of the static estimates next to the measured scores, per dimension
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EstimationCalibration {
+    /// Performance score
+    pub performance: EstimateError,
+    /// Energy score
+    pub energy: EstimateError,
+    /// Cost score
+    pub cost: EstimateError,
+}
+
+/// A dimension score from estimated metrics against the measured one
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EstimateError {
+    /// Score of the estimated metrics (0-100)
+    pub estimated: f64,
+    /// Score of the measured metrics (0-100)
+    pub measured: f64,
+    /// `estimated - measured`: positive when the estimate was too optimistic
+    pub error: f64,
+}
+
+impl EstimateError {
+    /// Compare an estimated score with the measured one
+    pub fn new(estimated: f64, measured: f64) -> Self {
+        Self {
+            estimated,
+            measured,
+            error: estimated - measured,
+        }
+    }
+}
+
+/// How trustworthy the metrics behind a score are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Confidence {
+    /// All metrics were measured on a running binary
+    Measured,
+    /// Metrics were estimated from static analysis only; also assumed for
+    /// scores that do not record how they were obtained
+    #[default]
+    Estimated,
+    /// Some metrics were measured, others fell back to estimates or defaults
+    Partial,
 }
 
 /// Summary of measurements used in scoring
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MeasurementSummary {
     /// Duration of the measurement period
     pub duration: std::time::Duration,
@@ -76,10 +465,27 @@ pub struct MeasurementSummary {
     pub iterations: u64,
     /// Environment where measurements were taken
     pub environment: Environment,
+    /// Per-target latency when one or more binaries were benchmarked
+    #[serde(default)]
+    pub targets: Vec<TargetMeasurement>,
+}
+
+/// Latency measured for a single benchmarked binary target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TargetMeasurement {
+    /// Target (binary) name
+    pub name: String,
+    /// Latency observed for this target
+    pub latency: metrics::LatencyMetrics,
+    /// Size of the target's executable in bytes, if known
+    #[serde(default)]
+    pub binary_size_bytes: Option<u64>,
 }
 
 /// Description of the execution environment
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Environment {
     /// Operating system
     pub os: String,
@@ -89,27 +495,103 @@ pub struct Environment {
     pub memory_gb: f32,
     /// Rust version used
     pub rust_version: String,
+    /// Hash of CPU model, core count and memory; measurements are only
+    /// comparable between reports with the same fingerprint
+    #[serde(default)]
+    pub hardware_fingerprint: String,
+    /// Speed relative to the reference machine when latencies were
+    /// normalized by calibration (`None` for raw measurements)
+    #[serde(default)]
+    pub speed_factor: Option<f64>,
+    /// CPU core the benchmarked processes were pinned to (`None` when the
+    /// scheduler was free to move them)
+    #[serde(default)]
+    pub pinned_cpu: Option<usize>,
+}
+
+impl Environment {
+    /// Describe the machine this process runs on.
+    ///
+    /// Values the platform does not expose are left empty (or zero); they
+    /// still feed into the fingerprint, so it stays stable on one machine.
+    pub fn detect() -> Self {
+        let cpu = host::cpu_model();
+        let memory_gb = host::memory_gb();
+        let cores = std::thread::available_parallelism().map_or(0, |n| n.get());
+        Self {
+            os: std::env::consts::OS.to_string(),
+            hardware_fingerprint: hardware_fingerprint(&cpu, cores, memory_gb),
+            cpu,
+            memory_gb,
+            rust_version: host::rustc_version(),
+            speed_factor: None,
+            pinned_cpu: None,
+        }
+    }
+}
+
+/// Stable hex digest identifying a hardware configuration. Memory is
+/// rounded to whole GB so small reporting differences do not matter.
+pub fn hardware_fingerprint(cpu: &str, cores: usize, memory_gb: f32) -> String {
+    let key = format!("{}|{cores}|{:.0}", cpu.trim(), memory_gb.round());
+    format!("{:016x}", cache::content_hash(key.as_bytes()))
 }
 
 /// Certification levels for CrabScore
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Levels are ordered from weakest to strongest, so `Certified > Verified`.
+/// `Sustainable` ranks just above `Certified`: it requires meeting the
+/// certification bar with exceptional energy efficiency, but not the overall
+/// performance of `Elite`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Certification {
     /// No certification
-    None,
+    None = 0,
     /// Basic verification passed
-    Verified,
+    Verified = 1,
     /// Meets all certification requirements
-    Certified,
+    Certified = 2,
     /// Top-tier performance and efficiency
-    Elite,
+    Elite = 4,
     /// Cutting-edge optimizations
-    Pioneer,
+    Pioneer = 5,
     /// Exceptional energy efficiency
-    Sustainable,
+    Sustainable = 3,
+}
+
+impl Certification {
+    /// Position of this level in the certification ordering (0 = none)
+    pub fn rank(self) -> u8 {
+        self as u8
+    }
+
+    /// Certification level earned by an overall score
+    pub fn from_overall(overall: f64) -> Self {
+        Self::SCORED
+            .into_iter()
+            .rev()
+            .find(|level| level.min_overall().is_some_and(|min| overall >= min))
+            .unwrap_or(Self::None)
+    }
+
+    /// Levels awarded by the overall score alone, lowest first
+    const SCORED: [Self; 2] = [Self::Verified, Self::Certified];
+
+    /// Overall score needed for this level, if the overall score alone
+    /// awards it
+    pub fn min_overall(self) -> Option<f64> {
+        match self {
+            Self::Verified => Some(70.0),
+            Self::Certified => Some(85.0),
+            _ => None,
+        }
+    }
 }
 
 /// Industry profile for certification
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum IndustryProfile {
     /// Web services and APIs (40% performance, 30% energy, 30% cost)
     WebServices,
@@ -124,6 +606,7 @@ pub enum IndustryProfile {
 }
 
 // Implement default weights for standard profiles
+#[allow(clippy::derivable_impls)]
 impl Default for IndustryProfile {
     fn default() -> Self {
         Self::WebServices
@@ -141,4 +624,13 @@ impl IndustryProfile {
             Self::Enterprise => ProfileWeights::new(0.3, 0.3, 0.4),
         }
     }
+
+    /// Largest binary (in bytes) the profile accepts before the energy score
+    /// is penalized, or `None` when binary size does not matter
+    pub fn binary_size_budget(&self) -> Option<u64> {
+        match self {
+            Self::IotEmbedded => Some(scoring::IOT_BINARY_SIZE_BUDGET),
+            _ => None,
+        }
+    }
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// Performance-related metrics
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PerformanceMetrics {
     /// Latency measurements
     pub latency: LatencyMetrics,
@@ -16,7 +17,8 @@ pub struct PerformanceMetrics {
 }
 
 /// Latency measurements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LatencyMetrics {
     /// 50th percentile latency in milliseconds
     pub p50_ms: f64,
@@ -24,6 +26,15 @@ pub struct LatencyMetrics {
     pub p95_ms: f64,
     /// 99th percentile latency in milliseconds
     pub p99_ms: f64,
+    /// 99.9th percentile latency in milliseconds
+    #[serde(default)]
+    pub p999_ms: f64,
+    /// Standard deviation of latency samples in milliseconds
+    #[serde(default)]
+    pub stddev_ms: f64,
+    /// Whether enough samples were taken for the tail percentiles to be meaningful
+    #[serde(default)]
+    pub reliable_percentiles: bool,
     /// Cold start latency in milliseconds
     pub cold_start_ms: f64,
     /// Time to first byte in milliseconds
@@ -32,6 +43,7 @@ pub struct LatencyMetrics {
 
 /// Throughput measurements
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ThroughputMetrics {
     /// Requests per second
     pub requests_per_second: f64,
@@ -45,6 +57,7 @@ pub struct ThroughputMetrics {
 
 /// Resource usage metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ResourceMetrics {
     /// CPU efficiency (operations per cycle)
     pub cpu_efficiency: f64,
@@ -54,10 +67,15 @@ pub struct ResourceMetrics {
     pub io_operations_per_sec: f64,
     /// Cache hit rate (0.0 to 1.0)
     pub cache_hit_rate: f64,
+    /// Size of the benchmarked executable in bytes (`None` when no binary
+    /// was measured)
+    #[serde(default)]
+    pub binary_size_bytes: Option<u64>,
 }
 
 /// Scalability metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SafetyMetrics {
     /// Total number of `unsafe` blocks
     pub unsafe_blocks: u32,
@@ -65,6 +83,13 @@ pub struct SafetyMetrics {
     pub clippy_warnings: u32,
     /// Average cyclomatic complexity per function
     pub avg_cyclomatic: f64,
+    /// `.unwrap()`/`.expect()` calls and `panic!`-like macros outside tests
+    #[serde(default)]
+    pub panic_sites: u32,
+    /// Compiler warnings emitted while building the project (`None` when
+    /// it was not built)
+    #[serde(default)]
+    pub build_warnings: Option<u32>,
 }
 
 impl Default for SafetyMetrics {
@@ -73,12 +98,15 @@ impl Default for SafetyMetrics {
             unsafe_blocks: 0,
             clippy_warnings: 0,
             avg_cyclomatic: 1.0,
+            panic_sites: 0,
+            build_warnings: None,
         }
     }
 }
 
 /// Scalability metrics for concurrent and parallel Rust workloads.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScalabilityMetrics {
     /// Linear scaling factor (1.0 = perfect linear scaling)
     pub linear_scaling_factor: f64,
@@ -92,6 +120,7 @@ pub struct ScalabilityMetrics {
 
 /// Energy-related metrics
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EnergyMetrics {
     /// Direct power consumption measurements
     pub direct_consumption: PowerConsumption,
@@ -105,6 +134,7 @@ pub struct EnergyMetrics {
 
 /// Power consumption metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PowerConsumption {
     /// Average power consumption in watts
     pub average_watts: f64,
@@ -118,6 +148,7 @@ pub struct PowerConsumption {
 
 /// Carbon efficiency metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CarbonEfficiency {
     /// Grams of CO2 per operation
     pub co2_per_operation: f64,
@@ -129,6 +160,7 @@ pub struct CarbonEfficiency {
 
 /// Hardware lifecycle metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HardwareLifecycle {
     /// Thermal efficiency (0.0 to 1.0)
     pub thermal_efficiency: f64,
@@ -140,6 +172,7 @@ pub struct HardwareLifecycle {
 
 /// Algorithm efficiency metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AlgorithmEfficiency {
     /// Time complexity (O(n), O(n log n), etc.)
     pub time_complexity: String,
@@ -153,6 +186,7 @@ pub struct AlgorithmEfficiency {
 
 /// Cost-related metrics
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CostMetrics {
     /// Infrastructure costs
     pub infrastructure: InfrastructureCosts,
@@ -166,6 +200,7 @@ pub struct CostMetrics {
 
 /// Infrastructure cost metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct InfrastructureCosts {
     /// Cloud compute costs per month in USD
     pub cloud_compute_usd: f64,
@@ -179,6 +214,7 @@ pub struct InfrastructureCosts {
 
 /// Operational cost metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct OperationalCosts {
     /// Mean time to resolve incidents in minutes
     pub mttr_minutes: f64,
@@ -192,6 +228,7 @@ pub struct OperationalCosts {
 
 /// Development cost metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DevelopmentCosts {
     /// Lines of code
     pub loc: u64,
@@ -205,6 +242,7 @@ pub struct DevelopmentCosts {
 
 /// Business impact metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BusinessImpact {
     /// Revenue impact per 100ms latency improvement in USD
     pub revenue_per_100ms_latency: f64,
@@ -226,6 +264,9 @@ impl Default for LatencyMetrics {
             p50_ms: 0.0,
             p95_ms: 0.0,
             p99_ms: 0.0,
+            p999_ms: 0.0,
+            stddev_ms: 0.0,
+            reliable_percentiles: false,
             cold_start_ms: 0.0,
             ttfb_ms: 0.0,
         }
@@ -250,6 +291,7 @@ impl Default for ResourceMetrics {
             memory_bandwidth_gb_s: 0.0,
             io_operations_per_sec: 0.0,
             cache_hit_rate: 0.0,
+            binary_size_bytes: None,
         }
     }
 }
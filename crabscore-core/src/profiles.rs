@@ -73,6 +73,7 @@ pub enum IndustryProfile {
     Custom(ProfileWeights),
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for IndustryProfile {
     fn default() -> Self {
         Self::WebServices
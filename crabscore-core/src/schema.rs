@@ -0,0 +1,120 @@
+//! Versioning and migration of serialized scores
+//!
+//! Every [`CrabScore`] records the [`SCHEMA_VERSION`] it was written with.
+//! Reports written before versioning existed are version 1. Fields added
+//! since carry `#[serde(default)]`, and [`migrate`] rewrites older JSON into
+//! the current shape, so stored baselines and history keep loading after an
+//! upgrade.
+
+use serde_json::{json, Value};
+
+use crate::{error::CrabScoreError, CrabScore, Result};
+
+/// Version of the serialized [`CrabScore`] format written by this release
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Version assumed for reports without a `schema_version` field
+pub(crate) fn unversioned() -> u32 {
+    1
+}
+
+/// Upgrade a serialized [`CrabScore`] to the current [`SCHEMA_VERSION`].
+///
+/// Fails for reports written by a newer CrabScore, whose fields this
+/// release would silently drop or misread.
+pub fn migrate(mut score: Value) -> Result<Value> {
+    let version = match score.get("schema_version") {
+        None => unversioned(),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| CrabScoreError::validation(format!("invalid schema_version {v}")))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(CrabScoreError::validation(format!(
+            "report schema version {version} is newer than the supported version {SCHEMA_VERSION}"
+        )));
+    }
+    if version < 2 {
+        v1_to_v2(&mut score);
+    }
+    score["schema_version"] = json!(SCHEMA_VERSION);
+    Ok(score)
+}
+
+/// Version 2 added the confidence, warnings and per-target measurements and
+/// the hardware fingerprint.
+fn v1_to_v2(score: &mut Value) {
+    let Some(metadata) = score.get_mut("metadata").and_then(Value::as_object_mut) else {
+        return;
+    };
+    // Version 1 did not record how the metrics were obtained
+    metadata.entry("confidence").or_insert(json!("Estimated"));
+    metadata.entry("warnings").or_insert(json!([]));
+    let Some(measurements) = metadata
+        .get_mut("measurements")
+        .and_then(Value::as_object_mut)
+    else {
+        return;
+    };
+    measurements.entry("targets").or_insert(json!([]));
+    if let Some(environment) = measurements
+        .get_mut("environment")
+        .and_then(Value::as_object_mut)
+    {
+        environment
+            .entry("hardware_fingerprint")
+            .or_insert(json!(""));
+    }
+}
+
+impl CrabScore {
+    /// Parse a serialized score of any supported schema version.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    /// Deserialize a score of any supported schema version.
+    pub fn from_value(value: Value) -> Result<Self> {
+        Ok(serde_json::from_value(migrate(value)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = include_str!("../tests/fixtures/score_v1.json");
+
+    #[test]
+    fn v1_report_loads_into_current_score() {
+        let score = CrabScore::from_json(V1).unwrap();
+        assert_eq!(score.schema_version, SCHEMA_VERSION);
+        assert_eq!(score.overall, 78.25);
+        assert_eq!(score.certification, crate::Certification::Verified);
+        assert_eq!(score.metadata.project_name, "legacy");
+        assert_eq!(score.metadata.confidence, crate::Confidence::Estimated);
+        assert!(score.metadata.warnings.is_empty());
+        assert!(score.metadata.measurements.targets.is_empty());
+        assert_eq!(
+            score.metadata.measurements.environment.hardware_fingerprint,
+            ""
+        );
+
+        // Plain deserialization relies on the field defaults alone
+        let direct: CrabScore = serde_json::from_str(V1).unwrap();
+        assert_eq!(direct.schema_version, 1);
+
+        // The current format round-trips unchanged
+        let json = serde_json::to_string(&score).unwrap();
+        assert_eq!(CrabScore::from_json(&json).unwrap().overall, 78.25);
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let mut future: Value = serde_json::from_str(V1).unwrap();
+        future["schema_version"] = json!(SCHEMA_VERSION + 1);
+        assert!(CrabScore::from_value(future).is_err());
+        assert!(migrate(json!({ "schema_version": "two" })).is_err());
+    }
+}
@@ -1,19 +1,722 @@
 //! Scoring logic for CrabScore
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     metrics::{CostMetrics, EnergyMetrics, PerformanceMetrics, SafetyMetrics},
     CrabScore, IndustryProfile,
 };
 
+/// Binary size budget of the IoT/embedded profile: 1 MiB
+pub const IOT_BINARY_SIZE_BUDGET: u64 = 1024 * 1024;
+
+/// Energy points an oversized binary loses
+///
+/// By default only the IoT/embedded profile penalizes size, against its
+/// [`IOT_BINARY_SIZE_BUDGET`]: 10 points per doubling beyond the budget, at
+/// most 50. Setting `budget_bytes` applies a budget under every profile;
+/// `enabled = false` turns the penalty off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BinarySizeConfig {
+    /// Whether oversized binaries lose energy points at all
+    pub enabled: bool,
+    /// Largest binary (in bytes) before the penalty applies; `None` uses the
+    /// profile's budget
+    pub budget_bytes: Option<u64>,
+    /// Energy points lost per doubling beyond the budget
+    pub penalty_per_doubling: f64,
+    /// Most energy points the penalty can take
+    pub max_penalty: f64,
+}
+
+impl Default for BinarySizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            budget_bytes: None,
+            penalty_per_doubling: 10.0,
+            max_penalty: 50.0,
+        }
+    }
+}
+
+impl BinarySizeConfig {
+    /// Check that the budget is positive and the penalties are finite,
+    /// non-negative numbers
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.budget_bytes == Some(0) {
+            return Err(crate::error::CrabScoreError::config(
+                "binary_size budget_bytes must be at least 1",
+            ));
+        }
+        for (name, value) in [
+            ("penalty_per_doubling", self.penalty_per_doubling),
+            ("max_penalty", self.max_penalty),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(crate::error::CrabScoreError::config(format!(
+                    "binary_size {name} must be a number of at least 0, got {value}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Size budget that applies under `profile`, or `None` when binary size
+    /// is not penalized
+    pub fn budget(&self, profile: IndustryProfile) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+        self.budget_bytes.or(profile.binary_size_budget())
+    }
+
+    /// Energy points lost by a `size`-byte binary against a `budget`:
+    /// `penalty_per_doubling` per doubling beyond it, at most `max_penalty`,
+    /// nothing within it
+    pub fn penalty(&self, size: u64, budget: u64) -> f64 {
+        if size <= budget || budget == 0 {
+            return 0.0;
+        }
+        ((size as f64 / budget as f64).log2() * self.penalty_per_doubling).min(self.max_penalty)
+    }
+}
+
+/// Minimum acceptable scores per dimension; `None` disables a check
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    /// Minimum overall score
+    pub overall: Option<f64>,
+    /// Minimum performance score
+    pub performance: Option<f64>,
+    /// Minimum energy score
+    pub energy: Option<f64>,
+    /// Minimum cost score
+    pub cost: Option<f64>,
+}
+
+/// Most points the safety bonuses can award together: the whole score scale
+pub const MAX_SAFETY_BONUS_POINTS: f64 = 100.0;
+
+/// Points awarded by the safety bonuses and how they fall off
+///
+/// By default each bonus is all-or-nothing: +4 for no unsafe code, +3 for no
+/// clippy warnings and +3 for an average cyclomatic complexity of at most 10.
+/// Setting a falloff turns a bonus into a graduated one that loses that many
+/// points per unsafe block (or clippy warning), down to zero. Setting
+/// `max_panic_sites` subtracts points for every panic site beyond it, at most
+/// the safety bonus earned.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyScoringConfig {
+    /// Points for code without `unsafe` blocks
+    pub no_unsafe_points: f64,
+    /// Points lost per `unsafe` block; `None` awards nothing for any unsafe code
+    pub unsafe_falloff: Option<f64>,
+    /// Points for code without clippy warnings
+    pub clippy_clean_points: f64,
+    /// Points lost per clippy warning; `None` awards nothing for any warning
+    pub clippy_falloff: Option<f64>,
+    /// Points for a low average cyclomatic complexity
+    pub low_complexity_points: f64,
+    /// Highest average cyclomatic complexity that still counts as low
+    pub max_avg_cyclomatic: f64,
+    /// Panic sites tolerated before the penalty applies; `None` disables it
+    pub max_panic_sites: Option<u32>,
+    /// Points subtracted per panic site above `max_panic_sites`
+    pub panic_site_penalty: f64,
+}
+
+impl Default for SafetyScoringConfig {
+    fn default() -> Self {
+        Self {
+            no_unsafe_points: 4.0,
+            unsafe_falloff: None,
+            clippy_clean_points: 3.0,
+            clippy_falloff: None,
+            low_complexity_points: 3.0,
+            max_avg_cyclomatic: 10.0,
+            max_panic_sites: None,
+            panic_site_penalty: 0.5,
+        }
+    }
+}
+
+impl SafetyScoringConfig {
+    /// Check that every setting is a finite, non-negative number and that the
+    /// bonuses award at most [`MAX_SAFETY_BONUS_POINTS`] together
+    pub fn validate(&self) -> crate::Result<()> {
+        let settings = [
+            ("no_unsafe_points", Some(self.no_unsafe_points)),
+            ("unsafe_falloff", self.unsafe_falloff),
+            ("clippy_clean_points", Some(self.clippy_clean_points)),
+            ("clippy_falloff", self.clippy_falloff),
+            ("low_complexity_points", Some(self.low_complexity_points)),
+            ("max_avg_cyclomatic", Some(self.max_avg_cyclomatic)),
+            ("panic_site_penalty", Some(self.panic_site_penalty)),
+        ];
+        for (name, value) in settings {
+            if let Some(value) = value.filter(|v| !(*v >= 0.0 && v.is_finite())) {
+                return Err(crate::error::CrabScoreError::config(format!(
+                    "safety {name} must be a number of at least 0, got {value}"
+                )));
+            }
+        }
+        let points = self.no_unsafe_points + self.clippy_clean_points + self.low_complexity_points;
+        if points > MAX_SAFETY_BONUS_POINTS {
+            return Err(crate::error::CrabScoreError::config(format!(
+                "safety bonuses award {points} points together, more than \
+                 {MAX_SAFETY_BONUS_POINTS}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Evaluate the safety bonuses for `s`
+    pub fn bonuses(&self, s: &SafetyMetrics) -> Vec<BonusExplanation> {
+        let mut bonuses = vec![
+            graduated(
+                "No Unsafe Code",
+                "unsafe_blocks",
+                s.unsafe_blocks,
+                self.no_unsafe_points,
+                self.unsafe_falloff,
+            ),
+            graduated(
+                "Clippy Clean",
+                "clippy_warnings",
+                s.clippy_warnings,
+                self.clippy_clean_points,
+                self.clippy_falloff,
+            ),
+            BonusExplanation::new(
+                "Low Complexity",
+                &format!("avg_cyclomatic <= {}", self.max_avg_cyclomatic),
+                s.avg_cyclomatic,
+                s.avg_cyclomatic <= self.max_avg_cyclomatic,
+                self.low_complexity_points,
+            ),
+        ];
+        if let Some(max) = self.max_panic_sites {
+            let earned: f64 = bonuses.iter().map(|b| b.points).sum();
+            let excess = s.panic_sites.saturating_sub(max);
+            let penalty = (self.panic_site_penalty * f64::from(excess)).min(earned);
+            bonuses.push(BonusExplanation::new(
+                "Panic Sites Penalty",
+                &format!("-{} * (panic_sites - {max})", self.panic_site_penalty),
+                f64::from(s.panic_sites),
+                excess > 0,
+                -penalty,
+            ));
+        }
+        bonuses
+    }
+}
+
+/// Bonus of `points` for a zero `count`, reduced by `falloff` per occurrence
+fn graduated(
+    name: &str,
+    metric: &str,
+    count: u32,
+    points: f64,
+    falloff: Option<f64>,
+) -> BonusExplanation {
+    let count_f = f64::from(count);
+    match falloff {
+        None => BonusExplanation::new(name, &format!("{metric} == 0"), count_f, count == 0, points),
+        Some(per) => {
+            let earned = (points - per * count_f).max(0.0);
+            BonusExplanation::new(
+                name,
+                &format!("{points} - {per} * {metric}"),
+                count_f,
+                earned > 0.0,
+                earned,
+            )
+        }
+    }
+}
+
+/// A dimension that fell below its configured threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdFailure {
+    /// Dimension name (`overall`, `performance`, `energy` or `cost`)
+    pub dimension: &'static str,
+    /// Score actually achieved
+    pub actual: f64,
+    /// Threshold that was not met
+    pub minimum: f64,
+}
+
+/// The gap between a score and the next certification level, from
+/// [`CrabScore::next_tier`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NextTier {
+    /// Name of the level to reach next: a built-in [`crate::Certification`]
+    /// level or a custom tier
+    pub certification: String,
+    /// Overall score that level needs
+    pub required_overall: f64,
+    /// Overall points still missing
+    pub gap: f64,
+    /// Dimension to improve (`performance`, `energy` or `cost`): the one with
+    /// the most weight-adjusted headroom
+    pub dimension: &'static str,
+    /// Overall points the dimension could add at most (weight × points below 100)
+    pub headroom: f64,
+    /// Points the dimension must gain to close the gap on its own
+    pub required_increase: f64,
+    /// Whether improving that dimension alone can close the gap
+    pub reachable: bool,
+}
+
+impl Thresholds {
+    /// Fill every unset threshold from `fallback`
+    pub fn or(self, fallback: Thresholds) -> Thresholds {
+        Thresholds {
+            overall: self.overall.or(fallback.overall),
+            performance: self.performance.or(fallback.performance),
+            energy: self.energy.or(fallback.energy),
+            cost: self.cost.or(fallback.cost),
+        }
+    }
+
+    /// Each dimension with its score and configured minimum, in report order
+    pub fn checks(&self, score: &CrabScore) -> [(&'static str, f64, Option<f64>); 4] {
+        [
+            ("performance", score.performance, self.performance),
+            ("energy", score.energy, self.energy),
+            ("cost", score.cost, self.cost),
+            ("overall", score.overall, self.overall),
+        ]
+    }
+
+    /// All dimensions of `score` that fall below their threshold
+    pub fn failures(&self, score: &CrabScore) -> Vec<ThresholdFailure> {
+        self.checks(score)
+            .into_iter()
+            .filter_map(|(dimension, actual, minimum)| {
+                minimum
+                    .filter(|min| actual < *min)
+                    .map(|minimum| ThresholdFailure {
+                        dimension,
+                        actual,
+                        minimum,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// A named value in a score explanation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Factor {
+    /// What the value represents
+    pub name: &'static str,
+    /// The value itself
+    pub value: f64,
+}
+
+impl Factor {
+    /// Create a named value
+    pub fn new(name: &'static str, value: f64) -> Self {
+        Self { name, value }
+    }
+}
+
+/// How a single dimension score was derived
+#[derive(Debug, Clone, Serialize)]
+pub struct DimensionExplanation {
+    /// Raw metric inputs
+    pub inputs: Vec<Factor>,
+    /// Intermediate sub-scores (0-100), averaged into `score`
+    pub sub_scores: Vec<Factor>,
+    /// Resulting dimension score (0-100)
+    pub score: f64,
+    /// Profile weight applied to the score
+    pub weight: f64,
+    /// Weighted contribution to the overall score
+    pub contribution: f64,
+}
+
+impl DimensionExplanation {
+    fn new(breakdown: Breakdown, score: f64, weight: f64) -> Self {
+        Self {
+            inputs: breakdown.inputs,
+            sub_scores: breakdown.sub_scores,
+            score,
+            weight,
+            contribution: score * weight,
+        }
+    }
+}
+
+/// Raw inputs and intermediate sub-scores behind one dimension score
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Breakdown {
+    /// Raw metric inputs
+    pub inputs: Vec<Factor>,
+    /// Intermediate sub-scores (0-100)
+    pub sub_scores: Vec<Factor>,
+}
+
+impl Breakdown {
+    /// Mean of the sub-scores, clamped to 0-100
+    pub fn mean_score(&self) -> f64 {
+        if self.sub_scores.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self.sub_scores.iter().map(|f| f.value).sum();
+        (sum / self.sub_scores.len() as f64).clamp(0.0, 100.0)
+    }
+}
+
+/// Formulas turning raw metrics into 0-100 dimension scores.
+///
+/// Implement this to experiment with alternative scoring models and plug it
+/// in with [`ScoringEngine::with_strategy`]. The `explain_*` methods feed
+/// `--explain` output and may be left at their empty defaults.
+pub trait ScoringStrategy: Send + Sync {
+    /// Performance score (0-100)
+    fn score_performance(&self, m: &PerformanceMetrics) -> f64;
+    /// Energy efficiency score (0-100)
+    fn score_energy(&self, m: &EnergyMetrics) -> f64;
+    /// Cost efficiency score (0-100)
+    fn score_cost(&self, m: &CostMetrics) -> f64;
+
+    /// Inputs and sub-scores behind [`Self::score_performance`]
+    fn explain_performance(&self, _m: &PerformanceMetrics) -> Breakdown {
+        Breakdown::default()
+    }
+    /// Inputs and sub-scores behind [`Self::score_energy`]
+    fn explain_energy(&self, _m: &EnergyMetrics) -> Breakdown {
+        Breakdown::default()
+    }
+    /// Inputs and sub-scores behind [`Self::score_cost`]
+    fn explain_cost(&self, _m: &CostMetrics) -> Breakdown {
+        Breakdown::default()
+    }
+}
+
+impl<S: ScoringStrategy + ?Sized> ScoringStrategy for &S {
+    fn score_performance(&self, m: &PerformanceMetrics) -> f64 {
+        (**self).score_performance(m)
+    }
+    fn score_energy(&self, m: &EnergyMetrics) -> f64 {
+        (**self).score_energy(m)
+    }
+    fn score_cost(&self, m: &CostMetrics) -> f64 {
+        (**self).score_cost(m)
+    }
+    fn explain_performance(&self, m: &PerformanceMetrics) -> Breakdown {
+        (**self).explain_performance(m)
+    }
+    fn explain_energy(&self, m: &EnergyMetrics) -> Breakdown {
+        (**self).explain_energy(m)
+    }
+    fn explain_cost(&self, m: &CostMetrics) -> Breakdown {
+        (**self).explain_cost(m)
+    }
+}
+
+/// The standard CrabScore heuristics: each dimension is the mean of a few
+/// saturating sub-scores.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStrategy;
+
+impl ScoringStrategy for DefaultStrategy {
+    fn score_performance(&self, m: &PerformanceMetrics) -> f64 {
+        self.explain_performance(m).mean_score()
+    }
+
+    fn score_energy(&self, m: &EnergyMetrics) -> f64 {
+        self.explain_energy(m).mean_score()
+    }
+
+    fn score_cost(&self, m: &CostMetrics) -> f64 {
+        self.explain_cost(m).mean_score()
+    }
+
+    fn explain_performance(&self, m: &PerformanceMetrics) -> Breakdown {
+        // Simple heuristic combining latency (lower better) & throughput (higher better)
+        let latency_ms = m.latency.p95_ms.max(1.0); // avoid div-by-zero
+        let latency_score = (1.0 / (1.0 + latency_ms / 100.0)) * 100.0;
+
+        let tps = m.throughput.requests_per_second;
+        let throughput_score = (tps / (tps + 1000.0)) * 100.0;
+
+        let resource_score = 100.0 * m.resource_usage.cpu_efficiency.min(1.0);
+
+        Breakdown {
+            inputs: vec![
+                Factor::new("p95_ms", m.latency.p95_ms),
+                Factor::new("requests_per_second", tps),
+                Factor::new("cpu_efficiency", m.resource_usage.cpu_efficiency),
+            ],
+            sub_scores: vec![
+                Factor::new("latency", latency_score),
+                Factor::new("throughput", throughput_score),
+                Factor::new("resource", resource_score),
+            ],
+        }
+    }
+
+    fn explain_energy(&self, m: &EnergyMetrics) -> Breakdown {
+        // Lower power and higher renewable percentage boost score
+        let watts = m.direct_consumption.average_watts.max(1.0);
+        let power_score = (1.0 / (1.0 + watts / 100.0)) * 100.0;
+        let renewable_score = m.carbon_efficiency.renewable_percentage * 100.0;
+        Breakdown {
+            inputs: vec![
+                Factor::new("average_watts", m.direct_consumption.average_watts),
+                Factor::new(
+                    "renewable_percentage",
+                    m.carbon_efficiency.renewable_percentage,
+                ),
+            ],
+            sub_scores: vec![
+                Factor::new("power", power_score),
+                Factor::new("renewable", renewable_score),
+            ],
+        }
+    }
+
+    fn explain_cost(&self, m: &CostMetrics) -> Breakdown {
+        let infra = m.infrastructure.cloud_compute_usd;
+        let infra_score = (1.0 / (1.0 + infra / 1000.0)) * 100.0;
+        let ops_score = (1.0 / (1.0 + m.operations.overhead_percentage)) * 100.0;
+        Breakdown {
+            inputs: vec![
+                Factor::new("cloud_compute_usd", infra),
+                Factor::new("overhead_percentage", m.operations.overhead_percentage),
+            ],
+            sub_scores: vec![
+                Factor::new("infrastructure", infra_score),
+                Factor::new("operations", ops_score),
+            ],
+        }
+    }
+}
+
+/// A bonus rule, whether it triggered, and the value it was checked against
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BonusExplanation {
+    /// Bonus name
+    pub name: String,
+    /// Triggering condition, e.g. `unsafe_blocks == 0`
+    pub rule: String,
+    /// Measured value compared against the rule
+    pub value: f64,
+    /// Whether the rule was satisfied
+    pub earned: bool,
+    /// Points awarded (0 when not earned)
+    pub points: f64,
+}
+
+impl BonusExplanation {
+    /// Bonus of `points` awarded when `earned`
+    pub fn new(name: &str, rule: &str, value: f64, earned: bool, points: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            rule: rule.to_string(),
+            value,
+            earned,
+            points: if earned { points } else { 0.0 },
+        }
+    }
+}
+
+/// Auditable trail of how a [`CrabScore`] was computed
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreExplanation {
+    /// Profile whose weights were applied
+    pub profile: IndustryProfile,
+    /// Performance dimension
+    pub performance: DimensionExplanation,
+    /// Energy dimension
+    pub energy: DimensionExplanation,
+    /// Cost dimension
+    pub cost: DimensionExplanation,
+    /// Every bonus rule that was evaluated
+    pub bonuses: Vec<BonusExplanation>,
+    /// Total bonus points added to the weighted sum
+    pub bonus_total: f64,
+    /// Final overall score
+    pub overall: f64,
+    /// Additional remarks (caps, adjustments)
+    pub notes: Vec<String>,
+}
+
+/// [`DefaultStrategy`] plus a latency consistency sub-score, for workloads
+/// where jitter matters as much as the median (e.g. trading systems).
+///
+/// Consistency is `100 / (1 + stddev / p50)`, so a spread equal to the
+/// median halves that sub-score. Metrics without a measured spread
+/// (`stddev_ms == 0`) score full consistency.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VarianceAwareStrategy;
+
+impl ScoringStrategy for VarianceAwareStrategy {
+    fn score_performance(&self, m: &PerformanceMetrics) -> f64 {
+        self.explain_performance(m).mean_score()
+    }
+
+    fn score_energy(&self, m: &EnergyMetrics) -> f64 {
+        DefaultStrategy.score_energy(m)
+    }
+
+    fn score_cost(&self, m: &CostMetrics) -> f64 {
+        DefaultStrategy.score_cost(m)
+    }
+
+    fn explain_performance(&self, m: &PerformanceMetrics) -> Breakdown {
+        let mut breakdown = DefaultStrategy.explain_performance(m);
+        let cv = m.latency.stddev_ms / m.latency.p50_ms.max(f64::EPSILON);
+        breakdown
+            .inputs
+            .push(Factor::new("stddev_ms", m.latency.stddev_ms));
+        breakdown
+            .sub_scores
+            .push(Factor::new("consistency", 100.0 / (1.0 + cv)));
+        breakdown
+    }
+
+    fn explain_energy(&self, m: &EnergyMetrics) -> Breakdown {
+        DefaultStrategy.explain_energy(m)
+    }
+
+    fn explain_cost(&self, m: &CostMetrics) -> Breakdown {
+        DefaultStrategy.explain_cost(m)
+    }
+}
+
+/// How steeply [`LatencySlaStrategy`] drops past the SLA: p95 at twice the
+/// target scores `100 / 2^3 = 12.5`
+pub const SLA_FALLOFF_EXPONENT: f64 = 3.0;
+
+/// Latency sub-score (0-100) of a p95 latency against an SLA target: 100 while
+/// the SLA is met, then `100 * (sla / p95)^`[`SLA_FALLOFF_EXPONENT`].
+pub fn sla_latency_score(p95_ms: f64, sla_ms: f64) -> f64 {
+    if p95_ms <= sla_ms {
+        100.0
+    } else {
+        100.0 * (sla_ms / p95_ms).powf(SLA_FALLOFF_EXPONENT)
+    }
+}
+
+/// A `base` strategy ([`DefaultStrategy`] unless given) with its latency
+/// sub-score anchored to an SLA target (see [`sla_latency_score`]) instead of
+/// the fixed `1 / (1 + ms / 100)` curve, so the same latency scores higher
+/// under a looser SLA.
+///
+/// Performance is rescored as the mean of the adjusted sub-scores; a base
+/// whose breakdown has no `latency` sub-score keeps its own performance score.
+#[derive(Debug, Clone, Copy)]
+pub struct LatencySlaStrategy<S = DefaultStrategy> {
+    /// p95 latency target in milliseconds
+    pub sla_ms: f64,
+    /// Strategy whose latency sub-score is replaced
+    pub base: S,
+}
+
+impl LatencySlaStrategy {
+    /// [`DefaultStrategy`] scored against a p95 target of `sla_ms`
+    pub fn new(sla_ms: f64) -> Self {
+        Self {
+            sla_ms,
+            base: DefaultStrategy,
+        }
+    }
+}
+
+impl<S: ScoringStrategy> ScoringStrategy for LatencySlaStrategy<S> {
+    fn score_performance(&self, m: &PerformanceMetrics) -> f64 {
+        let breakdown = self.base.explain_performance(m);
+        if breakdown.sub_scores.iter().any(|f| f.name == "latency") {
+            self.explain_performance(m).mean_score()
+        } else {
+            self.base.score_performance(m)
+        }
+    }
+
+    fn score_energy(&self, m: &EnergyMetrics) -> f64 {
+        self.base.score_energy(m)
+    }
+
+    fn score_cost(&self, m: &CostMetrics) -> f64 {
+        self.base.score_cost(m)
+    }
+
+    fn explain_performance(&self, m: &PerformanceMetrics) -> Breakdown {
+        let mut breakdown = self.base.explain_performance(m);
+        breakdown
+            .inputs
+            .push(Factor::new("latency_sla_ms", self.sla_ms));
+        for factor in &mut breakdown.sub_scores {
+            if factor.name == "latency" {
+                factor.value = sla_latency_score(m.latency.p95_ms, self.sla_ms);
+            }
+        }
+        breakdown
+    }
+
+    fn explain_energy(&self, m: &EnergyMetrics) -> Breakdown {
+        self.base.explain_energy(m)
+    }
+
+    fn explain_cost(&self, m: &CostMetrics) -> Breakdown {
+        self.base.explain_cost(m)
+    }
+}
+
 /// Engine for calculating CrabScores
 pub struct ScoringEngine {
     profile: IndustryProfile,
+    strategy: Box<dyn ScoringStrategy>,
+    safety: SafetyScoringConfig,
+    binary_size: BinarySizeConfig,
+    latency_sla_ms: Option<f64>,
 }
 
 impl ScoringEngine {
     /// Create a new scoring engine with the given industry profile
     pub fn new(profile: IndustryProfile) -> Self {
-        Self { profile }
+        Self {
+            profile,
+            strategy: Box::new(DefaultStrategy),
+            safety: SafetyScoringConfig::default(),
+            binary_size: BinarySizeConfig::default(),
+            latency_sla_ms: None,
+        }
+    }
+
+    /// Replace the [`DefaultStrategy`] dimension formulas; a latency SLA
+    /// still applies on top of `strategy`
+    pub fn with_strategy(mut self, strategy: Box<dyn ScoringStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Replace the default safety bonus points and falloff
+    pub fn with_safety_config(mut self, safety: SafetyScoringConfig) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    /// Replace the default binary size budget and penalty
+    pub fn with_binary_size_config(mut self, binary_size: BinarySizeConfig) -> Self {
+        self.binary_size = binary_size;
+        self
+    }
+
+    /// Score latency against a p95 target of `sla_ms` by wrapping the
+    /// strategy in a [`LatencySlaStrategy`] and record the target in the
+    /// score metadata
+    pub fn with_latency_sla(mut self, sla_ms: f64) -> Self {
+        self.latency_sla_ms = Some(sla_ms);
+        self
     }
 
     /// Calculate a CrabScore based on the provided metrics
@@ -24,25 +727,27 @@ impl ScoringEngine {
         cost: &CostMetrics,
         safety: &SafetyMetrics,
     ) -> CrabScore {
-        let weights = self.profile.weights();
+        self.calculate_score_at(performance, energy, cost, safety, chrono::Utc::now())
+    }
 
-        let perf_score = self.score_performance(performance);
-        let energy_score = self.score_energy(energy);
-        let cost_score = self.score_cost(cost);
-        let bonuses = self.score_safety(safety);
+    /// Like [`Self::calculate_score`], but stamped with `now` instead of the
+    /// current time, so the result is fully reproducible
+    pub fn calculate_score_at(
+        &self,
+        performance: &PerformanceMetrics,
+        energy: &EnergyMetrics,
+        cost: &CostMetrics,
+        safety: &SafetyMetrics,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> CrabScore {
+        let explanation = self.explain(performance, energy, cost, safety);
+        let perf_score = explanation.performance.score;
+        let energy_score = explanation.energy.score;
+        let cost_score = explanation.cost.score;
+        let bonuses = explanation.bonus_total;
+        let overall = explanation.overall;
 
-        let overall = (perf_score * weights.performance)
-            + (energy_score * weights.energy)
-            + (cost_score * weights.cost)
-            + bonuses;
-
-        let certification = if overall >= 85.0 {
-            crate::Certification::Certified
-        } else if overall >= 70.0 {
-            crate::Certification::Verified
-        } else {
-            crate::Certification::None
-        };
+        let certification = crate::Certification::from_overall(overall);
 
         let metadata = crate::ScoreMetadata {
             project_name: String::new(),
@@ -56,69 +761,486 @@ impl ScoringEngine {
                     cpu: String::new(),
                     memory_gb: 0.0,
                     rust_version: String::new(),
+                    hardware_fingerprint: String::new(),
+                    speed_factor: None,
+                    pinned_cpu: None,
                 },
+                targets: Vec::new(),
             },
+            confidence: crate::Confidence::default(),
+            warnings: Vec::new(),
+            diff_base: None,
+            latency_sla_ms: self.latency_sla_ms,
+            calibration: None,
         };
 
         CrabScore {
+            schema_version: crate::schema::SCHEMA_VERSION,
             overall,
             performance: perf_score,
             energy: energy_score,
             cost: cost_score,
             bonuses,
             certification,
-            timestamp: chrono::Utc::now(),
+            timestamp: now,
             metadata,
         }
     }
 
-    // ---------------------------------------------------------------------
-    // Internal helpers
-    // ---------------------------------------------------------------------
+    /// Explain, step by step, how [`Self::calculate_score`] scores these metrics
+    pub fn explain(
+        &self,
+        performance: &PerformanceMetrics,
+        energy: &EnergyMetrics,
+        cost: &CostMetrics,
+        safety: &SafetyMetrics,
+    ) -> ScoreExplanation {
+        let weights = self.profile.weights();
+        let base: &dyn ScoringStrategy = &*self.strategy;
+        let sla;
+        let strategy: &dyn ScoringStrategy = match self.latency_sla_ms {
+            Some(sla_ms) => {
+                sla = LatencySlaStrategy { sla_ms, base };
+                &sla
+            }
+            None => base,
+        };
+        let binary_size = performance.resource_usage.binary_size_bytes;
+        let performance = DimensionExplanation::new(
+            strategy.explain_performance(performance),
+            strategy.score_performance(performance).clamp(0.0, 100.0),
+            weights.performance,
+        );
+        let mut notes = Vec::new();
+        let mut energy_breakdown = strategy.explain_energy(energy);
+        let mut energy_score = strategy.score_energy(energy).clamp(0.0, 100.0);
+        if let Some((size, budget)) = binary_size.zip(self.binary_size.budget(self.profile)) {
+            let penalty = self.binary_size.penalty(size, budget).min(energy_score);
+            if penalty > 0.0 {
+                energy_breakdown
+                    .inputs
+                    .push(Factor::new("binary_size_bytes", size as f64));
+                energy_score -= penalty;
+                let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+                let source = match self.binary_size.budget_bytes {
+                    Some(_) => "configured".to_string(),
+                    None => format!("{:?} profile", self.profile),
+                };
+                notes.push(format!(
+                    "Energy score reduced by {penalty:.1} points: the {:.1} MiB binary \
+                     exceeds the {:.1} MiB budget of the {source}",
+                    mib(size),
+                    mib(budget),
+                ));
+            }
+        }
+        let energy = DimensionExplanation::new(energy_breakdown, energy_score, weights.energy);
+        let cost = DimensionExplanation::new(
+            strategy.explain_cost(cost),
+            strategy.score_cost(cost).clamp(0.0, 100.0),
+            weights.cost,
+        );
+        let bonuses = self.safety.bonuses(safety);
+        let bonus_total = bonuses.iter().map(|b| b.points).sum();
+        let overall =
+            performance.contribution + energy.contribution + cost.contribution + bonus_total;
 
-    fn clamp(v: f64) -> f64 {
-        v.clamp(0.0, 100.0)
+        ScoreExplanation {
+            profile: self.profile,
+            performance,
+            energy,
+            cost,
+            bonuses,
+            bonus_total,
+            overall,
+            notes,
+        }
     }
+}
 
-    fn score_performance(&self, m: &PerformanceMetrics) -> f64 {
-        // Simple heuristic combining latency (lower better) & throughput (higher better)
-        let latency_ms = m.latency.p95_ms.max(1.0); // avoid div-by-zero
-        let latency_score = (1.0 / (1.0 + latency_ms / 100.0)) * 100.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let tps = m.throughput.requests_per_second;
-        let throughput_score = (tps / (tps + 1000.0)) * 100.0;
+    struct Fixed;
 
-        let resource_score = 100.0 * m.resource_usage.cpu_efficiency.min(1.0);
+    impl ScoringStrategy for Fixed {
+        fn score_performance(&self, _: &PerformanceMetrics) -> f64 {
+            50.0
+        }
+        fn score_energy(&self, _: &EnergyMetrics) -> f64 {
+            60.0
+        }
+        fn score_cost(&self, _: &CostMetrics) -> f64 {
+            70.0
+        }
+    }
+
+    #[test]
+    fn custom_strategy_replaces_default_formulas() {
+        let safety = SafetyMetrics {
+            unsafe_blocks: 1,
+            clippy_warnings: 1,
+            avg_cyclomatic: 20.0,
+            panic_sites: 0,
+            build_warnings: None,
+        };
+        let engine =
+            ScoringEngine::new(IndustryProfile::WebServices).with_strategy(Box::new(Fixed));
+        let score = engine.calculate_score(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &safety,
+        );
+
+        assert_eq!(score.performance, 50.0);
+        assert_eq!(score.energy, 60.0);
+        assert_eq!(score.cost, 70.0);
+        assert_eq!(score.bonuses, 0.0);
+        assert!((score.overall - (50.0 * 0.4 + 60.0 * 0.3 + 70.0 * 0.3)).abs() < 1e-9);
 
-        Self::clamp((latency_score + throughput_score + resource_score) / 3.0)
+        let explanation = engine.explain(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &safety,
+        );
+        assert!(explanation.performance.sub_scores.is_empty());
     }
 
-    fn score_energy(&self, m: &EnergyMetrics) -> f64 {
-        // Lower power and higher renewable percentage boost score
-        let watts = m.direct_consumption.average_watts.max(1.0);
-        let power_score = (1.0 / (1.0 + watts / 100.0)) * 100.0;
-        let renewable_score = m.carbon_efficiency.renewable_percentage * 100.0;
-        Self::clamp((power_score + renewable_score) / 2.0)
+    #[test]
+    fn default_safety_bonuses_are_all_or_nothing() {
+        let clean = SafetyMetrics {
+            unsafe_blocks: 0,
+            clippy_warnings: 0,
+            avg_cyclomatic: 10.0,
+            panic_sites: 0,
+            build_warnings: None,
+        };
+        let points = |s: &SafetyMetrics| -> Vec<f64> {
+            SafetyScoringConfig::default()
+                .bonuses(s)
+                .iter()
+                .map(|b| b.points)
+                .collect()
+        };
+        assert_eq!(points(&clean), [4.0, 3.0, 3.0]);
+
+        let one_of_each = SafetyMetrics {
+            unsafe_blocks: 1,
+            clippy_warnings: 1,
+            avg_cyclomatic: 10.5,
+            panic_sites: 0,
+            build_warnings: None,
+        };
+        assert_eq!(points(&one_of_each), [0.0, 0.0, 0.0]);
     }
 
-    fn score_cost(&self, m: &CostMetrics) -> f64 {
-        let infra = m.infrastructure.cloud_compute_usd;
-        let infra_score = (1.0 / (1.0 + infra / 1000.0)) * 100.0;
-        let ops_score = (1.0 / (1.0 + m.operations.overhead_percentage)) * 100.0;
-        Self::clamp((infra_score + ops_score) / 2.0)
+    #[test]
+    fn graduated_safety_bonuses_fall_off_linearly() {
+        let config = SafetyScoringConfig {
+            no_unsafe_points: 6.0,
+            unsafe_falloff: Some(1.5),
+            clippy_falloff: Some(0.5),
+            ..Default::default()
+        };
+        let bonuses = |unsafe_blocks, clippy_warnings| {
+            let b = config.bonuses(&SafetyMetrics {
+                unsafe_blocks,
+                clippy_warnings,
+                avg_cyclomatic: 1.0,
+                panic_sites: 0,
+                build_warnings: None,
+            });
+            (b[0].points, b[1].points)
+        };
+        assert_eq!(bonuses(0, 0), (6.0, 3.0));
+        assert_eq!(bonuses(1, 2), (4.5, 2.0));
+        assert_eq!(bonuses(3, 5), (1.5, 0.5));
+        assert_eq!(bonuses(4, 6), (0.0, 0.0));
+        assert_eq!(bonuses(40, 60), (0.0, 0.0));
+
+        let safety = SafetyMetrics {
+            unsafe_blocks: 2,
+            clippy_warnings: 0,
+            avg_cyclomatic: 1.0,
+            panic_sites: 0,
+            build_warnings: None,
+        };
+        let engine = ScoringEngine::new(IndustryProfile::default()).with_safety_config(config);
+        let score = engine.calculate_score(
+            &PerformanceMetrics::default(),
+            &EnergyMetrics::default(),
+            &CostMetrics::default(),
+            &safety,
+        );
+        assert_eq!(score.bonuses, 3.0 + 3.0 + 3.0);
+    }
+
+    #[test]
+    fn safety_config_rejects_negative_or_excessive_points() {
+        assert!(SafetyScoringConfig::default().validate().is_ok());
+        let invalid = [
+            SafetyScoringConfig {
+                no_unsafe_points: -1.0,
+                ..Default::default()
+            },
+            SafetyScoringConfig {
+                clippy_falloff: Some(f64::NAN),
+                ..Default::default()
+            },
+            SafetyScoringConfig {
+                max_avg_cyclomatic: f64::INFINITY,
+                ..Default::default()
+            },
+            SafetyScoringConfig {
+                panic_site_penalty: -0.5,
+                ..Default::default()
+            },
+            SafetyScoringConfig {
+                low_complexity_points: 98.0,
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[test]
+    fn pinned_timestamp_makes_scores_reproducible() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let engine = ScoringEngine::new(IndustryProfile::IotEmbedded);
+        let score = || {
+            engine.calculate_score_at(
+                &PerformanceMetrics::default(),
+                &EnergyMetrics::default(),
+                &CostMetrics::default(),
+                &SafetyMetrics::default(),
+                now,
+            )
+        };
+
+        let first = serde_json::to_string(&score()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(first, serde_json::to_string(&score()).unwrap());
+        assert_eq!(score().timestamp, now);
+    }
+
+    #[test]
+    fn oversized_binaries_lose_energy_points_under_iot() {
+        let with_size = |bytes: u64| {
+            let mut perf = PerformanceMetrics::default();
+            perf.resource_usage.binary_size_bytes = Some(bytes);
+            perf
+        };
+        let small = with_size(512 * 1024);
+        let large = with_size(4 * IOT_BINARY_SIZE_BUDGET);
+        let score = |profile: IndustryProfile, perf: &PerformanceMetrics| {
+            ScoringEngine::new(profile).explain(
+                perf,
+                &EnergyMetrics::default(),
+                &CostMetrics::default(),
+                &SafetyMetrics::default(),
+            )
+        };
+
+        let (small_iot, large_iot) = (
+            score(IndustryProfile::IotEmbedded, &small),
+            score(IndustryProfile::IotEmbedded, &large),
+        );
+        assert!((small_iot.energy.score - large_iot.energy.score - 20.0).abs() < 1e-9);
+        assert!(large_iot.overall < small_iot.overall);
+        assert!(small_iot.notes.is_empty());
+        assert!(large_iot.notes[0].contains("4.0 MiB binary"));
+
+        let web = IndustryProfile::WebServices;
+        assert_eq!(score(web, &small).overall, score(web, &large).overall);
+
+        let config = BinarySizeConfig::default();
+        assert_eq!(config.penalty(100, 100), 0.0);
+        assert_eq!(config.penalty(u64::MAX, 1), config.max_penalty);
     }
 
-    fn score_safety(&self, s: &SafetyMetrics) -> f64 {
-        let mut bonus = 0.0;
-        if s.unsafe_blocks == 0 {
-            bonus += 4.0;
+    #[test]
+    fn binary_size_penalty_follows_its_config() {
+        let mut perf = PerformanceMetrics::default();
+        perf.resource_usage.binary_size_bytes = Some(4 * IOT_BINARY_SIZE_BUDGET);
+        let energy = |profile: IndustryProfile, config: BinarySizeConfig| {
+            ScoringEngine::new(profile)
+                .with_binary_size_config(config)
+                .explain(
+                    &perf,
+                    &EnergyMetrics::default(),
+                    &CostMetrics::default(),
+                    &SafetyMetrics::default(),
+                )
+                .energy
+                .score
+        };
+        let iot = IndustryProfile::IotEmbedded;
+        let unpenalized = energy(
+            iot,
+            BinarySizeConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        );
+        assert!((unpenalized - energy(iot, BinarySizeConfig::default()) - 20.0).abs() < 1e-9);
+
+        let steep = BinarySizeConfig {
+            penalty_per_doubling: 15.0,
+            max_penalty: 25.0,
+            ..Default::default()
+        };
+        assert!((unpenalized - energy(iot, steep) - 25.0).abs() < 1e-9);
+
+        let web = IndustryProfile::WebServices;
+        let budgeted = BinarySizeConfig {
+            budget_bytes: Some(2 * IOT_BINARY_SIZE_BUDGET),
+            ..Default::default()
+        };
+        assert!(
+            (energy(web, BinarySizeConfig::default()) - energy(web, budgeted) - 10.0).abs() < 1e-9
+        );
+
+        assert!(BinarySizeConfig {
+            budget_bytes: Some(0),
+            ..Default::default()
         }
-        if s.clippy_warnings == 0 {
-            bonus += 3.0;
+        .validate()
+        .is_err());
+        assert!(BinarySizeConfig {
+            max_penalty: f64::NAN,
+            ..Default::default()
         }
-        if s.avg_cyclomatic <= 10.0 {
-            bonus += 3.0;
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn variance_aware_strategy_penalizes_jitter() {
+        let mut steady = PerformanceMetrics::default();
+        steady.latency.p50_ms = 10.0;
+        steady.latency.p95_ms = 12.0;
+        let mut jittery = steady.clone();
+        jittery.latency.stddev_ms = 10.0;
+
+        let strategy = VarianceAwareStrategy;
+        assert!(strategy.score_performance(&jittery) < strategy.score_performance(&steady));
+        assert_eq!(
+            DefaultStrategy.score_performance(&jittery),
+            DefaultStrategy.score_performance(&steady)
+        );
+    }
+
+    #[test]
+    fn default_strategy_score_is_mean_of_sub_scores() {
+        let m = PerformanceMetrics::default();
+        let breakdown = DefaultStrategy.explain_performance(&m);
+        assert_eq!(breakdown.sub_scores.len(), 3);
+        assert_eq!(
+            DefaultStrategy.score_performance(&m),
+            breakdown.mean_score()
+        );
+    }
+
+    #[test]
+    fn latency_sla_anchors_the_latency_curve() {
+        let mut m = PerformanceMetrics::default();
+        m.latency.p95_ms = 40.0;
+        let safety = SafetyMetrics {
+            unsafe_blocks: 0,
+            clippy_warnings: 0,
+            avg_cyclomatic: 1.0,
+            panic_sites: 0,
+            build_warnings: None,
+        };
+        let score = |engine: &ScoringEngine| {
+            engine.calculate_score(
+                &m,
+                &EnergyMetrics::default(),
+                &CostMetrics::default(),
+                &safety,
+            )
+        };
+        let latency = |engine: &ScoringEngine| {
+            let explanation = engine.explain(
+                &m,
+                &EnergyMetrics::default(),
+                &CostMetrics::default(),
+                &safety,
+            );
+            explanation
+                .performance
+                .sub_scores
+                .iter()
+                .find(|f| f.name == "latency")
+                .unwrap()
+                .value
+        };
+
+        let loose = ScoringEngine::new(IndustryProfile::WebServices).with_latency_sla(50.0);
+        let tight = ScoringEngine::new(IndustryProfile::WebServices).with_latency_sla(20.0);
+        assert_eq!(latency(&loose), 100.0);
+        assert_eq!(latency(&tight), 12.5);
+
+        let (loose_score, tight_score) = (score(&loose), score(&tight));
+        assert!(loose_score.performance > tight_score.performance);
+        assert_eq!(loose_score.metadata.latency_sla_ms, Some(50.0));
+        assert_eq!(tight_score.metadata.latency_sla_ms, Some(20.0));
+
+        // Without a target the fixed curve applies and nothing is recorded
+        let plain = ScoringEngine::new(IndustryProfile::WebServices);
+        assert!((latency(&plain) - 100.0 / 1.4).abs() < 1e-9);
+        assert_eq!(score(&plain).metadata.latency_sla_ms, None);
+
+        // The SLA applies on top of a custom strategy in either order
+        let sub_scores = |engine: &ScoringEngine| {
+            engine
+                .explain(
+                    &m,
+                    &EnergyMetrics::default(),
+                    &CostMetrics::default(),
+                    &safety,
+                )
+                .performance
+                .sub_scores
+                .iter()
+                .map(|f| f.name)
+                .collect::<Vec<_>>()
+        };
+        let engines = [
+            ScoringEngine::new(IndustryProfile::WebServices)
+                .with_strategy(Box::new(VarianceAwareStrategy))
+                .with_latency_sla(20.0),
+            ScoringEngine::new(IndustryProfile::WebServices)
+                .with_latency_sla(20.0)
+                .with_strategy(Box::new(VarianceAwareStrategy)),
+        ];
+        for engine in &engines {
+            assert_eq!(latency(engine), 12.5);
+            assert!(sub_scores(engine).contains(&"consistency"));
         }
-        bonus // out of 10 max, added directly
+
+        // A strategy without a latency sub-score keeps its own performance score
+        let fixed = ScoringEngine::new(IndustryProfile::WebServices)
+            .with_strategy(Box::new(Fixed))
+            .with_latency_sla(20.0);
+        assert_eq!(
+            fixed
+                .explain(
+                    &m,
+                    &EnergyMetrics::default(),
+                    &CostMetrics::default(),
+                    &safety
+                )
+                .performance
+                .score,
+            50.0
+        );
     }
 }
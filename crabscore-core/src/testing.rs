@@ -0,0 +1,17 @@
+//! Fixtures shared by the tests of the CrabScore crates
+
+use crate::{
+    metrics::{CostMetrics, EnergyMetrics, PerformanceMetrics, SafetyMetrics},
+    scoring::ScoringEngine,
+    CrabScore, IndustryProfile,
+};
+
+/// Score of all-default metrics under `profile`
+pub fn default_score(profile: IndustryProfile) -> CrabScore {
+    ScoringEngine::new(profile).calculate_score(
+        &PerformanceMetrics::default(),
+        &EnergyMetrics::default(),
+        &CostMetrics::default(),
+        &SafetyMetrics::default(),
+    )
+}
@@ -1,41 +1,55 @@
 //! Cost provider trait and simple implementations.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use crabscore_core::metrics::{
     BusinessImpact, CostMetrics, DevelopmentCosts, InfrastructureCosts, OperationalCosts,
 };
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Cost file looked up in the project root when none is configured.
+pub const DEFAULT_COST_FILE: &str = "crabscore-cost.json";
+
 /// Provides `CostMetrics` for a project.
 #[async_trait]
 pub trait CostProvider {
     /// Collect cost metrics given a project root path.
-    async fn collect(&self, project_root: &str) -> Result<CostMetrics>;
+    async fn collect(&self, project_root: &Path) -> Result<CostMetrics>;
 }
 
 /// A static cost provider that returns user-specified numbers via a JSON file.
 /// Useful for on-prem or when cloud APIs are unavailable.
 pub struct StaticCostProvider {
-    file_path: String,
+    file_path: PathBuf,
     #[allow(dead_code)]
     refresh: Duration,
 }
 
 impl StaticCostProvider {
     /// Create new provider pointing to a JSON or YAML config.
-    pub fn new(file_path: impl Into<String>) -> Self {
+    ///
+    /// A relative path is resolved against the project root passed to
+    /// [`CostProvider::collect`].
+    pub fn new(file_path: impl Into<PathBuf>) -> Self {
         Self {
             file_path: file_path.into(),
             refresh: Duration::from_secs(300),
         }
     }
+
+    /// Path of the cost file for the project at `project_root`.
+    pub fn resolve(&self, project_root: &Path) -> PathBuf {
+        project_root.join(&self.file_path)
+    }
 }
 
 #[async_trait]
 impl CostProvider for StaticCostProvider {
-    async fn collect(&self, _project_root: &str) -> Result<CostMetrics> {
-        let content = std::fs::read_to_string(&self.file_path)?;
+    async fn collect(&self, project_root: &Path) -> Result<CostMetrics> {
+        let path = self.resolve(project_root);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read cost file {}", path.display()))?;
         let v: serde_json::Value = serde_json::from_str(&content)?;
         let infra = v.get("infrastructure").cloned().unwrap_or_default();
         let ops = v.get("operations").cloned().unwrap_or_default();
@@ -115,3 +129,31 @@ impl CostProvider for StaticCostProvider {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reads_cost_file_inside_project() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(
+            project.path().join(DEFAULT_COST_FILE),
+            r#"{"infrastructure": {"cloud_compute_usd": 42.5}, "operations": {"mttr_minutes": 7}}"#,
+        )
+        .unwrap();
+
+        let provider = StaticCostProvider::new(DEFAULT_COST_FILE);
+        let root = project.path();
+        assert_eq!(
+            provider.resolve(root),
+            project.path().join(DEFAULT_COST_FILE)
+        );
+        let cost = provider.collect(root).await.unwrap();
+        assert_eq!(cost.infrastructure.cloud_compute_usd, 42.5);
+        assert_eq!(cost.operations.mttr_minutes, 7.0);
+
+        let elsewhere = tempfile::tempdir().unwrap();
+        assert!(provider.collect(elsewhere.path()).await.is_err());
+    }
+}
@@ -1,18 +1,77 @@
 //! Cross-platform energy monitoring interface
 
 use anyhow::Result;
+use crabscore_core::error::CrabScoreError;
 use crabscore_core::metrics::{
     AlgorithmEfficiency, CarbonEfficiency, EnergyMetrics, HardwareLifecycle, PowerConsumption,
 };
+use std::future::Future;
+use std::time::{Duration, Instant};
 
 /// Collects `EnergyMetrics` for the current host.
 #[async_trait::async_trait]
 pub trait EnergyMonitor {
     /// Sample power/energy information and return a populated `EnergyMetrics`.
     async fn collect(&self) -> Result<EnergyMetrics>;
+
+    /// Measure energy while `workload` runs and return it with the workload's
+    /// output.
+    ///
+    /// `joules_per_operation` of the result covers the whole workload; divide
+    /// it by the number of operations performed with [`per_operation`]. The
+    /// default implementation brackets the workload with two
+    /// [`Self::collect`] snapshots and integrates their mean power over the
+    /// elapsed wall time, which suits platforms that only report
+    /// instantaneous power.
+    async fn sample_during<F>(&self, workload: F) -> Result<(EnergyMetrics, F::Output)>
+    where
+        Self: Sync + Sized,
+        F: Future + Send,
+        F::Output: Send,
+    {
+        let before = self.collect().await?;
+        let started = Instant::now();
+        let output = workload.await;
+        let elapsed = started.elapsed();
+        let after = self.collect().await?;
+        Ok((average_over(&before, after, elapsed), output))
+    }
+}
+
+/// Energy over an interval of length `elapsed`, from power snapshots taken
+/// at its start (`before`) and end (`after`).
+///
+/// Everything but the power figures is taken from `after`.
+pub fn average_over(
+    before: &EnergyMetrics,
+    mut after: EnergyMetrics,
+    elapsed: Duration,
+) -> EnergyMetrics {
+    let start = &before.direct_consumption;
+    let end = &mut after.direct_consumption;
+    let average_watts = (start.average_watts + end.average_watts) / 2.0;
+    end.peak_watts = start.peak_watts.max(end.peak_watts);
+    end.idle_watts = start.idle_watts.min(end.idle_watts);
+    end.average_watts = average_watts;
+    end.joules_per_operation = average_watts * elapsed.as_secs_f64();
+    after
+}
+
+/// Energy of one operation, from the energy of a window in which
+/// `operations` operations were measured.
+///
+/// `window.direct_consumption.joules_per_operation` holds the joules of the
+/// whole window, as returned by [`EnergyMonitor::sample_during`]. Without any
+/// counted operation the window total is kept.
+pub fn per_operation(mut window: EnergyMetrics, operations: u64) -> EnergyMetrics {
+    if operations > 0 {
+        window.direct_consumption.joules_per_operation /= operations as f64;
+    }
+    window
 }
 
 /// Fallback monitor that returns zeros everywhere (works on any OS).
+#[derive(Debug, Clone, Copy)]
 pub struct NullMonitor;
 
 #[async_trait::async_trait]
@@ -26,3 +85,305 @@ impl EnergyMonitor for NullMonitor {
         })
     }
 }
+
+/// Best energy monitor available on the current host.
+#[derive(Debug, Clone, Copy)]
+pub enum PlatformMonitor {
+    /// No power telemetry; reports zeros
+    Null(NullMonitor),
+    /// Windows power meter counters
+    #[cfg(target_os = "windows")]
+    Windows(crate::windows::WindowsMonitor),
+}
+
+impl PlatformMonitor {
+    /// Pick the platform's monitor, falling back to [`NullMonitor`] when it
+    /// reports no telemetry.
+    pub async fn detect() -> Self {
+        #[cfg(target_os = "windows")]
+        match crate::windows::WindowsMonitor::detect().await {
+            Ok(monitor) => return Self::Windows(monitor),
+            Err(e) => tracing::info!("Windows energy monitoring unavailable: {}", e),
+        }
+        Self::Null(NullMonitor)
+    }
+
+    /// Whether the monitor reads real power telemetry
+    pub fn has_telemetry(&self) -> bool {
+        !matches!(self, Self::Null(_))
+    }
+
+    /// Short description of the monitor, for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Null(_) => "none (no power telemetry, energy reported as zero)",
+            #[cfg(target_os = "windows")]
+            Self::Windows(_) => "Windows power meter (PDH)",
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EnergyMonitor for PlatformMonitor {
+    async fn collect(&self) -> Result<EnergyMetrics> {
+        match self {
+            Self::Null(monitor) => monitor.collect().await,
+            // A detected monitor that stops responding must not abort the
+            // workload bracketed by `sample_during`
+            #[cfg(target_os = "windows")]
+            Self::Windows(monitor) => match monitor.collect().await {
+                Ok(metrics) => Ok(metrics),
+                Err(e) => {
+                    tracing::warn!("Energy sample failed, reporting zeros: {}", e);
+                    NullMonitor.collect().await
+                }
+            },
+        }
+    }
+}
+
+/// Monitor that returns fixed, injected metrics.
+///
+/// Useful in tests and for supplying energy numbers measured by external
+/// tooling.
+#[derive(Debug, Clone)]
+pub struct StaticMonitor {
+    /// Metrics returned by every call to [`EnergyMonitor::collect`]
+    pub metrics: EnergyMetrics,
+}
+
+impl StaticMonitor {
+    /// Create a monitor that always reports `metrics`.
+    pub fn new(metrics: EnergyMetrics) -> Self {
+        Self { metrics }
+    }
+}
+
+#[async_trait::async_trait]
+impl EnergyMonitor for StaticMonitor {
+    async fn collect(&self) -> Result<EnergyMetrics> {
+        Ok(self.metrics.clone())
+    }
+}
+
+/// How a [`CompositeMonitor`] turns several readings into one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CombineStrategy {
+    /// Use the first monitor that returns a reading, in insertion order
+    #[default]
+    FirstSuccess,
+    /// Average the power figures of every monitor that returns a reading
+    Average,
+    /// Take the largest power figures of every monitor that returns a reading
+    Max,
+}
+
+/// Monitor combining the readings of several monitors, for hosts with
+/// overlapping telemetry (e.g. battery discharge and RAPL on a laptop).
+///
+/// [`CombineStrategy::Average`] and [`CombineStrategy::Max`] combine the
+/// `direct_consumption` figures field by field; all other sections come from
+/// the first successful reading. Monitors that fail are skipped, and
+/// [`EnergyMonitor::collect`] only fails when every monitor does.
+pub struct CompositeMonitor {
+    monitors: Vec<Box<dyn EnergyMonitor + Send + Sync>>,
+    strategy: CombineStrategy,
+}
+
+impl CompositeMonitor {
+    /// Create an empty composite that combines readings with `strategy`.
+    pub fn new(strategy: CombineStrategy) -> Self {
+        Self {
+            monitors: Vec::new(),
+            strategy,
+        }
+    }
+
+    /// Add a monitor; with [`CombineStrategy::FirstSuccess`], earlier
+    /// monitors take precedence.
+    pub fn with_monitor(mut self, monitor: impl EnergyMonitor + Send + Sync + 'static) -> Self {
+        self.monitors.push(Box::new(monitor));
+        self
+    }
+
+    /// Number of monitors in the composite
+    pub fn len(&self) -> usize {
+        self.monitors.len()
+    }
+
+    /// Whether the composite has no monitors
+    pub fn is_empty(&self) -> bool {
+        self.monitors.is_empty()
+    }
+}
+
+impl std::fmt::Debug for CompositeMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompositeMonitor")
+            .field("monitors", &self.monitors.len())
+            .field("strategy", &self.strategy)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl EnergyMonitor for CompositeMonitor {
+    async fn collect(&self) -> Result<EnergyMetrics> {
+        let mut readings = Vec::new();
+        let mut last_error = None;
+        for monitor in &self.monitors {
+            match monitor.collect().await {
+                Ok(metrics) if self.strategy == CombineStrategy::FirstSuccess => {
+                    return Ok(metrics)
+                }
+                Ok(metrics) => readings.push(metrics),
+                Err(e) => {
+                    tracing::debug!("Energy monitor failed, skipping: {}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let Some(first) = readings.first() else {
+            return Err(last_error
+                .unwrap_or_else(|| CrabScoreError::unsupported("no energy monitors").into()));
+        };
+        let mut combined = first.clone();
+        let power = |f: fn(&PowerConsumption) -> f64| {
+            let values = readings.iter().map(|m| f(&m.direct_consumption));
+            match self.strategy {
+                CombineStrategy::Max => values.fold(f64::NEG_INFINITY, f64::max),
+                _ => values.sum::<f64>() / readings.len() as f64,
+            }
+        };
+        combined.direct_consumption = PowerConsumption {
+            average_watts: power(|p| p.average_watts),
+            peak_watts: power(|p| p.peak_watts),
+            idle_watts: power(|p| p.idle_watts),
+            joules_per_operation: power(|p| p.joules_per_operation),
+        };
+        Ok(combined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{
+        metrics::{CostMetrics, PerformanceMetrics, SafetyMetrics},
+        scoring::ScoringEngine,
+        IndustryProfile,
+    };
+
+    #[tokio::test]
+    async fn sampling_integrates_power_over_the_workload() {
+        let mut metrics = NullMonitor.collect().await.unwrap();
+        metrics.direct_consumption.average_watts = 20.0;
+        let monitor = StaticMonitor::new(metrics);
+
+        let (energy, output) = monitor
+            .sample_during(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                42
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, 42);
+        assert_eq!(energy.direct_consumption.average_watts, 20.0);
+        // 20 W for at least 50 ms
+        let joules = energy.direct_consumption.joules_per_operation;
+        assert!(joules >= 1.0, "{joules}");
+    }
+
+    #[tokio::test]
+    async fn window_energy_is_divided_by_the_operations() {
+        let mut metrics = NullMonitor.collect().await.unwrap();
+        metrics.direct_consumption.average_watts = 20.0;
+        metrics.direct_consumption.joules_per_operation = 12.0;
+        let monitor = StaticMonitor::new(metrics);
+
+        let window = monitor.collect().await.unwrap();
+        let energy = per_operation(window.clone(), 8);
+        assert_eq!(energy.direct_consumption.joules_per_operation, 1.5);
+        assert_eq!(energy.direct_consumption.average_watts, 20.0);
+        assert_eq!(
+            per_operation(window, 0)
+                .direct_consumption
+                .joules_per_operation,
+            12.0
+        );
+
+        // 20 W for at least 50 ms, over 10 operations
+        let (window, operations) = monitor
+            .sample_during(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                10
+            })
+            .await
+            .unwrap();
+        let joules = per_operation(window, operations)
+            .direct_consumption
+            .joules_per_operation;
+        assert!(joules >= 0.1, "{joules}");
+    }
+
+    #[tokio::test]
+    async fn static_monitor_feeds_energy_scoring() {
+        let mut metrics = NullMonitor.collect().await.unwrap();
+        metrics.direct_consumption.average_watts = 35.0;
+        metrics.direct_consumption.joules_per_operation = 0.002;
+        let monitor = StaticMonitor::new(metrics);
+
+        let collected = monitor.collect().await.unwrap();
+        assert_eq!(collected.direct_consumption.average_watts, 35.0);
+        assert_eq!(collected.direct_consumption.joules_per_operation, 0.002);
+
+        let engine = ScoringEngine::new(IndustryProfile::IotEmbedded);
+        let score = |energy| {
+            engine
+                .calculate_score(
+                    &PerformanceMetrics::default(),
+                    energy,
+                    &CostMetrics::default(),
+                    &SafetyMetrics::default(),
+                )
+                .energy
+        };
+        let null = NullMonitor.collect().await.unwrap();
+        assert_ne!(score(&collected), score(&null));
+    }
+
+    #[tokio::test]
+    async fn composite_monitor_combines_readings() {
+        let monitor = |average_watts, peak_watts| {
+            let mut metrics = EnergyMetrics::default();
+            metrics.direct_consumption.average_watts = average_watts;
+            metrics.direct_consumption.peak_watts = peak_watts;
+            StaticMonitor::new(metrics)
+        };
+        let composite = |strategy| {
+            CompositeMonitor::new(strategy)
+                .with_monitor(monitor(10.0, 30.0))
+                .with_monitor(monitor(20.0, 25.0))
+        };
+
+        let averaged = composite(CombineStrategy::Average).collect().await.unwrap();
+        assert_eq!(averaged.direct_consumption.average_watts, 15.0);
+        assert_eq!(averaged.direct_consumption.peak_watts, 27.5);
+
+        let max = composite(CombineStrategy::Max).collect().await.unwrap();
+        assert_eq!(max.direct_consumption.average_watts, 20.0);
+        assert_eq!(max.direct_consumption.peak_watts, 30.0);
+
+        let first = composite(CombineStrategy::FirstSuccess)
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(first.direct_consumption.average_watts, 10.0);
+
+        assert!(CompositeMonitor::new(CombineStrategy::Average)
+            .collect()
+            .await
+            .is_err());
+    }
+}
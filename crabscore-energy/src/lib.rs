@@ -9,7 +9,12 @@
 #[cfg(target_os = "macos")]
 pub mod macos;
 
-// Platform-specific energy monitoring not yet implemented for Windows
+/// Windows energy monitoring via PDH power meter counters
+#[cfg(target_os = "windows")]
+pub mod windows;
 
 /// Cross-platform energy monitoring interface
 pub mod interface;
+
+/// Thermal throttling detection during benchmarks
+pub mod thermal;
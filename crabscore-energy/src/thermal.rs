@@ -0,0 +1,237 @@
+//! Thermal throttling detection from CPU frequency and temperature samples.
+//!
+//! On Linux the clock of the fastest core (`scaling_cur_freq` relative to
+//! `cpuinfo_max_freq` under `/sys/devices/system/cpu`) and the hottest
+//! thermal zone (`/sys/class/thermal/thermal_zone*/temp`) are sampled while a
+//! workload runs. A clock that sags below its peak while the CPU is hot, or
+//! that stays below the base clock (`base_frequency`) when no temperature is
+//! exposed, is throttling, and lowers [`thermal_efficiency`]. Elsewhere, and
+//! on machines without cpufreq (most VMs), no samples are taken and callers
+//! keep [`DEFAULT_THERMAL_EFFICIENCY`].
+
+use anyhow::Result;
+use crabscore_core::error::CrabScoreError;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Thermal efficiency assumed when it cannot be measured
+pub const DEFAULT_THERMAL_EFFICIENCY: f64 = 0.8;
+
+/// Temperature (°C) from which a falling clock is attributed to heat rather
+/// than to the frequency governor saving power
+pub const HOT_CELSIUS: f64 = 80.0;
+
+/// Consecutive samples below the base clock that count as throttling when
+/// the temperature is unknown; a shorter dip is the governor
+pub const SUSTAINED_SAMPLES: usize = 3;
+
+/// One reading of the CPU clock and temperature
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalSample {
+    /// Clock of the fastest core as a fraction of its maximum frequency
+    pub frequency_ratio: f64,
+    /// Base (non-boost) clock of that core as a fraction of its maximum
+    /// frequency, if the driver exposes it
+    pub base_ratio: Option<f64>,
+    /// Temperature of the hottest thermal zone in °C, if any is exposed
+    pub celsius: Option<f64>,
+}
+
+/// Read the current CPU clock and temperature on a blocking thread.
+///
+/// Fails with [`CrabScoreError::UnsupportedError`] when the platform exposes
+/// no CPU frequency.
+pub async fn sample() -> Result<ThermalSample> {
+    if !cfg!(target_os = "linux") {
+        return Err(CrabScoreError::unsupported("thermal sampling requires Linux").into());
+    }
+    tokio::task::spawn_blocking(|| sample_sysfs(Path::new("/sys")))
+        .await?
+        .ok_or_else(|| CrabScoreError::unsupported("no cpufreq data in /sys").into())
+}
+
+/// Read a sample from a sysfs tree mounted at `sys`
+fn sample_sysfs(sys: &Path) -> Option<ThermalSample> {
+    let read =
+        |path: &Path| -> Option<f64> { std::fs::read_to_string(path).ok()?.trim().parse().ok() };
+
+    let (frequency_ratio, base_ratio) = std::fs::read_dir(sys.join("devices/system/cpu"))
+        .ok()?
+        .filter_map(|entry| {
+            let cpufreq = entry.ok()?.path().join("cpufreq");
+            let current = read(&cpufreq.join("scaling_cur_freq"))?;
+            let max = read(&cpufreq.join("cpuinfo_max_freq")).filter(|max| *max > 0.0)?;
+            let base = read(&cpufreq.join("base_frequency"));
+            Some((current / max, base.map(|base| base / max)))
+        })
+        .reduce(|a, b| if b.0 > a.0 { b } else { a })?;
+    let celsius = std::fs::read_dir(sys.join("class/thermal"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| read(&entry.ok()?.path().join("temp")))
+        .map(|millidegrees| millidegrees / 1000.0)
+        .reduce(f64::max);
+    Some(ThermalSample {
+        frequency_ratio,
+        base_ratio,
+        celsius,
+    })
+}
+
+/// Run `workload`, sampling the CPU every `interval` while it runs.
+///
+/// Returns no samples where [`sample`] is unsupported.
+pub async fn sample_during<F: Future>(
+    workload: F,
+    interval: Duration,
+) -> (Vec<ThermalSample>, F::Output) {
+    let samples = Mutex::new(Vec::new());
+    // Runs alongside the workload and stops at the first unsupported read
+    let mut sampler = Box::pin(async {
+        while let Ok(s) = sample().await {
+            samples.lock().unwrap().push(s);
+            tokio::time::sleep(interval).await;
+        }
+    });
+    tokio::pin!(workload);
+    let output = tokio::select! {
+        output = &mut workload => output,
+        () = &mut sampler => workload.await,
+    };
+    drop(sampler);
+    (samples.into_inner().unwrap(), output)
+}
+
+/// Thermal efficiency (0.0 to 1.0) of the samples taken under load, or
+/// `None` with fewer than two samples.
+///
+/// The efficiency is the mean clock relative to the peak clock of the run,
+/// so 1.0 means the CPU sustained its speed. A clock drop only counts when
+/// the CPU reached [`HOT_CELSIUS`] or, with its temperature unknown, when the
+/// clock stayed below the base clock for [`SUSTAINED_SAMPLES`] samples in a
+/// row; otherwise it is the governor or the end of a boost, not throttling.
+pub fn thermal_efficiency(samples: &[ThermalSample]) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let peak = samples
+        .iter()
+        .map(|s| s.frequency_ratio)
+        .fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return None;
+    }
+    let mean = samples.iter().map(|s| s.frequency_ratio).sum::<f64>() / samples.len() as f64;
+    let hottest = samples.iter().filter_map(|s| s.celsius).reduce(f64::max);
+    let throttled = match hottest {
+        Some(celsius) => celsius >= HOT_CELSIUS,
+        None => samples.windows(SUSTAINED_SAMPLES).any(|run| {
+            run.iter()
+                .all(|s| s.base_ratio.is_some_and(|base| s.frequency_ratio < base))
+        }),
+    };
+    if !throttled {
+        return Some(1.0);
+    }
+    Some((mean / peak).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(points: &[(f64, Option<f64>)]) -> Vec<ThermalSample> {
+        points
+            .iter()
+            .map(|&(frequency_ratio, celsius)| ThermalSample {
+                frequency_ratio,
+                base_ratio: Some(0.5),
+                celsius,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn downclocking_while_hot_lowers_efficiency() {
+        // Boosts to full speed, then sags to 60% as it heats up
+        let throttled = samples(&[
+            (1.0, Some(70.0)),
+            (1.0, Some(85.0)),
+            (0.6, Some(95.0)),
+            (0.6, Some(97.0)),
+        ]);
+        assert!((thermal_efficiency(&throttled).unwrap() - 0.8).abs() < 1e-9);
+
+        // Without a temperature sensor only a sustained drop below the base
+        // clock (half the maximum here) counts
+        let unknown = samples(&[(0.9, None), (0.45, None), (0.45, None), (0.45, None)]);
+        assert!((thermal_efficiency(&unknown).unwrap() - 0.625).abs() < 1e-9);
+        let dip = samples(&[(0.9, None), (0.45, None), (0.45, None), (0.9, None)]);
+        assert_eq!(thermal_efficiency(&dip), Some(1.0));
+        let boost_ended = samples(&[(1.0, None), (0.6, None), (0.6, None), (0.6, None)]);
+        assert_eq!(thermal_efficiency(&boost_ended), Some(1.0));
+        let no_base: Vec<_> = unknown
+            .iter()
+            .map(|s| ThermalSample {
+                base_ratio: None,
+                ..*s
+            })
+            .collect();
+        assert_eq!(thermal_efficiency(&no_base), Some(1.0));
+
+        let steady = samples(&[(0.95, Some(90.0)), (0.95, Some(92.0))]);
+        assert_eq!(thermal_efficiency(&steady), Some(1.0));
+
+        // A cool CPU changing clocks is power management, not throttling
+        let cool = samples(&[(1.0, Some(50.0)), (0.4, Some(55.0))]);
+        assert_eq!(thermal_efficiency(&cool), Some(1.0));
+
+        assert_eq!(thermal_efficiency(&samples(&[(1.0, Some(99.0))])), None);
+        assert_eq!(
+            thermal_efficiency(&samples(&[(0.0, None), (0.0, None)])),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_fastest_core_and_hottest_zone_from_sysfs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sys = tmp.path();
+        let write = |path: &str, value: &str| {
+            let path = sys.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        assert_eq!(sample_sysfs(sys), None);
+
+        for (cpu, current) in [("cpu0", "1200000\n"), ("cpu1", "3000000\n")] {
+            let cpufreq = format!("devices/system/cpu/{cpu}/cpufreq");
+            write(&format!("{cpufreq}/scaling_cur_freq"), current);
+            write(&format!("{cpufreq}/cpuinfo_max_freq"), "4000000\n");
+        }
+        write("devices/system/cpu/online", "0-1\n");
+        let sample = sample_sysfs(sys).unwrap();
+        assert_eq!(sample.frequency_ratio, 0.75);
+        assert_eq!(sample.base_ratio, None);
+        assert_eq!(sample.celsius, None);
+
+        write(
+            "devices/system/cpu/cpu1/cpufreq/base_frequency",
+            "2000000\n",
+        );
+        assert_eq!(sample_sysfs(sys).unwrap().base_ratio, Some(0.5));
+
+        write("class/thermal/thermal_zone0/temp", "45000\n");
+        write("class/thermal/thermal_zone1/temp", "87500\n");
+        assert_eq!(sample_sysfs(sys).unwrap().celsius, Some(87.5));
+    }
+
+    #[tokio::test]
+    async fn sampling_returns_the_workload_output() {
+        let (samples, output) = sample_during(async { 42 }, Duration::from_millis(1)).await;
+        assert_eq!(output, 42);
+        assert!(samples.len() <= 1);
+    }
+}
@@ -0,0 +1,93 @@
+//! Windows energy monitoring via PDH power meter counters.
+//!
+//! Windows publishes power telemetry from ACPI power meters and the Energy
+//! Meter Interface (EMI), which also feeds the Energy Estimation Engine, as
+//! the `\Power Meter(_Total)\Power` performance counter in milliwatts. It is
+//! sampled with the bundled `typeperf` tool, so no FFI (and no unsafe code)
+//! is needed. Desktops and servers without power telemetry do not expose
+//! the counter; [`WindowsMonitor::detect`] then reports
+//! [`CrabScoreError::UnsupportedError`] so callers can fall back to
+//! [`NullMonitor`](crate::interface::NullMonitor).
+
+use anyhow::Result;
+use crabscore_core::error::CrabScoreError;
+use crabscore_core::metrics::{EnergyMetrics, PowerConsumption};
+use tokio::process::Command;
+
+use crate::interface::EnergyMonitor;
+
+/// PDH counter with the total measured power draw in milliwatts
+const POWER_COUNTER: &str = r"\Power Meter(_Total)\Power";
+
+/// Energy monitor reading the Windows power meter counters.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowsMonitor;
+
+impl WindowsMonitor {
+    /// Probe the power meter counter, failing with
+    /// [`CrabScoreError::UnsupportedError`] when the machine has no power
+    /// telemetry.
+    pub async fn detect() -> Result<Self> {
+        read_power_watts().await?;
+        Ok(Self)
+    }
+}
+
+#[async_trait::async_trait]
+impl EnergyMonitor for WindowsMonitor {
+    async fn collect(&self) -> Result<EnergyMetrics> {
+        let watts = read_power_watts().await?;
+        Ok(EnergyMetrics {
+            direct_consumption: PowerConsumption {
+                average_watts: watts,
+                peak_watts: watts,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+}
+
+/// Take one sample of the power counter, in watts.
+async fn read_power_watts() -> Result<f64> {
+    let output = Command::new("typeperf")
+        .args([POWER_COUNTER, "-sc", "1"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| CrabScoreError::unsupported(format!("typeperf unavailable: {e}")))?;
+    let milliwatts = output
+        .status
+        .success()
+        .then(|| parse_typeperf(&String::from_utf8_lossy(&output.stdout)))
+        .flatten()
+        .ok_or_else(|| CrabScoreError::unsupported("no power meter telemetry on this machine"))?;
+    Ok(milliwatts / 1000.0)
+}
+
+/// Sum of the counter values in the last sample row of `typeperf` CSV output.
+fn parse_typeperf(csv: &str) -> Option<f64> {
+    csv.lines()
+        .filter(|line| line.starts_with('"') && !line.contains("(PDH-CSV"))
+        .filter_map(|line| {
+            line.split(',')
+                .skip(1)
+                .map(|field| field.trim().trim_matches('"').parse::<f64>().ok())
+                .sum::<Option<f64>>()
+        })
+        .next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_typeperf_samples() {
+        let csv = "\r\n\"(PDH-CSV 4.0)\",\"\\\\HOST\\Power Meter(_Total)\\Power\"\r\n\
+                   \"10/15/2026 10:00:00.123\",\"12500.000000\"\r\n\
+                   Exiting, please wait...\r\nThe command completed successfully.\r\n";
+        assert_eq!(parse_typeperf(csv), Some(12500.0));
+        assert_eq!(parse_typeperf("Error: No valid counters.\r\n"), None);
+    }
+}
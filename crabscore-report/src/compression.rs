@@ -0,0 +1,153 @@
+//! Gzip and Brotli compression for written reports and `/data.json`.
+
+use std::io::{Read, Write};
+
+/// Compression applied to a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Gzip (`.gz`, `Content-Encoding: gzip`)
+    Gzip,
+    /// Brotli (`.br`, `Content-Encoding: br`)
+    Brotli,
+}
+
+/// Brotli quality (0–11); 9 keeps large reports fast to write
+const BROTLI_QUALITY: u32 = 9;
+
+/// Brotli window size as a power of two
+const BROTLI_WINDOW: u32 = 22;
+
+impl Compression {
+    /// File extension appended to the report's name
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Brotli => "br",
+        }
+    }
+
+    /// `Content-Encoding` token
+    pub fn encoding(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "br",
+        }
+    }
+
+    /// Compress `data`
+    pub fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Brotli => {
+                let mut out = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(
+                        &mut out,
+                        4096,
+                        BROTLI_QUALITY,
+                        BROTLI_WINDOW,
+                    );
+                    encoder.write_all(data)?;
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decompress data written by [`Self::compress`]
+    pub fn decompress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        match self {
+            Self::Gzip => flate2::read::GzDecoder::new(data).read_to_end(&mut out)?,
+            Self::Brotli => brotli::Decompressor::new(data, 4096).read_to_end(&mut out)?,
+        };
+        Ok(out)
+    }
+
+    /// Compression with the highest quality value in an `Accept-Encoding`
+    /// header, Brotli on a tie; `None` means the response is sent
+    /// uncompressed.
+    ///
+    /// An encoding named in the header takes its own quality value, so
+    /// `br;q=0` refuses Brotli even when `*` allows everything else.
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let entries: Vec<(&str, f64)> = accept_encoding
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';').map(str::trim);
+                let name = parts.next().filter(|name| !name.is_empty())?;
+                let q = parts
+                    .find_map(|p| p.strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f64>().ok())?;
+                Some((name, q))
+            })
+            .collect();
+        let quality = |token: &str| {
+            let find = |matches: &dyn Fn(&str) -> bool| {
+                entries.iter().find(|(name, _)| matches(name)).map(|e| e.1)
+            };
+            find(&|name| name.eq_ignore_ascii_case(token)).or_else(|| find(&|name| name == "*"))
+        };
+        let mut best: Option<(Self, f64)> = None;
+        for compression in [Self::Brotli, Self::Gzip] {
+            match quality(compression.encoding()) {
+                Some(q) if q > 0.0 && best.is_none_or(|(_, best)| q > best) => {
+                    best = Some((compression, q))
+                }
+                _ => {}
+            }
+        }
+        best.map(|(compression, _)| compression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::generate_json;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    #[test]
+    fn compressed_report_round_trips() {
+        let score = default_score(IndustryProfile::default());
+        let json = generate_json(&score).to_pretty_string();
+        for compression in [Compression::Gzip, Compression::Brotli] {
+            let compressed = compression.compress(json.as_bytes()).unwrap();
+            assert!(compressed.len() < json.len(), "{compression:?}");
+            let restored = compression.decompress(&compressed).unwrap();
+            assert_eq!(String::from_utf8(restored).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn negotiates_accept_encoding() {
+        assert_eq!(
+            Compression::negotiate("gzip, deflate, br"),
+            Some(Compression::Brotli)
+        );
+        assert_eq!(
+            Compression::negotiate("gzip;q=1.0, br;q=0"),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::negotiate("identity"), None);
+        assert_eq!(Compression::negotiate(""), None);
+        // An explicit exclusion wins over the wildcard
+        assert_eq!(Compression::negotiate("*, br;q=0"), Some(Compression::Gzip));
+        assert_eq!(Compression::negotiate("br;q=0, gzip;q=0, *"), None);
+        // Higher quality values are preferred
+        assert_eq!(
+            Compression::negotiate("br;q=0.5, gzip;q=0.8"),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::negotiate("gzip, *;q=0.5"),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::negotiate("*"), Some(Compression::Brotli));
+    }
+}
@@ -1,6 +1,7 @@
 //! Compliance & reporting export formats.
 
-use crabscore_core::CrabScore;
+use crabscore_core::{scoring::Thresholds, CrabScore};
+use html_escape::encode_double_quoted_attribute as escape_attr;
 use serde_json::json;
 
 /// Export to CSRD-compatible JSON
@@ -35,3 +36,178 @@ pub fn export_cra(score: &CrabScore) -> String {
     }))
     .unwrap()
 }
+
+/// Export the score as a YAML document, e.g. for diffing scores in pull
+/// requests.
+///
+/// Field names and values match the JSON report: the timestamp is an RFC 3339
+/// string and enums are their variant names.
+pub fn export_yaml(score: &CrabScore) -> anyhow::Result<String> {
+    Ok(serde_yaml::to_string(score)?)
+}
+
+/// Export to SARIF 2.1.0 so code scanning dashboards pick up the run.
+///
+/// The scores go into the run's `properties`; every warning in the score
+/// metadata (failed builds, estimated metrics, ...) becomes a `warning`
+/// result of the `crabscore/degraded` rule.
+pub fn export_sarif(score: &CrabScore) -> String {
+    let results: Vec<_> = score
+        .metadata
+        .warnings
+        .iter()
+        .map(|warning| {
+            json!({
+                "ruleId": "crabscore/degraded",
+                "level": "warning",
+                "message": { "text": warning },
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "crabscore",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [{
+                        "id": "crabscore/degraded",
+                        "shortDescription": {
+                            "text": "A metric was estimated or defaulted instead of measured",
+                        },
+                    }],
+                },
+            },
+            "results": results,
+            "properties": {
+                "overall": score.overall,
+                "performance": score.performance,
+                "energy": score.energy,
+                "cost": score.cost,
+                "certification": format!("{:?}", score.certification),
+            },
+        }],
+    }))
+    .unwrap()
+}
+
+/// Export to JUnit XML so CI systems render each dimension as a test case.
+///
+/// Every dimension (performance, energy, cost, overall) becomes a
+/// `<testcase>`; a `<failure>` is attached when it falls below the
+/// corresponding threshold.
+pub fn export_junit(score: &CrabScore, thresholds: &Thresholds) -> String {
+    let checks = thresholds.checks(score);
+    let failures = thresholds.failures(score);
+    let suite = if score.metadata.project_name.is_empty() {
+        "crabscore".to_string()
+    } else {
+        format!("crabscore.{}", score.metadata.project_name)
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" timestamp=\"{}\">\n",
+        escape_attr(&suite),
+        checks.len(),
+        failures.len(),
+        score.timestamp.format("%Y-%m-%dT%H:%M:%S"),
+    ));
+    for (dimension, actual, _) in checks {
+        xml.push_str(&format!(
+            "  <testcase classname=\"{}\" name=\"{dimension}\">\n",
+            escape_attr(&suite)
+        ));
+        if let Some(f) = failures.iter().find(|f| f.dimension == dimension) {
+            xml.push_str(&format!(
+                "    <failure type=\"threshold\" message=\"{dimension} score {:.1} is below threshold {:.1}\"/>\n",
+                f.actual, f.minimum
+            ));
+        }
+        xml.push_str(&format!(
+            "    <system-out>{dimension}: {actual:.1}/100</system-out>\n"
+        ));
+        xml.push_str("  </testcase>\n");
+    }
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    #[test]
+    fn junit_parses_and_counts_failures() {
+        let mut score = default_score(IndustryProfile::default());
+        score.metadata.project_name = "demo <&>".to_string();
+        score.performance = 40.0;
+        score.energy = 80.0;
+        score.cost = 60.0;
+        score.overall = 65.0;
+        let thresholds = Thresholds {
+            overall: Some(70.0),
+            performance: Some(50.0),
+            energy: Some(50.0),
+            cost: None,
+        };
+
+        let xml = export_junit(&score, &thresholds);
+        let doc = roxmltree::Document::parse(&xml).expect("valid XML");
+        let suite = doc.root_element();
+        assert_eq!(suite.attribute("tests"), Some("4"));
+        assert_eq!(suite.attribute("failures"), Some("2"));
+
+        let failed: Vec<_> = suite
+            .children()
+            .filter(|n| n.has_tag_name("testcase"))
+            .filter(|n| n.children().any(|c| c.has_tag_name("failure")))
+            .map(|n| n.attribute("name").unwrap())
+            .collect();
+        assert_eq!(failed, ["performance", "overall"]);
+    }
+
+    #[test]
+    fn yaml_round_trips() {
+        let mut score = default_score(IndustryProfile::Financial);
+        score.metadata.project_name = "demo: yaml".to_string();
+        score.metadata.warnings = vec!["Cost file not found".to_string()];
+        score.metadata.diff_base = Some("main".to_string());
+
+        let yaml = export_yaml(&score).unwrap();
+        assert!(yaml.contains("profile: Financial"), "{yaml}");
+        assert!(yaml.contains(&format!("certification: {:?}", score.certification)));
+        let timestamp = score
+            .timestamp
+            .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+        assert!(
+            yaml.lines()
+                .any(|l| l.starts_with("timestamp: ") && l.contains(&timestamp)),
+            "{yaml}"
+        );
+        let parsed: CrabScore = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, score);
+    }
+
+    #[test]
+    fn sarif_lists_each_warning_as_a_result() {
+        let mut score = default_score(IndustryProfile::default());
+        score.metadata.warnings = vec!["Build failed".into(), "Default costs".into()];
+
+        let sarif: serde_json::Value = serde_json::from_str(&export_sarif(&score)).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "crabscore");
+        let messages: Vec<_> = run["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["message"]["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(messages, ["Build failed", "Default costs"]);
+        assert_eq!(run["properties"]["overall"], score.overall);
+    }
+}
@@ -1,41 +1,458 @@
-//! CrabScore report generator – JSON + minimal HTML
+//! CrabScore report generator – JSON + HTML dashboard
 
-use crabscore_core::CrabScore;
+use chrono::SecondsFormat;
+use crabscore_core::{CertificationBands, CrabScore};
+use html_escape::encode_text;
 use serde::{Deserialize, Serialize};
 
 /// Wrapper type returned by `/data.json` or `generate_json` helpers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct JsonReport {
     /// The computed CrabScore values for this report.
     pub score: CrabScore,
+    /// Letter grade (A–F) of the overall score.
+    #[serde(default = "ungraded")]
+    pub grade: char,
+    /// Custom certification tier earned, when the project defines tiers
+    /// replacing the built-in certification levels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certification_tier: Option<String>,
+}
+
+fn ungraded() -> char {
+    '?'
 }
 
 impl JsonReport {
+    /// Name the certification after custom `bands`, when any are defined,
+    /// from the overall score as reported.
+    pub fn with_certification_bands(mut self, bands: &CertificationBands) -> Self {
+        self.certification_tier =
+            (!bands.0.is_empty()).then(|| self.score.certification_with(bands));
+        self
+    }
+
     /// Pretty-print the JSON for human consumption.
     pub fn to_pretty_string(&self) -> String {
         serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
     }
 }
 
-/// Package the score into the JSON wrapper so Serde can serialise it.
+/// Decimal places of the scores and bonuses in a JSON report
+pub const SCORE_DECIMALS: i32 = 2;
+
+/// Decimal places of latencies (milliseconds) in a JSON report, i.e.
+/// microsecond precision
+pub const LATENCY_DECIMALS: i32 = 3;
+
+/// Package the score into the JSON wrapper so Serde can serialise it, with
+/// floats rounded by [`round_for_report`]. The grade is taken from the
+/// rounded overall score, so it always matches the printed one.
 pub fn generate_json(score: &CrabScore) -> JsonReport {
+    let score = round_for_report(score);
+    JsonReport {
+        grade: score.grade(),
+        score,
+        certification_tier: None,
+    }
+}
+
+/// Like [`generate_json`], but with every float at full precision.
+pub fn generate_json_raw(score: &CrabScore) -> JsonReport {
     JsonReport {
         score: score.clone(),
+        grade: score.grade(),
+        certification_tier: None,
+    }
+}
+
+/// `score` with its floats rounded to the precision worth reporting:
+/// [`SCORE_DECIMALS`] for the scores, bonuses and estimate checks,
+/// [`LATENCY_DECIMALS`] for per-target latencies, and two decimals for memory
+/// and the calibration speed factor.
+pub fn round_for_report(score: &CrabScore) -> CrabScore {
+    let round = |value: f64, decimals: i32| {
+        let scale = 10f64.powi(decimals);
+        (value * scale).round() / scale
+    };
+    let mut score = score.clone();
+    for value in [
+        &mut score.overall,
+        &mut score.performance,
+        &mut score.energy,
+        &mut score.cost,
+        &mut score.bonuses,
+    ] {
+        *value = round(*value, SCORE_DECIMALS);
+    }
+    let measurements = &mut score.metadata.measurements;
+    for target in &mut measurements.targets {
+        let latency = &mut target.latency;
+        for value in [
+            &mut latency.p50_ms,
+            &mut latency.p95_ms,
+            &mut latency.p99_ms,
+            &mut latency.p999_ms,
+            &mut latency.stddev_ms,
+            &mut latency.cold_start_ms,
+            &mut latency.ttfb_ms,
+        ] {
+            *value = round(*value, LATENCY_DECIMALS);
+        }
+    }
+    let environment = &mut measurements.environment;
+    environment.memory_gb = round(f64::from(environment.memory_gb), 2) as f32;
+    environment.speed_factor = environment.speed_factor.map(|f| round(f, 2));
+    if let Some(calibration) = &mut score.metadata.calibration {
+        for dimension in [
+            &mut calibration.performance,
+            &mut calibration.energy,
+            &mut calibration.cost,
+        ] {
+            for value in [
+                &mut dimension.estimated,
+                &mut dimension.measured,
+                &mut dimension.error,
+            ] {
+                *value = round(*value, SCORE_DECIMALS);
+            }
+        }
     }
+    score
 }
 
-/// Produce a very small, self-contained HTML page that simply displays the
-/// prettified JSON report in a `<pre>` block.  This is only needed by the CLI
-/// when the user asks for an HTML file instead of JSON.
+/// Serialize the score as a TOML document.
+///
+/// The timestamp is written as a native TOML datetime rather than a string,
+/// so tools reading the file get a typed value.
+pub fn generate_toml(score: &CrabScore) -> anyhow::Result<String> {
+    generate_toml_with(score, &CertificationBands::default())
+}
+
+/// Like [`generate_toml`], adding the custom certification tier earned as
+/// `certification_tier` when `bands` defines any.
+pub fn generate_toml_with(score: &CrabScore, bands: &CertificationBands) -> anyhow::Result<String> {
+    let mut value = toml::Value::try_from(score)?;
+    if let Some(table) = value.as_table_mut() {
+        let timestamp = score.timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        table.insert(
+            "timestamp".to_string(),
+            toml::Value::Datetime(timestamp.parse()?),
+        );
+        if !bands.0.is_empty() {
+            table.insert(
+                "certification_tier".to_string(),
+                toml::Value::String(score.certification_with(bands)),
+            );
+        }
+    }
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+/// Read a score written by [`generate_toml`], migrating older schema versions.
+pub fn parse_toml(toml: &str) -> anyhow::Result<CrabScore> {
+    let mut value: toml::Value = toml::from_str(toml)?;
+    // chrono only deserializes timestamps from strings
+    if let Some(table) = value.as_table_mut() {
+        if let Some(toml::Value::Datetime(timestamp)) = table.get("timestamp") {
+            let timestamp = toml::Value::String(timestamp.to_string());
+            table.insert("timestamp".to_string(), timestamp);
+        }
+    }
+    Ok(CrabScore::from_value(serde_json::to_value(value)?)?)
+}
+
+/// JSON Schema describing the [`JsonReport`] format, for validating report files.
+#[cfg(feature = "schema")]
+pub fn json_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(JsonReport)).unwrap_or_default()
+}
+
+/// Points on the bonus bar that count as a full bar (core safety bonuses plus
+/// the CLI's capped complexity bonuses).
+const BONUS_SCALE: f64 = 20.0;
+
+/// Produce a self-contained HTML dashboard for `score`.
+///
+/// The page shows a gauge for the overall score and a bar chart of the
+/// performance, energy, cost and bonus dimensions, drawn as inline SVG so
+/// it renders offline and can be shared as a single file. The full JSON
+/// report is included in a collapsible block. The markup is well-formed XML.
 pub fn generate_html(score: &CrabScore) -> String {
-    let json_pretty =
-        serde_json::to_string_pretty(&generate_json(score)).unwrap_or_else(|_| "{}".to_string());
+    generate_html_with(score, &CertificationBands::default())
+}
+
+/// Like [`generate_html`], naming the certification after custom `bands`
+/// when any are defined.
+pub fn generate_html_with(score: &CrabScore, bands: &CertificationBands) -> String {
+    let report = generate_json(score).with_certification_bands(bands);
+    let json_pretty = serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string());
+    let meta = &score.metadata;
+    let title = if meta.project_name.is_empty() {
+        "CrabScore Report".to_string()
+    } else {
+        format!("CrabScore Report – {}", meta.project_name)
+    };
+
+    let bars = [
+        ("Performance", score.performance, 100.0, "#ff5522"),
+        ("Energy", score.energy, 100.0, "#00c8b0"),
+        ("Cost", score.cost, 100.0, "#ffb300"),
+        ("Bonuses", score.bonuses, BONUS_SCALE, "#b4ff00"),
+    ];
+    let warnings: String = meta
+        .warnings
+        .iter()
+        .map(|w| format!("<li>{}</li>", encode_text(w)))
+        .collect();
+    let warnings = if warnings.is_empty() {
+        String::new()
+    } else {
+        format!("<h2>Warnings</h2><ul class=\"warnings\">{warnings}</ul>")
+    };
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\"/>\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"/>\
+         <title>{title}</title><style>{STYLE}</style></head><body><main>\
+         <h1>CRABSCORE REPORT</h1><p class=\"sub\">{project}{profile:?} profile · \
+         {confidence:?} · {timestamp}</p>\
+         <div class=\"charts\">{gauge}{bars}</div>{warnings}\
+         <details><summary>Raw JSON</summary><pre>{json}</pre></details>\
+         </main></body></html>\n",
+        title = encode_text(&title),
+        project = if meta.project_name.is_empty() {
+            String::new()
+        } else {
+            format!("{} · ", encode_text(&meta.project_name))
+        },
+        profile = meta.profile,
+        confidence = meta.confidence,
+        timestamp = score.timestamp.format("%Y-%m-%d %H:%M UTC"),
+        gauge = gauge_svg(score.overall, &score.certification_with(bands)),
+        bars = bar_chart_svg(&bars),
+        json = encode_text(&json_pretty),
+    )
+}
+
+const STYLE: &str = "body{margin:0;background:#18191c;color:#f7f7f7;\
+    font-family:'JetBrains Mono',monospace}main{max-width:960px;margin:0 auto;padding:2rem 1rem}\
+    h1{color:#ff5522;text-align:center;margin-bottom:0.25rem}h2{font-size:1.1rem}\
+    .sub{text-align:center;color:#bbb}\
+    .charts{display:flex;flex-wrap:wrap;gap:1.5rem;justify-content:center;margin:2rem 0}\
+    svg{background:#232428;border-radius:0.75rem}svg text{fill:#f7f7f7;font-family:inherit}\
+    .warnings{color:#ffb300}details{margin-top:2rem}\
+    pre{background:#111;color:#aaa;padding:1rem;border-radius:0.5rem;overflow-x:auto}";
+
+/// Semicircular gauge for the overall score (clamped to 0–100).
+fn gauge_svg(overall: f64, certification: &str) -> String {
+    let fill = overall.clamp(0.0, 100.0);
+    // The arc's pathLength is 100, so the dash length is the score itself.
+    let arc = "M 30 140 A 100 100 0 0 1 230 140";
+    format!(
+        "<svg class=\"gauge\" width=\"260\" height=\"200\" viewBox=\"0 0 260 200\" \
+         role=\"img\" aria-label=\"Overall score {overall:.0} of 100\">\
+         <path d=\"{arc}\" pathLength=\"100\" fill=\"none\" stroke=\"#333\" \
+         stroke-width=\"20\" stroke-linecap=\"round\"/>\
+         <path d=\"{arc}\" pathLength=\"100\" fill=\"none\" stroke=\"#ff5522\" \
+         stroke-width=\"20\" stroke-linecap=\"round\" stroke-dasharray=\"{fill:.1} 100\"/>\
+         <text x=\"130\" y=\"130\" text-anchor=\"middle\" font-size=\"40\" \
+         font-weight=\"700\">{overall:.1}</text>\
+         <text x=\"130\" y=\"175\" text-anchor=\"middle\" font-size=\"16\" \
+         fill=\"#bbb\">{}</text></svg>",
+        encode_text(certification),
+    )
+}
 
+/// Horizontal bar chart of `(label, value, full scale, colour)` rows.
+fn bar_chart_svg(bars: &[(&str, f64, f64, &str)]) -> String {
+    const ROW: f64 = 40.0;
+    const WIDTH: f64 = 260.0;
+    let height = ROW * bars.len() as f64 + 20.0;
+    let rows: String = bars
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value, scale, colour))| {
+            let y = 20.0 + ROW * i as f64;
+            let width = (value / scale).clamp(0.0, 1.0) * WIDTH;
+            let shown = if *label == "Bonuses" {
+                format!("+{value:.1}")
+            } else {
+                format!("{value:.1}")
+            };
+            format!(
+                "<text x=\"16\" y=\"{ty}\" font-size=\"14\">{label}</text>\
+                 <rect x=\"120\" y=\"{y}\" width=\"{WIDTH}\" height=\"16\" rx=\"4\" fill=\"#333\"/>\
+                 <rect class=\"bar\" x=\"120\" y=\"{y}\" width=\"{width:.1}\" height=\"16\" \
+                 rx=\"4\" fill=\"{colour}\"/>\
+                 <text x=\"{tx}\" y=\"{ty}\" font-size=\"14\">{shown}</text>",
+                ty = y + 13.0,
+                tx = 120.0 + WIDTH + 10.0,
+            )
+        })
+        .collect();
     format!(
-        "<!DOCTYPE html><html><head><meta charset='utf-8'><title>CrabScore Report</title>\
-         <style>body{{background:#18191c;color:#f7f7f7;font-family:'JetBrains Mono',monospace;\
-         padding:2rem}}</style></head><body><h1 style='color:#ff5522;text-align:center'>\
-         CRABSCORE REPORT</h1><pre style='background:#111;color:#aaa;padding:1rem;border-radius:0.5rem;\
-         overflow-x:auto'>{json_pretty}</pre></body></html>"
+        "<svg class=\"bars\" width=\"460\" height=\"{height}\" viewBox=\"0 0 460 {height}\" \
+         role=\"img\" aria-label=\"Score by dimension\">{rows}</svg>"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crabscore_core::{testing::default_score, IndustryProfile};
+
+    #[test]
+    fn html_report_charts_scores_in_valid_markup() {
+        let mut score = default_score(IndustryProfile::default());
+        score.performance = 72.5;
+        score.energy = 64.0;
+        score.cost = 91.25;
+        score.bonuses = 5.0;
+        score.overall = 78.4;
+        score.metadata.project_name = "demo <&>".to_string();
+        score.metadata.warnings = vec!["Cost file a&b.json not found".to_string()];
+
+        let html = generate_html(&score);
+        for value in ["72.5", "64.0", "91.2", "+5.0", "78.4"] {
+            assert!(html.contains(value), "missing {value}");
+        }
+        assert!(!html.contains("http://") && !html.contains("https://"));
+
+        let opts = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let doc = roxmltree::Document::parse_with_options(&html, opts).expect("well-formed markup");
+        let bars: Vec<_> = doc
+            .descendants()
+            .filter(|n| n.attribute("class") == Some("bar"))
+            .map(|n| n.attribute("width").unwrap())
+            .collect();
+        assert_eq!(bars, ["188.5", "166.4", "237.2", "65.0"]);
+        assert!(doc
+            .descendants()
+            .any(|n| n.text() == Some("Cost file a&b.json not found")));
+        assert!(
+            doc.descendants()
+                .any(|n| n.has_tag_name("path")
+                    && n.attribute("stroke-dasharray") == Some("78.4 100"))
+        );
+    }
+
+    #[test]
+    fn toml_report_round_trips() {
+        let mut score = default_score(IndustryProfile::default());
+        score.metadata.project_name = "demo".to_string();
+        score.metadata.warnings = vec!["Cost file not found".to_string()];
+        score.metadata.diff_base = Some("main".to_string());
+
+        let toml = generate_toml(&score).unwrap();
+        assert!(
+            toml.lines().any(|l| l.starts_with("timestamp = 20")),
+            "{toml}"
+        );
+        assert_eq!(parse_toml(&toml).unwrap(), score);
+    }
+
+    #[test]
+    fn reports_show_custom_certification_tiers() {
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = 82.0;
+        let bands: CertificationBands = serde_json::from_str(
+            r#"[{"name": "Bronze", "min_overall": 60}, {"name": "Silver", "min_overall": 80}]"#,
+        )
+        .unwrap();
+
+        let report = generate_json(&score).with_certification_bands(&bands);
+        assert_eq!(report.certification_tier.as_deref(), Some("Silver"));
+        let plain = serde_json::to_value(generate_json(&score)).unwrap();
+        assert!(plain.get("certification_tier").is_none());
+
+        let toml = generate_toml_with(&score, &bands).unwrap();
+        assert!(toml.contains("certification_tier = \"Silver\""), "{toml}");
+        assert_eq!(parse_toml(&toml).unwrap(), score);
+        assert!(!generate_toml(&score)
+            .unwrap()
+            .contains("certification_tier"));
+
+        let html = generate_html_with(&score, &bands);
+        assert!(html.contains(">Silver<"), "{html}");
+        assert!(!generate_html(&score).contains("Silver"));
+    }
+
+    #[test]
+    fn json_report_rounds_floats_unless_raw() {
+        let mut score = default_score(IndustryProfile::default());
+        score.overall = 87.333_333_333;
+        score.bonuses = 2.0 / 3.0;
+        score.metadata.measurements.environment.memory_gb = 15.526_34;
+        let latency = crabscore_core::metrics::LatencyMetrics {
+            p50_ms: 1.234_567_89,
+            stddev_ms: 0.000_123_4,
+            ..Default::default()
+        };
+        score
+            .metadata
+            .measurements
+            .targets
+            .push(crabscore_core::TargetMeasurement {
+                name: "app".into(),
+                latency,
+                binary_size_bytes: None,
+            });
+
+        fn max_decimals(value: &serde_json::Value) -> usize {
+            match value {
+                serde_json::Value::Number(n) => {
+                    n.to_string().split_once('.').map_or(0, |(_, d)| d.len())
+                }
+                serde_json::Value::Array(items) => {
+                    items.iter().map(max_decimals).max().unwrap_or(0)
+                }
+                serde_json::Value::Object(fields) => {
+                    fields.values().map(max_decimals).max().unwrap_or(0)
+                }
+                _ => 0,
+            }
+        }
+        // Parse the written text: `memory_gb` is an `f32`, which only prints
+        // short once serialized as one
+        let report: serde_json::Value =
+            serde_json::from_str(&generate_json(&score).to_pretty_string()).unwrap();
+        assert!(max_decimals(&report) <= 3, "{report:#}");
+        assert_eq!(report["score"]["overall"], 87.33);
+        assert_eq!(report["score"]["bonuses"], 0.67);
+        let target = &report["score"]["metadata"]["measurements"]["targets"][0];
+        assert_eq!(target["latency"]["p50_ms"], 1.235);
+        assert_eq!(target["latency"]["stddev_ms"], 0.0);
+
+        let raw = serde_json::to_value(generate_json_raw(&score)).unwrap();
+        assert_eq!(raw["score"]["overall"], 87.333_333_333);
+
+        // The grade and tier follow the printed score, not the hidden decimals
+        score.overall = 89.996;
+        let bands: CertificationBands =
+            serde_json::from_str(r#"[{"name": "Gold", "min_overall": 90}]"#).unwrap();
+        let report = generate_json(&score).with_certification_bands(&bands);
+        assert_eq!(report.score.overall, 90.0);
+        assert_eq!(report.grade, 'A');
+        assert_eq!(report.certification_tier.as_deref(), Some("Gold"));
+        let raw = generate_json_raw(&score).with_certification_bands(&bands);
+        assert_eq!(raw.grade, 'B');
+        assert_ne!(raw.certification_tier.as_deref(), Some("Gold"));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn schema_validates_generated_report() {
+        let score = default_score(IndustryProfile::default());
+        let report = serde_json::to_value(generate_json(&score)).unwrap();
+
+        let schema = json_schema();
+        let validator = jsonschema::JSONSchema::compile(&schema).expect("valid schema");
+        assert!(validator.is_valid(&report));
+        assert!(!validator.is_valid(&serde_json::json!({ "score": { "overall": "high" } })));
+    }
+}
@@ -6,6 +6,9 @@
 /// Report generation
 pub mod generator;
 
+/// Gzip and Brotli compression of reports
+pub mod compression;
+
 /// Web dashboard
 pub mod web;
 
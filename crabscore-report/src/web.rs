@@ -1,46 +1,398 @@
 //! Lightweight Axum dashboard for CrabScore reports.
+//!
+//! Besides the precomputed report, `POST /score` scores metrics sent as JSON
+//! (the `performance`, `energy`, `cost` and `safety` sections of a report,
+//! plus an optional `profile`) and answers with the resulting `CrabScore`.
+//!
+//! `/search` and `/query` follow Grafana's JSON datasource contract
+//! (SimpleJSON, or Infinity in JSON mode) so dashboards can chart the score
+//! history:
+//!
+//! - `POST /search` answers with the metric names: `["overall",
+//!   "performance", "energy", "cost", "bonuses"]`.
+//! - `POST /query` takes `{"range": {"from": ..., "to": ...}, "targets":
+//!   [{"target": "overall"}, ...]}` (RFC 3339 times; `range` is optional) and
+//!   answers with one `{"target": ..., "datapoints": [[value, unix_ms], ...]}`
+//!   series per target, oldest point first. Unknown targets and malformed
+//!   bodies get a 400.
 
-use axum::{extract::State, http::StatusCode, response::Redirect, routing::get, Router};
-use serde_json::Value;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Redirect, Response},
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::{net::SocketAddr, sync::Arc};
 use tower_http::services::ServeDir;
 
-use crate::generator::generate_json;
-use crabscore_core::CrabScore;
+use crate::{compression::Compression, generator::generate_json};
+use crabscore_core::{
+    metrics::{CostMetrics, EnergyMetrics, PerformanceMetrics, SafetyMetrics},
+    scoring::ScoringEngine,
+    CertificationBands, CrabScore, IndustryProfile,
+};
+
+/// Score dimensions charted through `/search` and `/query`
+const DIMENSIONS: [&str; 5] = ["overall", "performance", "energy", "cost", "bonuses"];
 
 #[derive(Clone)]
 struct AppState {
     score: Arc<CrabScore>,
+    /// Custom certification tiers named in `/data.json`
+    certifications: Arc<CertificationBands>,
+    /// Earlier scores of the project followed by `score`, oldest first
+    series: Arc<Vec<CrabScore>>,
 }
 
 /// Start a blocking web server on the given address.
-pub async fn serve(score: CrabScore, addr: SocketAddr) -> anyhow::Result<()> {
+///
+/// `history` holds earlier scores of the same project, oldest first; with
+/// `score` as the latest point they make up the time series of `/query`.
+/// The certification is named after `certifications` when any are defined.
+pub async fn serve(
+    score: CrabScore,
+    history: Vec<CrabScore>,
+    certifications: CertificationBands,
+    addr: SocketAddr,
+) -> anyhow::Result<()> {
+    tracing::info!("Listening on http://{}", addr);
+    axum::Server::bind(&addr)
+        .serve(app(score, history, certifications).into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// Routes of the dashboard serving `score`, and `history` plus `score` to
+/// Grafana
+fn app(
+    score: CrabScore,
+    mut history: Vec<CrabScore>,
+    certifications: CertificationBands,
+) -> Router {
+    history.push(score.clone());
     let state = AppState {
         score: Arc::new(score),
+        certifications: Arc::new(certifications),
+        series: Arc::new(history),
     };
-
-    // Routes
-    let app = Router::new()
+    Router::new()
         .route("/", get(root))
         .route("/data.json", get(data))
+        .route("/score", post(score_metrics))
+        .route("/search", post(search))
+        .route("/query", post(query))
         .with_state(state)
         .nest_service(
             "/static",
             ServeDir::new(concat!(env!("CARGO_MANIFEST_DIR"), "/static")),
-        );
+        )
+}
 
-    tracing::info!("Listening on http://{}", addr);
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await?;
-    Ok(())
+/// Body of `POST /score`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScoreRequest {
+    #[serde(default)]
+    profile: IndustryProfile,
+    performance: PerformanceMetrics,
+    energy: EnergyMetrics,
+    cost: CostMetrics,
+    safety: SafetyMetrics,
+}
+
+/// Score the metrics in the request body, or answer 400 with the parse error
+async fn score_metrics(body: Bytes) -> Result<Json<CrabScore>, (StatusCode, String)> {
+    let request: ScoreRequest = serde_json::from_slice(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid metrics: {e}")))?;
+    Ok(Json(ScoringEngine::new(request.profile).calculate_score(
+        &request.performance,
+        &request.energy,
+        &request.cost,
+        &request.safety,
+    )))
+}
+
+/// Body of `POST /query`
+#[derive(Debug, Deserialize)]
+struct QueryRequest {
+    #[serde(default)]
+    range: Option<QueryRange>,
+    #[serde(default)]
+    targets: Vec<QueryTarget>,
+}
+
+/// Time range of a Grafana panel, inclusive
+#[derive(Debug, Deserialize)]
+struct QueryRange {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryTarget {
+    target: String,
+}
+
+/// One series of a `POST /query` answer
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct TimeSeries {
+    target: String,
+    /// `[value, unix milliseconds]` pairs, oldest first
+    datapoints: Vec<(f64, i64)>,
+}
+
+/// Value of the dimension `name` of `score`
+fn dimension(score: &CrabScore, name: &str) -> Option<f64> {
+    match name {
+        "overall" => Some(score.overall),
+        "performance" => Some(score.performance),
+        "energy" => Some(score.energy),
+        "cost" => Some(score.cost),
+        "bonuses" => Some(score.bonuses),
+        _ => None,
+    }
+}
+
+/// The metric names Grafana offers for a query (the body is ignored)
+async fn search() -> Json<[&'static str; 5]> {
+    Json(DIMENSIONS)
+}
+
+/// The requested dimensions of every score within the range
+async fn query(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<Json<Vec<TimeSeries>>, (StatusCode, String)> {
+    let request: QueryRequest = serde_json::from_slice(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid query: {e}")))?;
+    let in_range = |score: &&CrabScore| {
+        request
+            .range
+            .as_ref()
+            .is_none_or(|range| (range.from..=range.to).contains(&score.timestamp))
+    };
+    let mut series = Vec::with_capacity(request.targets.len());
+    for QueryTarget { target } in request.targets {
+        if !DIMENSIONS.contains(&target.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown target `{target}`"),
+            ));
+        }
+        let datapoints = state
+            .series
+            .iter()
+            .filter(in_range)
+            .filter_map(|score| {
+                Some((
+                    dimension(score, &target)?,
+                    score.timestamp.timestamp_millis(),
+                ))
+            })
+            .collect();
+        series.push(TimeSeries { target, datapoints });
+    }
+    Ok(Json(series))
 }
 
 async fn root() -> Redirect {
     axum::response::Redirect::permanent("/static/report.html")
 }
 
-async fn data(State(state): State<AppState>) -> Result<axum::Json<Value>, StatusCode> {
-    let json = generate_json(&state.score);
-    Ok(axum::Json(serde_json::to_value(json).unwrap()))
+/// The JSON report, compressed when the client's `Accept-Encoding` allows it
+async fn data(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, StatusCode> {
+    let report = generate_json(&state.score).with_certification_bands(&state.certifications);
+    let json = serde_json::to_vec(&report).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let accept = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let response = match Compression::negotiate(accept) {
+        Some(compression) => {
+            let body = compression
+                .compress(&json)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            (
+                [
+                    (header::CONTENT_TYPE, "application/json"),
+                    (header::CONTENT_ENCODING, compression.encoding()),
+                    (header::VARY, "accept-encoding"),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        None => (
+            [
+                (header::CONTENT_TYPE, "application/json"),
+                (header::VARY, "accept-encoding"),
+            ],
+            json,
+        )
+            .into_response(),
+    };
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use crabscore_core::testing::default_score;
+    use tower::ServiceExt;
+
+    async fn post(app: Router, uri: &str, body: &str) -> (StatusCode, Bytes) {
+        let response = app
+            .oneshot(
+                Request::post(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        (
+            status,
+            hyper::body::to_bytes(response.into_body()).await.unwrap(),
+        )
+    }
+
+    async fn post_score(body: &str) -> (StatusCode, Bytes) {
+        let app = app(
+            default_score(IndustryProfile::default()),
+            Vec::new(),
+            CertificationBands::default(),
+        );
+        post(app, "/score", body).await
+    }
+
+    /// Scores of 60, 70 and 80 (the current one), a day apart from 2026-01-01
+    fn history_app() -> Router {
+        let mut scores: Vec<CrabScore> = [60.0, 70.0, 80.0]
+            .into_iter()
+            .enumerate()
+            .map(|(day, overall)| {
+                let mut score = default_score(IndustryProfile::default());
+                score.overall = overall;
+                score.timestamp = format!("2026-01-0{}T00:00:00Z", day + 1).parse().unwrap();
+                score
+            })
+            .collect();
+        let current = scores.pop().unwrap();
+        app(current, scores, CertificationBands::default())
+    }
+
+    #[tokio::test]
+    async fn grafana_search_lists_the_dimensions() {
+        let (status, body) = post(history_app(), "/search", r#"{"target": ""}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let names: Vec<String> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(names, DIMENSIONS);
+    }
+
+    #[tokio::test]
+    async fn grafana_query_returns_time_series() {
+        let day = |d: i64| 1_767_225_600_000 + d * 86_400_000;
+        let query =
+            r#"{"targets": [{"target": "overall"}, {"target": "cost", "type": "timeserie"}]}"#;
+        let (status, body) = post(history_app(), "/query", query).await;
+        assert_eq!(status, StatusCode::OK);
+        let series: Vec<TimeSeries> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].target, "overall");
+        assert_eq!(
+            series[0].datapoints,
+            [(60.0, day(0)), (70.0, day(1)), (80.0, day(2))]
+        );
+        assert_eq!(series[1].target, "cost");
+        assert_eq!(series[1].datapoints.len(), 3);
+
+        let ranged = r#"{
+            "range": {"from": "2026-01-02T00:00:00Z", "to": "2026-01-03T00:00:00Z"},
+            "targets": [{"target": "overall"}]
+        }"#;
+        let (_, body) = post(history_app(), "/query", ranged).await;
+        let series: Vec<TimeSeries> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(series[0].datapoints, [(70.0, day(1)), (80.0, day(2))]);
+
+        for bad in [r#"{"targets": [{"target": "speed"}]}"#, "[1, 2]"] {
+            let (status, _) = post(history_app(), "/query", bad).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{bad}");
+        }
+    }
+
+    #[tokio::test]
+    async fn data_json_honours_accept_encoding() {
+        let score = default_score(IndustryProfile::default());
+        let get = |accept: &'static str| {
+            let app = app(score.clone(), Vec::new(), CertificationBands::default());
+            async move {
+                let mut request = Request::get("/data.json");
+                if !accept.is_empty() {
+                    request = request.header(header::ACCEPT_ENCODING, accept);
+                }
+                let response = app
+                    .oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let encoding = response
+                    .headers()
+                    .get(header::CONTENT_ENCODING)
+                    .map(|v| v.to_str().unwrap().to_string());
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                (encoding, body)
+            }
+        };
+
+        let (encoding, plain) = get("").await;
+        assert_eq!(encoding, None);
+        let report: serde_json::Value = serde_json::from_slice(&plain).unwrap();
+        assert!(report.get("score").is_some());
+
+        for (accept, compression) in [
+            ("gzip, br", Compression::Brotli),
+            ("*, br;q=0", Compression::Gzip),
+        ] {
+            let (encoding, body) = get(accept).await;
+            assert_eq!(
+                encoding.as_deref(),
+                Some(compression.encoding()),
+                "{accept}"
+            );
+            assert_eq!(compression.decompress(&body).unwrap(), plain);
+        }
+        assert_eq!(get("br;q=0, gzip;q=0").await.0, None);
+    }
+
+    #[tokio::test]
+    async fn scores_posted_metrics() {
+        let metrics = serde_json::json!({
+            "profile": "Financial",
+            "performance": PerformanceMetrics::default(),
+            "energy": EnergyMetrics::default(),
+            "cost": CostMetrics::default(),
+            "safety": SafetyMetrics::default(),
+        });
+        let (status, body) = post_score(&metrics.to_string()).await;
+        assert_eq!(status, StatusCode::OK);
+        let score: CrabScore = serde_json::from_slice(&body).unwrap();
+        let expected = default_score(IndustryProfile::Financial);
+        assert_eq!(score.overall, expected.overall);
+        assert_eq!(score.metadata.profile, IndustryProfile::Financial);
+    }
+
+    #[tokio::test]
+    async fn malformed_bodies_are_bad_requests() {
+        for body in ["not json", "{}", r#"{"performance": 1}"#] {
+            let (status, body) = post_score(body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(String::from_utf8_lossy(&body).starts_with("invalid metrics"));
+        }
+    }
 }